@@ -1,11 +1,18 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
 use colored::Colorize;
-use tracekit_core::{detect_inefficiencies, top_expensive_messages, AnalysisResult};
-use tracekit_ingest as ingest;
-use tracekit_report::{html as html_report, json as jreport, terminal};
+use std::path::PathBuf;
+use tracekit_core::{
+    default_detectors, detect_and_explain, detect_inefficiencies, top_expensive_messages,
+    AnalysisResult, DetectorTrace, SessionKind,
+};
+use tracekit_ingest::{self as ingest, DiscoveryFilter};
+use tracekit_report::{currency::fmt_money, html as html_report, json as jreport, terminal};
 
-use super::{parse_agents, parse_datetime};
+use super::{
+    analyze_sessions, apply_pseudonyms, expand_path, parse_agents, parse_datetime, parse_session,
+    redact_results, report_file_name, run_paged, short_id, write_output, DEFAULT_CHARS_PER_TOKEN,
+};
 
 #[derive(Args)]
 pub struct AnalyzeArgs {
@@ -29,9 +36,61 @@ pub enum AnalyzeSubcommand {
         #[arg(long, default_value = "cost")]
         optimize_for: String,
 
-        /// Output format: table, json
+        /// Only analyze turns with sequence >= N
+        #[arg(long)]
+        from_turn: Option<usize>,
+
+        /// Only analyze turns with sequence <= N
+        #[arg(long)]
+        to_turn: Option<usize>,
+
+        /// Output format: table, json, html
         #[arg(long, default_value = "table")]
         format: String,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// Replace session IDs with stable aliases (S-001, ...); the mapping goes to stderr
+        #[arg(long)]
+        pseudonymize: bool,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+
+        /// With --format html, only print where the report would be written
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Keep re-analyzing the session as it grows (Ctrl-C to stop). Prints one
+        /// status line per change, so report options don't combine with it
+        #[arg(long, conflicts_with_all = [
+            "from_turn", "to_turn", "format", "redact_paths", "pseudonymize", "dry_run",
+            "explain", "include_system_prompt_size", "top_turns",
+        ])]
+        watch: bool,
+
+        /// Seconds between re-analysis in --watch mode
+        #[arg(long, default_value = "2")]
+        interval: u64,
+
+        /// In --watch mode, warn (and ring the bell) once session cost exceeds this many USD
+        #[arg(long, requires = "watch")]
+        alert_over: Option<f64>,
+
+        /// Show each detector's signal vs threshold, whether or not it fired
+        #[arg(long)]
+        explain: bool,
+
+        /// Report the size of the opening context (system prompt, tools, instructions)
+        #[arg(long)]
+        include_system_prompt_size: bool,
+
+        /// How many of the most expensive turns to list
+        #[arg(long, default_value = "10")]
+        top_turns: usize,
     },
 
     /// Analyze N most recent sessions
@@ -44,6 +103,10 @@ pub enum AnalyzeSubcommand {
         #[arg(long, default_value = "10")]
         limit: usize,
 
+        /// Skip sessions with fewer than N messages (use 0 to include everything)
+        #[arg(long, default_value_t = ingest::DEFAULT_MIN_MESSAGES)]
+        min_messages: usize,
+
         /// Only sessions after this time
         #[arg(long)]
         since: Option<String>,
@@ -51,6 +114,17 @@ pub enum AnalyzeSubcommand {
         /// Output format: table, json
         #[arg(long, default_value = "table")]
         format: String,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+        /// Show table output through $PAGER (default `less -R`) when on a terminal
+        #[arg(long)]
+        page: bool,
     },
 
     /// Find and analyze the most expensive sessions
@@ -63,6 +137,10 @@ pub enum AnalyzeSubcommand {
         #[arg(long, default_value = "10")]
         top: usize,
 
+        /// Skip sessions with fewer than N messages (use 0 to include everything)
+        #[arg(long, default_value_t = ingest::DEFAULT_MIN_MESSAGES)]
+        min_messages: usize,
+
         /// Only sessions after this time
         #[arg(long)]
         since: Option<String>,
@@ -70,10 +148,71 @@ pub enum AnalyzeSubcommand {
         /// Output format: table, json
         #[arg(long, default_value = "table")]
         format: String,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+        /// Show table output through $PAGER (default `less -R`) when on a terminal
+        #[arg(long)]
+        page: bool,
+    },
+
+    /// Analyze every session found recursively under a directory of exported traces
+    Dir {
+        /// Directory to search (any layout; files are matched by each agent's naming)
+        #[arg(value_parser = expand_path)]
+        path: PathBuf,
+
+        /// Agent filter
+        #[arg(long, default_value = "all")]
+        agent: String,
+
+        /// Skip sessions with fewer than N messages (use 0 to include everything)
+        #[arg(long, default_value_t = ingest::DEFAULT_MIN_MESSAGES)]
+        min_messages: usize,
+
+        /// Only sessions after this time
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only sessions before this time
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Filter by working directory (substring match)
+        #[arg(long)]
+        cwd: Option<String>,
+
+        /// Output format: table, json
+        #[arg(long, default_value = "table")]
+        format: String,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+        /// Show table output through $PAGER (default `less -R`) when on a terminal
+        #[arg(long)]
+        page: bool,
     },
 }
 
-fn analyze_session_by_id(session_id: &str, agent: &str, top_n: usize) -> Result<AnalysisResult> {
+fn analyze_session_by_id(
+    session_id: &str,
+    agent: &str,
+    top_n: usize,
+    estimate_tokens: Option<f64>,
+    turns: (Option<usize>, Option<usize>),
+    explain: bool,
+    system_prompt_size: bool,
+) -> Result<(AnalysisResult, Vec<DetectorTrace>)> {
     let agents = parse_agents(agent)?;
     let session = ingest::find_session(session_id, &agents)?
         .ok_or_else(|| anyhow::anyhow!("No session found matching '{}'", session_id))?;
@@ -81,17 +220,100 @@ fn analyze_session_by_id(session_id: &str, agent: &str, top_n: usize) -> Result<
     eprintln!(
         "{} Parsing session {}...",
         "→".cyan(),
-        &session.session_id[..8.min(session.session_id.len())]
+        short_id(&session.session_id)
     );
-    let parsed = ingest::parse_session(&session)?;
-    let findings = detect_inefficiencies(&parsed);
+    let mut parsed = parse_session(&session, estimate_tokens)?;
+    // Measured before slicing: it's about the session's first request
+    if system_prompt_size {
+        parsed.session.system_prompt =
+            parsed.system_prompt_size(estimate_tokens.unwrap_or(DEFAULT_CHARS_PER_TOKEN));
+    }
+    parsed.slice_turns(turns.0, turns.1);
+    let (findings, traces) = if explain {
+        detect_and_explain(&parsed, &default_detectors())
+    } else {
+        (detect_inefficiencies(&parsed), Vec::new())
+    };
     let top_expensive = top_expensive_messages(&parsed, top_n);
 
-    Ok(AnalysisResult {
-        session: parsed.session,
-        findings,
-        top_expensive_messages: top_expensive,
-    })
+    Ok((
+        AnalysisResult {
+            session_kind: SessionKind::of(&parsed),
+            session: parsed.session,
+            findings,
+            top_expensive_messages: top_expensive,
+        },
+        traces,
+    ))
+}
+
+/// Re-parse a live session every `interval` seconds, printing a status line
+/// whenever it changes and alerting once when cost crosses `alert_over`.
+fn watch_session(
+    session_id: &str,
+    agent: &str,
+    estimate_tokens: Option<f64>,
+    interval: u64,
+    alert_over: Option<f64>,
+) -> Result<()> {
+    let agents = parse_agents(agent)?;
+    let session = ingest::find_session(session_id, &agents)?
+        .ok_or_else(|| anyhow::anyhow!("No session found matching '{}'", session_id))?;
+
+    eprintln!(
+        "{} Watching {} (every {}s, Ctrl-C to stop)",
+        "→".cyan(),
+        session.session_id,
+        interval.max(1)
+    );
+
+    // Claude and Codex traces are read incrementally; others are re-parsed each tick
+    let mut tail = ingest::SessionTail::new(&session);
+    let mut last_seen: Option<(usize, usize)> = None;
+    let mut alerted = false;
+    loop {
+        let parsed = match tail.as_mut() {
+            Some(tail) => {
+                tail.poll()?;
+                let mut parsed = tail.parsed();
+                if let Some(chars_per_token) = estimate_tokens {
+                    parsed.estimate_missing_usage(chars_per_token);
+                }
+                parsed
+            }
+            None => parse_session(&session, estimate_tokens)?,
+        };
+        let findings = detect_inefficiencies(&parsed);
+        let s = &parsed.session;
+        let cost = s.total_cost_usd.unwrap_or(0.0);
+
+        if last_seen != Some((s.message_count, findings.len())) {
+            last_seen = Some((s.message_count, findings.len()));
+            let waste: f64 = findings.iter().filter_map(|f| f.wasted_cost_usd).sum();
+            println!(
+                "[{}]  {:>10}  {:>5} msgs  {:>3} findings  ~{} waste",
+                chrono::Local::now().format("%H:%M:%S"),
+                terminal::fmt_cost(s.total_cost_usd).yellow(),
+                s.message_count,
+                findings.len(),
+                fmt_money(waste, 2)
+            );
+        }
+
+        if let Some(limit) = alert_over {
+            if cost > limit && !alerted {
+                alerted = true;
+                eprintln!(
+                    "\x07{} Session cost {} crossed the {} alert threshold",
+                    "⚠ ALERT".red().bold(),
+                    terminal::fmt_cost(Some(cost)).red().bold(),
+                    fmt_money(limit, 2)
+                );
+            }
+        }
+
+        std::thread::sleep(std::time::Duration::from_secs(interval.max(1)));
+    }
 }
 
 pub fn run(args: AnalyzeArgs) -> Result<()> {
@@ -100,32 +322,75 @@ pub fn run(args: AnalyzeArgs) -> Result<()> {
             session_id,
             agent,
             optimize_for: _,
+            from_turn,
+            to_turn,
             format,
+            redact_paths,
+            pseudonymize,
+            estimate_tokens,
+            dry_run,
+            watch,
+            interval,
+            alert_over,
+            explain,
+            include_system_prompt_size,
+            top_turns,
         } => {
-            let result = analyze_session_by_id(&session_id, &agent, 10)?;
+            if watch {
+                return watch_session(&session_id, &agent, estimate_tokens, interval, alert_over);
+            }
+            let (mut result, traces) = analyze_session_by_id(
+                &session_id,
+                &agent,
+                top_turns,
+                estimate_tokens,
+                (from_turn, to_turn),
+                explain,
+                include_system_prompt_size,
+            )?;
+            redact_results(std::slice::from_mut(&mut result), redact_paths);
+            apply_pseudonyms(std::slice::from_mut(&mut result), pseudonymize);
             match format.as_str() {
                 "json" => println!("{}", jreport::render_analysis(&result)?),
                 "html" => {
                     let content = html_report::render_analysis(&result)?;
-                    let out = format!("report-{}.html", &session_id[..8.min(session_id.len())]);
-                    std::fs::write(&out, &content)?;
-                    eprintln!("{} Written to {}", "✓".green(), out);
+                    let out = report_file_name(&result.session.session_id, "html");
+                    write_output(std::path::Path::new(&out), &content, dry_run)?;
                     // Also print summary to terminal
                     terminal::print_analysis(&result);
+                    terminal::print_detector_traces(&traces);
+                }
+                _ => {
+                    terminal::print_analysis(&result);
+                    terminal::print_detector_traces(&traces);
                 }
-                _ => terminal::print_analysis(&result),
             }
         }
 
         AnalyzeSubcommand::Recent {
             agent,
             limit,
+            min_messages,
             since,
             format,
+            redact_paths,
+            estimate_tokens,
+            page,
         } => {
+            if format != "json" && run_paged(page)? {
+                return Ok(());
+            }
             let agents = parse_agents(&agent)?;
             let since_dt = since.as_deref().map(parse_datetime).transpose()?;
-            let sessions = ingest::discover_sessions(&agents, since_dt, None, None, Some(limit))?;
+            let sessions = ingest::discover_sessions(
+                &agents,
+                &DiscoveryFilter {
+                    since: since_dt,
+                    min_messages,
+                    limit: Some(limit),
+                    ..Default::default()
+                },
+            )?;
 
             if sessions.is_empty() {
                 println!("{}", "No sessions found.".yellow());
@@ -134,32 +399,64 @@ pub fn run(args: AnalyzeArgs) -> Result<()> {
 
             eprintln!("{} Analyzing {} sessions...", "→".cyan(), sessions.len());
 
-            let results: Vec<AnalysisResult> = sessions
-                .iter()
-                .map(|s| {
-                    let parsed = match ingest::parse_session(s) {
-                        Ok(p) => p,
-                        Err(e) => {
-                            eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e);
-                            return AnalysisResult {
-                                session: s.clone(),
-                                findings: Vec::new(),
-                                top_expensive_messages: Vec::new(),
-                            };
-                        }
-                    };
-                    let findings = detect_inefficiencies(&parsed);
-                    let top = top_expensive_messages(&parsed, 3);
-                    AnalysisResult {
-                        session: parsed.session,
-                        findings,
-                        top_expensive_messages: top,
-                    }
-                })
-                .collect();
+            let mut results = analyze_sessions(&sessions, estimate_tokens, 3);
+            redact_results(&mut results, redact_paths);
+
+            match format.as_str() {
+                "json" => println!("{}", jreport::render_aggregate(&results, None)?),
+                _ => terminal::print_aggregate(&results),
+            }
+        }
+
+        AnalyzeSubcommand::Dir {
+            path,
+            agent,
+            min_messages,
+            since,
+            until,
+            cwd,
+            format,
+            redact_paths,
+            estimate_tokens,
+            page,
+        } => {
+            if !path.is_dir() {
+                anyhow::bail!("{} is not a directory", path.display());
+            }
+            if format != "json" && run_paged(page)? {
+                return Ok(());
+            }
+            let agents = parse_agents(&agent)?;
+            let since_dt = since.as_deref().map(parse_datetime).transpose()?;
+            let until_dt = until.as_deref().map(parse_datetime).transpose()?;
+            let sessions = ingest::filter_sessions(
+                ingest::discover_sessions_in(&path, &agents),
+                &DiscoveryFilter {
+                    since: since_dt,
+                    until: until_dt,
+                    cwd,
+                    min_messages,
+                    ..Default::default()
+                },
+            );
+
+            if sessions.is_empty() {
+                println!("{}", "No sessions found.".yellow());
+                return Ok(());
+            }
+
+            eprintln!(
+                "{} Analyzing {} sessions under {}...",
+                "→".cyan(),
+                sessions.len(),
+                path.display()
+            );
+
+            let mut results = analyze_sessions(&sessions, estimate_tokens, 3);
+            redact_results(&mut results, redact_paths);
 
             match format.as_str() {
-                "json" => println!("{}", jreport::render_aggregate(&results)?),
+                "json" => println!("{}", jreport::render_aggregate(&results, None)?),
                 _ => terminal::print_aggregate(&results),
             }
         }
@@ -167,14 +464,28 @@ pub fn run(args: AnalyzeArgs) -> Result<()> {
         AnalyzeSubcommand::Expensive {
             agent,
             top,
+            min_messages,
             since,
             format,
+            redact_paths,
+            estimate_tokens,
+            page,
         } => {
+            if format != "json" && run_paged(page)? {
+                return Ok(());
+            }
             let agents = parse_agents(&agent)?;
             let since_dt = since.as_deref().map(parse_datetime).transpose()?;
 
             // We need to parse all sessions to find cost, then take top N
-            let sessions = ingest::discover_sessions(&agents, since_dt, None, None, None)?;
+            let sessions = ingest::discover_sessions(
+                &agents,
+                &DiscoveryFilter {
+                    since: since_dt,
+                    min_messages,
+                    ..Default::default()
+                },
+            )?;
 
             if sessions.is_empty() {
                 println!("{}", "No sessions found.".yellow());
@@ -186,10 +497,11 @@ pub fn run(args: AnalyzeArgs) -> Result<()> {
             let mut results: Vec<AnalysisResult> = sessions
                 .iter()
                 .filter_map(|s| {
-                    let parsed = ingest::parse_session(s).ok()?;
+                    let parsed = parse_session(s, estimate_tokens).ok()?;
                     let findings = detect_inefficiencies(&parsed);
                     let top_msgs = top_expensive_messages(&parsed, 5);
                     Some(AnalysisResult {
+                        session_kind: SessionKind::of(&parsed),
                         session: parsed.session,
                         findings,
                         top_expensive_messages: top_msgs,
@@ -206,12 +518,119 @@ pub fn run(args: AnalyzeArgs) -> Result<()> {
                     .unwrap_or(std::cmp::Ordering::Equal)
             });
             results.truncate(top);
+            redact_results(&mut results, redact_paths);
 
             match format.as_str() {
-                "json" => println!("{}", jreport::render_aggregate(&results)?),
+                "json" => println!("{}", jreport::render_aggregate(&results, None)?),
                 _ => terminal::print_expensive_sessions(&results, top),
             }
         }
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use tracekit_core::ParsedSession;
+
+    #[derive(Parser)]
+    struct Cmd {
+        #[command(flatten)]
+        analyze: AnalyzeArgs,
+    }
+
+    #[test]
+    fn top_turns_limits_the_expensive_turn_list() {
+        let cmd = Cmd::try_parse_from([
+            "analyze",
+            "session",
+            "--session-id",
+            "abc",
+            "--top-turns",
+            "3",
+        ])
+        .unwrap();
+        let AnalyzeSubcommand::Session { top_turns, .. } = cmd.analyze.subcommand else {
+            panic!("expected the session subcommand");
+        };
+        assert_eq!(top_turns, 3);
+
+        let messages: Vec<serde_json::Value> = (0..8)
+            .map(|seq| {
+                serde_json::json!({
+                    "message_id": format!("m{}", seq),
+                    "session_id": "s",
+                    "parent_id": null,
+                    "sequence": seq,
+                    "role": if seq % 2 == 0 { "user" } else { "assistant" },
+                    "model": null,
+                    "ts": null,
+                    "usage": {
+                        "input_tokens": 100,
+                        "output_tokens": 10,
+                        "reasoning_tokens": 0,
+                        "cache_read_tokens": 0,
+                        "cache_write_tokens": 0,
+                        "cost_observed_usd": seq as f64,
+                        "cost_estimated_usd": null,
+                    },
+                    "tool_calls": [],
+                    "is_sidechain": false,
+                    "finish_reason": null,
+                })
+            })
+            .collect();
+        let parsed: ParsedSession = serde_json::from_value(serde_json::json!({
+            "session": {
+                "session_id": "s",
+                "source_agent": "claude",
+                "source_path": "/tmp/s.jsonl",
+                "cwd": null,
+                "title": null,
+                "started_at": null,
+                "ended_at": null,
+                "model": null,
+                "message_count": 8,
+                "total_cost_usd": null,
+                "total_input_tokens": 0,
+                "total_output_tokens": 0,
+            },
+            "messages": messages,
+        }))
+        .unwrap();
+
+        // Four priced assistant turns; exactly the three costliest are listed
+        let rows = top_expensive_messages(&parsed, top_turns);
+        let seqs: Vec<usize> = rows.iter().map(|m| m.sequence).collect();
+        assert_eq!(seqs, [7, 5, 3]);
+    }
+
+    #[test]
+    fn watch_rejects_report_options() {
+        let parse = |extra: &[&str]| {
+            let mut args = vec!["analyze", "session", "--session-id", "abc", "--watch"];
+            args.extend_from_slice(extra);
+            Cmd::try_parse_from(args)
+        };
+        assert!(parse(&["--interval", "5", "--alert-over", "1.5"]).is_ok());
+        for extra in [
+            &["--from-turn", "3"][..],
+            &["--to-turn", "9"],
+            &["--format", "json"],
+            &["--redact-paths"],
+            &["--pseudonymize"],
+            &["--explain"],
+            &["--top-turns", "3"],
+        ] {
+            let err = parse(extra).err().expect("--watch should conflict");
+            assert_eq!(
+                err.kind(),
+                clap::error::ErrorKind::ArgumentConflict,
+                "{:?}",
+                extra
+            );
+        }
+    }
+}
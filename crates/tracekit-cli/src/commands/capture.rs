@@ -4,9 +4,10 @@ use colored::Colorize;
 use serde_json::Value;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use tracekit_ingest::{self as ingest};
+use tracekit_ingest::{self as ingest, DiscoveryFilter};
+use tracekit_report::format::fmt_system_prompt;
 
-use super::parse_agents;
+use super::{expand_path, parse_agents, DEFAULT_CHARS_PER_TOKEN};
 
 #[derive(Args)]
 pub struct CaptureArgs {
@@ -18,6 +19,8 @@ pub struct CaptureArgs {
 pub enum InspectMode {
     Analysis,
     Forensic,
+    /// Original trace records, one JSON object per line, untransformed
+    Raw,
 }
 
 #[derive(Subcommand)]
@@ -37,6 +40,28 @@ pub enum CaptureSubcommand {
         #[arg(long, default_value = "20")]
         limit: usize,
     },
+    /// Parse every session into the local parse cache so later analysis is fast.
+    /// Re-running skips sessions already cached, so an interrupted import resumes.
+    Import {
+        /// Agent filter
+        #[arg(long, default_value = "all")]
+        agent: String,
+        /// Pause between parsed sessions, in milliseconds, to limit disk/CPU load
+        #[arg(long, default_value = "0")]
+        throttle_ms: u64,
+    },
+    /// Attach tags to a session, for `--tag` filters and `--group-by tag`.
+    /// Tags are kept in tracekit's own state file, not the agent's trace.
+    Tag {
+        /// Session ID (prefix match)
+        session_id: String,
+        /// Tags to add, e.g. prod-incident experiment
+        #[arg(required = true)]
+        tags: Vec<String>,
+        /// Agent filter
+        #[arg(long, default_value = "all")]
+        agent: String,
+    },
     /// Show details for a single session
     Session {
         /// Agent name
@@ -52,11 +77,41 @@ pub enum CaptureSubcommand {
         #[arg(long, default_value_t = false)]
         inspect_terminal: bool,
         /// Optional output file path for inspect file
-        #[arg(long)]
+        #[arg(long, value_parser = expand_path)]
         inspect_out: Option<PathBuf>,
-        /// Inspect rendering mode: analysis (deduped/noise-reduced) or forensic (full)
+        /// Inspect rendering mode: analysis (deduped/noise-reduced), forensic (full),
+        /// or raw (original records as JSONL)
         #[arg(long, value_enum, default_value_t = InspectMode::Analysis)]
         inspect_mode: InspectMode,
+        /// In raw mode, blank instruction/signature fields and truncate long strings.
+        /// Redacted records are re-serialized, so their keys come out sorted.
+        #[arg(long)]
+        raw_redact: bool,
+        /// Also inspect Claude subagent traces, interleaved by timestamp and
+        /// labeled with the subagent id
+        #[arg(long)]
+        follow_subagents: bool,
+        /// Marker appended to truncated inspect text (e.g. "..." for terminals without "…")
+        #[arg(long, default_value = "…")]
+        ellipsis: String,
+        /// Cut truncated inspect text at the preceding whitespace instead of mid-word
+        #[arg(long)]
+        word_boundary: bool,
+        /// Report the size of the opening context (system prompt, tools, instructions)
+        #[arg(long)]
+        include_system_prompt_size: bool,
+        /// Only show inspect entries numbered >= N
+        #[arg(long)]
+        from_turn: Option<usize>,
+        /// Only show inspect entries numbered <= N
+        #[arg(long)]
+        to_turn: Option<usize>,
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+        /// Metadata output format: table, json
+        #[arg(long, default_value = "table")]
+        format: String,
     },
 }
 
@@ -64,7 +119,7 @@ pub fn run(args: CaptureArgs) -> Result<()> {
     match args.subcommand {
         CaptureSubcommand::All { agent } => {
             let agents = parse_agents(&agent)?;
-            let sessions = ingest::discover_sessions(&agents, None, None, None, None)?;
+            let sessions = ingest::discover_sessions(&agents, &Default::default())?;
             println!("{} Discovered {} sessions", "✓".green(), sessions.len());
             for s in &sessions {
                 println!("  {} {}", s.source_agent.to_string().cyan(), s.session_id);
@@ -72,7 +127,13 @@ pub fn run(args: CaptureArgs) -> Result<()> {
         }
         CaptureSubcommand::Recent { agent, limit } => {
             let agents = parse_agents(&agent)?;
-            let sessions = ingest::discover_sessions(&agents, None, None, None, Some(limit))?;
+            let sessions = ingest::discover_sessions(
+                &agents,
+                &DiscoveryFilter {
+                    limit: Some(limit),
+                    ..Default::default()
+                },
+            )?;
             println!("{} Found {} recent sessions", "✓".green(), sessions.len());
             for s in &sessions {
                 println!(
@@ -83,6 +144,76 @@ pub fn run(args: CaptureArgs) -> Result<()> {
                 );
             }
         }
+        CaptureSubcommand::Import { agent, throttle_ms } => {
+            let agents = parse_agents(&agent)?;
+            let sessions = ingest::discover_sessions(&agents, &Default::default())?;
+            let total = sessions.len();
+            let (mut imported, mut skipped, mut failed) = (0usize, 0usize, 0usize);
+            for (i, s) in sessions.iter().enumerate() {
+                if ingest::cache::is_cached(s) {
+                    skipped += 1;
+                    continue;
+                }
+                eprintln!(
+                    "{} [{}/{}] {} {}",
+                    "→".cyan(),
+                    i + 1,
+                    total,
+                    s.source_agent.to_string().cyan(),
+                    s.session_id
+                );
+                match ingest::parse_session(s).and_then(|parsed| ingest::cache::store(s, &parsed)) {
+                    Ok(()) => imported += 1,
+                    Err(e) => {
+                        eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e);
+                        failed += 1;
+                    }
+                }
+                if throttle_ms > 0 {
+                    std::thread::sleep(std::time::Duration::from_millis(throttle_ms));
+                }
+            }
+            println!(
+                "{} Imported {} sessions ({} already cached, {} failed)",
+                "✓".green(),
+                imported,
+                skipped,
+                failed
+            );
+            if let Some(dir) = ingest::cache::cache_dir() {
+                println!("  Cache: {}", dir.display().to_string().dimmed());
+            }
+        }
+        CaptureSubcommand::Tag {
+            session_id,
+            tags,
+            agent,
+        } => {
+            let tags: Vec<String> = tags
+                .iter()
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect();
+            if tags.is_empty() {
+                anyhow::bail!("No tags given");
+            }
+            let agents = parse_agents(&agent)?;
+            let Some(s) = ingest::find_session(&session_id, &agents)? else {
+                println!("{} No session found matching '{}'", "✗".red(), session_id);
+                return Ok(());
+            };
+            let mut store = ingest::tags::TagStore::load()?;
+            let added = store.add(&s.session_id, &tags);
+            let path = store.save()?;
+            println!(
+                "{} Tagged {} ({} new): {}",
+                "✓".green(),
+                s.session_id,
+                added,
+                store.get(&s.session_id).join(", ").cyan()
+            );
+            println!("  Tags: {}", path.display().to_string().dimmed());
+        }
         CaptureSubcommand::Session {
             agent,
             session_id,
@@ -90,31 +221,92 @@ pub fn run(args: CaptureArgs) -> Result<()> {
             inspect_terminal,
             inspect_out,
             inspect_mode,
+            raw_redact,
+            follow_subagents,
+            ellipsis,
+            word_boundary,
+            include_system_prompt_size,
+            from_turn,
+            to_turn,
+            redact_paths,
+            format,
         } => {
             let agents = parse_agents(&agent)?;
             match ingest::find_session(&session_id, &agents)? {
                 Some(s) => {
-                    println!("{} Found session", "✓".green());
-                    println!("  Agent    : {}", s.source_agent.to_string().cyan());
-                    println!("  ID       : {}", s.session_id);
-                    println!("  Path     : {}", s.source_path.display());
-                    println!("  CWD      : {}", s.cwd.as_deref().unwrap_or("-"));
-                    println!(
-                        "  Started  : {}",
-                        s.started_at
-                            .map(|t| t.to_string())
-                            .unwrap_or_else(|| "-".to_string())
-                    );
+                    // Inspect still reads from the real path; only the displayed copy is redacted
+                    let mut shown = s.clone();
+                    if let Some(mode) = redact_paths {
+                        ingest::redact_session_paths(&mut shown, mode);
+                    }
+                    if include_system_prompt_size {
+                        let parsed = ingest::cache::parse_session_cached(&s)?;
+                        shown.system_prompt = parsed.system_prompt_size(DEFAULT_CHARS_PER_TOKEN);
+                    }
+                    if format == "json" {
+                        println!("{}", tracekit_report::json::render_session(&shown)?);
+                    } else {
+                        println!("{} Found session", "✓".green());
+                        println!("  Agent    : {}", shown.source_agent.to_string().cyan());
+                        println!("  ID       : {}", shown.session_id);
+                        println!("  Path     : {}", shown.source_path.display());
+                        println!("  CWD      : {}", shown.cwd.as_deref().unwrap_or("-"));
+                        println!(
+                            "  Started  : {}",
+                            shown
+                                .started_at
+                                .map(|t| t.to_string())
+                                .unwrap_or_else(|| "-".to_string())
+                        );
+                        if let Some(size) = &shown.system_prompt {
+                            println!("  System   : {}", fmt_system_prompt(size));
+                        }
+                    }
 
                     let write_inspect = inspect_file || inspect_out.is_some();
-                    if write_inspect || inspect_terminal {
-                        let entries = build_inspect_entries(&s)?;
-                        let transformed = transform_inspect_entries(&entries, inspect_mode);
+                    if matches!(inspect_mode, InspectMode::Raw)
+                        && (write_inspect || inspect_terminal)
+                    {
+                        let records =
+                            slice_records(raw_records(&s, raw_redact)?, from_turn, to_turn);
+                        if write_inspect {
+                            let out_path =
+                                inspect_out.unwrap_or_else(|| default_raw_path(&s.session_id));
+                            if let Some(parent) = out_path.parent() {
+                                if !parent.as_os_str().is_empty() {
+                                    std::fs::create_dir_all(parent)?;
+                                }
+                            }
+                            let mut jsonl = records.join("\n");
+                            jsonl.push('\n');
+                            std::fs::write(&out_path, jsonl)?;
+                            println!(
+                                "{} Raw records: {} ({} records)",
+                                "✓".green(),
+                                out_path.display(),
+                                records.len()
+                            );
+                        }
+                        if inspect_terminal {
+                            println!();
+                            for record in &records {
+                                println!("{}", record);
+                            }
+                        }
+                    } else if write_inspect || inspect_terminal {
+                        let limit = TextLimit {
+                            ellipsis,
+                            word_boundary,
+                        };
+                        let entries = build_inspect_entries(&s, &limit, follow_subagents)?;
+                        let mut transformed = transform_inspect_entries(&entries, inspect_mode);
+                        transformed.slice(from_turn, to_turn);
 
                         if write_inspect {
                             let out_path =
                                 inspect_out.unwrap_or_else(|| default_inspect_path(&s.session_id));
-                            let markdown = render_inspect_markdown(&s, &transformed, inspect_mode);
+                            let markdown =
+                                render_inspect_markdown(&shown, &transformed, inspect_mode);
                             if let Some(parent) = out_path.parent() {
                                 if !parent.as_os_str().is_empty() {
                                     std::fs::create_dir_all(parent)?;
@@ -126,7 +318,7 @@ pub fn run(args: CaptureArgs) -> Result<()> {
 
                         if inspect_terminal {
                             println!();
-                            print_inspect_terminal(&s, &transformed, inspect_mode);
+                            print_inspect_terminal(&shown, &transformed, inspect_mode);
                         }
                     }
                 }
@@ -163,23 +355,100 @@ struct InspectSummary {
 struct InspectRender {
     entries: Vec<InspectEntry>,
     summary: InspectSummary,
+    /// Number of the first rendered entry minus one (non-zero after slicing)
+    offset: usize,
+}
+
+impl InspectRender {
+    /// Keep entries numbered `from..=to` (1-based, as rendered), preserving numbering.
+    fn slice(&mut self, from: Option<usize>, to: Option<usize>) {
+        if from.is_none() && to.is_none() {
+            return;
+        }
+        let start = from.unwrap_or(1).max(1) - 1;
+        let end = to.unwrap_or(usize::MAX).min(self.entries.len());
+        self.entries = if start < end {
+            self.entries[start..end].to_vec()
+        } else {
+            Vec::new()
+        };
+        self.offset = start;
+        self.summary = build_summary(
+            self.summary.raw_entries,
+            &self.entries,
+            self.summary.dropped_noise,
+            self.summary.dropped_duplicates,
+        );
+    }
 }
 
 fn default_inspect_path(session_id: &str) -> PathBuf {
     PathBuf::from("inspect-traces").join(format!("tracekit-inspect-{}.md", session_id))
 }
 
-fn build_inspect_entries(session: &tracekit_core::CanonicalSession) -> Result<Vec<InspectEntry>> {
+fn default_raw_path(session_id: &str) -> PathBuf {
+    PathBuf::from("inspect-traces").join(format!("tracekit-raw-{}.jsonl", session_id))
+}
+
+fn build_inspect_entries(
+    session: &tracekit_core::CanonicalSession,
+    limit: &TextLimit,
+    follow_subagents: bool,
+) -> Result<Vec<InspectEntry>> {
     match session.source_agent {
-        tracekit_core::Agent::Claude => inspect_claude(session),
-        tracekit_core::Agent::Codex => inspect_codex(session),
-        tracekit_core::Agent::Opencode => inspect_opencode(session),
-        _ => inspect_generic_jsonl(&session.source_path, &session.source_agent.to_string()),
+        tracekit_core::Agent::Claude if follow_subagents => {
+            inspect_claude_with_subagents(session, limit)
+        }
+        tracekit_core::Agent::Claude => inspect_claude(&session.source_path, limit),
+        tracekit_core::Agent::Codex => inspect_codex(session, limit),
+        tracekit_core::Agent::Opencode => inspect_opencode(session, limit),
+        _ => inspect_generic_jsonl(
+            &session.source_path,
+            &session.source_agent.to_string(),
+            limit,
+        ),
     }
 }
 
-fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<InspectEntry>> {
-    let content = std::fs::read_to_string(&session.source_path)?;
+/// The main trace's entries with every subagent's merged in by timestamp. Each
+/// subagent entry is tagged with the agent id taken from its file name.
+fn inspect_claude_with_subagents(
+    session: &tracekit_core::CanonicalSession,
+    limit: &TextLimit,
+) -> Result<Vec<InspectEntry>> {
+    let mut keyed = timestamp_keyed(inspect_claude(&session.source_path, limit)?);
+    for path in ingest::claude::subagent_files(session) {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("-");
+        let id = stem.strip_prefix("agent-").unwrap_or(stem).to_string();
+        let mut entries = inspect_claude(&path, limit)?;
+        for entry in &mut entries {
+            entry.title = format!("[subagent {}] {}", id, entry.title);
+            entry.source_type = entry.source_type.replacen("claude:", "claude:subagent:", 1);
+            entry.metadata.push(("subagent".to_string(), id.clone()));
+        }
+        keyed.extend(timestamp_keyed(entries));
+    }
+    keyed.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(keyed.into_iter().map(|(_, e)| e).collect())
+}
+
+/// Pair each of one file's entries with its sort key: its own timestamp, or the
+/// previous entry's if it has none, so untimestamped entries keep their place.
+fn timestamp_keyed(entries: Vec<InspectEntry>) -> Vec<(Option<String>, InspectEntry)> {
+    let mut last_ts: Option<String> = None;
+    entries
+        .into_iter()
+        .map(|e| {
+            if e.ts.is_some() {
+                last_ts = e.ts.clone();
+            }
+            (last_ts.clone(), e)
+        })
+        .collect()
+}
+
+fn inspect_claude(path: &Path, limit: &TextLimit) -> Result<Vec<InspectEntry>> {
+    let content = std::fs::read_to_string(path)?;
     let mut out = Vec::new();
 
     for line in content.lines() {
@@ -199,6 +468,13 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
             .and_then(|v| v.as_str())
             .map(|s| s.to_string());
 
+        if matches!(kind, "system" | "attachment") {
+            if let Some(entry) = claude_hook_or_permission(&record, ts.clone(), limit) {
+                out.push(entry);
+                continue;
+            }
+        }
+
         match kind {
             "user" => {
                 if let Some(content) = record.pointer("/message/content") {
@@ -207,7 +483,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                             ts: ts.clone(),
                             label: "USER".to_string(),
                             title: "User prompt".to_string(),
-                            body: Some(limit_text(s, 8000)),
+                            body: Some(limit.apply(s, 8000)),
                             source_type: "claude:user".to_string(),
                             metadata: vec![(
                                 "is_meta".to_string(),
@@ -236,7 +512,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                                             .unwrap_or(false);
                                         let body =
                                             extract_text(block.get("content")).or_else(|| {
-                                                Some(limit_text(&compact_json(block), 1200))
+                                                Some(limit.apply(&compact_json(block), 1200))
                                             });
                                         out.push(InspectEntry {
                                             ts: ts.clone(),
@@ -258,7 +534,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                                                 ts: ts.clone(),
                                                 label: "USER".to_string(),
                                                 title: "User prompt".to_string(),
-                                                body: Some(limit_text(text, 8000)),
+                                                body: Some(limit.apply(text, 8000)),
                                                 source_type: "claude:user.text".to_string(),
                                                 metadata: vec![],
                                             });
@@ -268,7 +544,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                                         ts: ts.clone(),
                                         label: "USER".to_string(),
                                         title: format!("User block: {}", btype),
-                                        body: Some(limit_text(&compact_json(block), 1200)),
+                                        body: Some(limit.apply(&compact_json(block), 1200)),
                                         source_type: "claude:user.block".to_string(),
                                         metadata: vec![],
                                     }),
@@ -280,6 +556,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                 }
             }
             "assistant" => {
+                let first_entry = out.len();
                 if let Some(content_arr) = record
                     .pointer("/message/content")
                     .and_then(|v| v.as_array())
@@ -296,7 +573,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                                         ts: ts.clone(),
                                         label: "ASSISTANT".to_string(),
                                         title: "Assistant reply".to_string(),
-                                        body: Some(limit_text(text, 8000)),
+                                        body: Some(limit.apply(text, 8000)),
                                         source_type: "claude:assistant.text".to_string(),
                                         metadata: vec![],
                                     });
@@ -312,7 +589,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                                     ts: ts.clone(),
                                     label: "THINKING".to_string(),
                                     title: "Assistant reasoning".to_string(),
-                                    body: Some(limit_text(thought, 8000)),
+                                    body: Some(limit.apply(thought, 8000)),
                                     source_type: "claude:assistant.thinking".to_string(),
                                     metadata: vec![],
                                 });
@@ -330,7 +607,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                                     ts: ts.clone(),
                                     label: "TOOL_CALL".to_string(),
                                     title: format!("Tool call: {}", name),
-                                    body: Some(limit_text(&args, 2000)),
+                                    body: Some(limit.apply(&args, 2000)),
                                     source_type: "claude:assistant.tool_use".to_string(),
                                     metadata: vec![("tool_id".to_string(), tool_id.to_string())],
                                 });
@@ -339,6 +616,18 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                         }
                     }
                 }
+                // Attach the stop reason to the last entry of this message
+                if let Some(reason) = record
+                    .pointer("/message/stop_reason")
+                    .and_then(|v| v.as_str())
+                {
+                    if out.len() > first_entry {
+                        if let Some(last) = out.last_mut() {
+                            last.metadata
+                                .push(("finish_reason".to_string(), reason.to_string()));
+                        }
+                    }
+                }
             }
             "system" => out.push(InspectEntry {
                 ts: ts.clone(),
@@ -350,10 +639,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                         .and_then(|v| v.as_str())
                         .unwrap_or("general")
                 ),
-                body: Some(limit_text(
-                    &compact_json(&redact_record(record.clone())),
-                    1500,
-                )),
+                body: Some(limit.apply(&compact_json(&redact_record(record.clone())), 1500)),
                 source_type: "claude:system".to_string(),
                 metadata: vec![],
             }),
@@ -361,10 +647,7 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
                 ts: ts.clone(),
                 label: "EVENT".to_string(),
                 title: format!("Event: {}", kind),
-                body: Some(limit_text(
-                    &compact_json(&redact_record(record.clone())),
-                    1200,
-                )),
+                body: Some(limit.apply(&compact_json(&redact_record(record.clone())), 1200)),
                 source_type: format!("claude:{}", kind),
                 metadata: vec![],
             }),
@@ -375,7 +658,98 @@ fn inspect_claude(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspe
     Ok(out)
 }
 
-fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<InspectEntry>> {
+/// Recognize Claude Code hook runs and tool-permission decisions, which arrive as
+/// `system` records (e.g. `stop_hook_summary`, `PreToolUse:Bash ...` notices) or
+/// `attachment` records (`hook_success`, `hook_blocking_error`, ...).
+fn claude_hook_or_permission(
+    record: &Value,
+    ts: Option<String>,
+    limit: &TextLimit,
+) -> Option<InspectEntry> {
+    let attachment = record.get("attachment");
+    let subtype = record
+        .get("subtype")
+        .or_else(|| attachment.and_then(|a| a.get("type")))
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    let field = |key: &str| {
+        record
+            .get(key)
+            .or_else(|| attachment.and_then(|a| a.get(key)))
+            .and_then(|v| v.as_str())
+    };
+    let body = Some(limit.apply(&compact_json(&redact_record(record.clone())), 1500));
+
+    if subtype.contains("permission") || field("decision").is_some() {
+        let tool = field("toolName")
+            .or_else(|| field("tool_name"))
+            .unwrap_or("tool")
+            .to_lowercase();
+        let decision = field("decision")
+            .or_else(|| field("behavior"))
+            .unwrap_or("requested");
+        let verb = match decision {
+            "allow" | "approve" | "approved" | "accept" | "granted" => "granted",
+            "deny" | "denied" | "block" | "reject" | "rejected" => "denied",
+            other => other,
+        };
+        return Some(InspectEntry {
+            ts,
+            label: "PERMISSION".to_string(),
+            title: format!("Permission {} for {}", verb, tool),
+            body,
+            source_type: "claude:system.permission".to_string(),
+            metadata: vec![("decision".to_string(), decision.to_string())],
+        });
+    }
+
+    // Older records only carry a notice like "PreToolUse:Bash [cmd] completed successfully"
+    let content_hook = field("content")
+        .filter(|c| {
+            field("toolUseID").is_some()
+                && c.split_whitespace().next().is_some_and(|w| w.contains(':'))
+        })
+        .and_then(|c| c.split_whitespace().next());
+    if !subtype.contains("hook") && field("hookName").is_none() && content_hook.is_none() {
+        return None;
+    }
+    let name = field("hookName")
+        .or_else(|| field("hookEvent"))
+        .or(content_hook)
+        .or_else(|| {
+            record
+                .pointer("/hookInfos/0/command")
+                .and_then(|v| v.as_str())
+        })
+        .unwrap_or(subtype);
+    let outcome = if record
+        .get("preventedContinuation")
+        .and_then(|v| v.as_bool())
+        == Some(true)
+        || subtype.contains("blocking")
+    {
+        "blocked"
+    } else if subtype.contains("error") || field("level") == Some("error") {
+        "error"
+    } else if subtype.contains("cancel") {
+        "cancelled"
+    } else {
+        "ok"
+    };
+    Some(InspectEntry {
+        ts,
+        label: "HOOK".to_string(),
+        title: format!("Hook {} ({})", name, outcome),
+        body,
+        source_type: "claude:system.hook".to_string(),
+        metadata: vec![("outcome".to_string(), outcome.to_string())],
+    })
+}
+
+fn inspect_codex(
+    session: &tracekit_core::CanonicalSession,
+    limit: &TextLimit,
+) -> Result<Vec<InspectEntry>> {
     let content = std::fs::read_to_string(&session.source_path)?;
     let mut out = Vec::new();
 
@@ -410,7 +784,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                     ts: ts.clone(),
                     label: "SYSTEM".to_string(),
                     title: "Session metadata".to_string(),
-                    body: Some(limit_text(&compact_json(&body), 1200)),
+                    body: Some(limit.apply(&compact_json(&body), 1200)),
                     source_type: "codex:session_meta".to_string(),
                     metadata: vec![],
                 });
@@ -427,9 +801,10 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             .get("role")
                             .and_then(|v| v.as_str())
                             .unwrap_or("assistant");
-                        let text = extract_codex_message_text(&payload).unwrap_or_else(|| {
-                            limit_text(&compact_json(&redact_record(payload.clone())), 1200)
-                        });
+                        let text =
+                            extract_codex_message_text(&payload, limit).unwrap_or_else(|| {
+                                limit.apply(&compact_json(&redact_record(payload.clone())), 1200)
+                            });
                         out.push(InspectEntry {
                             ts: ts.clone(),
                             label: role.to_uppercase(),
@@ -449,7 +824,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "USER".to_string(),
                             title: "User prompt".to_string(),
-                            body: Some(limit_text(text, 8000)),
+                            body: Some(limit.apply(text, 8000)),
                             source_type: "codex:response_item.user_message".to_string(),
                             metadata: vec![],
                         });
@@ -470,7 +845,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "THINKING".to_string(),
                             title: "Assistant reasoning".to_string(),
-                            body: Some(limit_text(&text, 8000)),
+                            body: Some(limit.apply(&text, 8000)),
                             source_type: "codex:response_item.reasoning".to_string(),
                             metadata: vec![],
                         });
@@ -495,7 +870,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "TOOL_CALL".to_string(),
                             title: format!("Tool call: {}", name),
-                            body: Some(limit_text(&args, 2000)),
+                            body: Some(limit.apply(&args, 2000)),
                             source_type: format!("codex:response_item.{}", ptype),
                             metadata: vec![(
                                 "call_id".to_string(),
@@ -522,7 +897,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "TOOL_RESULT".to_string(),
                             title: "Tool output".to_string(),
-                            body: Some(limit_text(&output, 4000)),
+                            body: Some(limit.apply(&output, 4000)),
                             source_type: format!("codex:response_item.{}", ptype),
                             metadata: vec![(
                                 "call_id".to_string(),
@@ -553,7 +928,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "ASSISTANT".to_string(),
                             title: "Assistant reply".to_string(),
-                            body: Some(limit_text(text, 8000)),
+                            body: Some(limit.apply(text, 8000)),
                             source_type: "codex:event_msg.agent_message".to_string(),
                             metadata: vec![],
                         });
@@ -567,7 +942,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "THINKING".to_string(),
                             title: "Assistant reasoning".to_string(),
-                            body: Some(limit_text(text, 8000)),
+                            body: Some(limit.apply(text, 8000)),
                             source_type: "codex:event_msg.agent_reasoning".to_string(),
                             metadata: vec![],
                         });
@@ -581,7 +956,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                             ts: ts.clone(),
                             label: "METRICS".to_string(),
                             title: "Token usage snapshot".to_string(),
-                            body: Some(limit_text(&compact_json(&summary), 1200)),
+                            body: Some(limit.apply(&compact_json(&summary), 1200)),
                             source_type: "codex:event_msg.token_count".to_string(),
                             metadata: vec![],
                         });
@@ -590,10 +965,9 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                         ts: ts.clone(),
                         label: "EVENT".to_string(),
                         title: format!("Event: {}", ptype),
-                        body: Some(limit_text(
-                            &compact_json(&redact_record(payload.clone())),
-                            1200,
-                        )),
+                        body: Some(
+                            limit.apply(&compact_json(&redact_record(payload.clone())), 1200),
+                        ),
                         source_type: format!("codex:event_msg.{}", ptype),
                         metadata: vec![],
                     }),
@@ -612,7 +986,7 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
                     ts: ts.clone(),
                     label: "CONTEXT".to_string(),
                     title: "Turn context".to_string(),
-                    body: Some(limit_text(&compact_json(&summary), 1200)),
+                    body: Some(limit.apply(&compact_json(&summary), 1200)),
                     source_type: "codex:turn_context".to_string(),
                     metadata: vec![],
                 });
@@ -624,7 +998,10 @@ fn inspect_codex(session: &tracekit_core::CanonicalSession) -> Result<Vec<Inspec
     Ok(out)
 }
 
-fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<InspectEntry>> {
+fn inspect_opencode(
+    session: &tracekit_core::CanonicalSession,
+    limit: &TextLimit,
+) -> Result<Vec<InspectEntry>> {
     let mut out = Vec::new();
     let session_json = std::fs::read_to_string(&session.source_path)?;
     let session_value: Value = serde_json::from_str(&session_json).unwrap_or(Value::Null);
@@ -633,15 +1010,12 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
         ts: None,
         label: "SYSTEM".to_string(),
         title: "Session metadata".to_string(),
-        body: Some(limit_text(
-            &compact_json(&redact_record(session_value.clone())),
-            1400,
-        )),
+        body: Some(limit.apply(&compact_json(&redact_record(session_value.clone())), 1400)),
         source_type: "opencode:session".to_string(),
         metadata: vec![],
     });
 
-    let root = match session.source_path.ancestors().nth(3) {
+    let root = match ingest::opencode::storage_root(&session.source_path) {
         Some(p) => p.to_path_buf(),
         None => return Ok(out),
     };
@@ -734,7 +1108,7 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
                             "User text"
                         }
                         .to_string(),
-                        body: Some(limit_text(text, 8000)),
+                        body: Some(limit.apply(text, 8000)),
                         source_type: "opencode:part.text".to_string(),
                         metadata: vec![],
                     });
@@ -748,7 +1122,7 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
                         ts: p_ts,
                         label: "THINKING".to_string(),
                         title: "Assistant reasoning".to_string(),
-                        body: Some(limit_text(text, 8000)),
+                        body: Some(limit.apply(text, 8000)),
                         source_type: "opencode:part.reasoning".to_string(),
                         metadata: vec![],
                     });
@@ -771,7 +1145,7 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
                         ts: p_ts.clone(),
                         label: "TOOL_CALL".to_string(),
                         title: format!("Tool: {}", tool_name),
-                        body: Some(limit_text(&input, 2000)),
+                        body: Some(limit.apply(&input, 2000)),
                         source_type: "opencode:part.tool".to_string(),
                         metadata: vec![("status".to_string(), status.to_string())],
                     });
@@ -779,7 +1153,7 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
                         ts: p_ts,
                         label: "TOOL_RESULT".to_string(),
                         title: format!("Tool result: {}", tool_name),
-                        body: Some(limit_text(&output, 2000)),
+                        body: Some(limit.apply(&output, 2000)),
                         source_type: "opencode:part.tool".to_string(),
                         metadata: vec![("status".to_string(), status.to_string())],
                     });
@@ -794,19 +1168,31 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
                         ts: p_ts,
                         label: "METRICS".to_string(),
                         title: "Step finish".to_string(),
-                        body: Some(limit_text(&compact_json(&summary), 1200)),
+                        body: Some(limit.apply(&compact_json(&summary), 1200)),
                         source_type: "opencode:part.step-finish".to_string(),
                         metadata: vec![],
                     });
                 }
+                "patch" => {
+                    let files: Vec<&str> = part
+                        .get("files")
+                        .and_then(|v| v.as_array())
+                        .map(|arr| arr.iter().filter_map(|f| f.as_str()).collect())
+                        .unwrap_or_default();
+                    out.push(InspectEntry {
+                        ts: p_ts,
+                        label: "TOOL_CALL".to_string(),
+                        title: format!("Patch: {} file(s)", files.len()),
+                        body: Some(limit.apply(&files.join("\n"), 2000)),
+                        source_type: "opencode:part.patch".to_string(),
+                        metadata: vec![],
+                    });
+                }
                 _ => out.push(InspectEntry {
                     ts: p_ts,
                     label: "EVENT".to_string(),
                     title: format!("Part: {}", ptype),
-                    body: Some(limit_text(
-                        &compact_json(&redact_record(part.clone())),
-                        1200,
-                    )),
+                    body: Some(limit.apply(&compact_json(&redact_record(part.clone())), 1200)),
                     source_type: format!("opencode:part.{}", ptype),
                     metadata: vec![],
                 }),
@@ -817,7 +1203,11 @@ fn inspect_opencode(session: &tracekit_core::CanonicalSession) -> Result<Vec<Ins
     Ok(out)
 }
 
-fn inspect_generic_jsonl(path: &Path, agent_name: &str) -> Result<Vec<InspectEntry>> {
+fn inspect_generic_jsonl(
+    path: &Path,
+    agent_name: &str,
+    limit: &TextLimit,
+) -> Result<Vec<InspectEntry>> {
     let mut out = Vec::new();
     let content = std::fs::read_to_string(path)?;
     for line in content.lines() {
@@ -840,10 +1230,7 @@ fn inspect_generic_jsonl(path: &Path, agent_name: &str) -> Result<Vec<InspectEnt
             ts,
             label: "EVENT".to_string(),
             title: format!("{} record: {}", capitalize(agent_name), kind),
-            body: Some(limit_text(
-                &compact_json(&redact_record(record.clone())),
-                1500,
-            )),
+            body: Some(limit.apply(&compact_json(&redact_record(record.clone())), 1500)),
             source_type: format!("{}:{}", agent_name, kind),
             metadata: vec![],
         });
@@ -851,14 +1238,118 @@ fn inspect_generic_jsonl(path: &Path, agent_name: &str) -> Result<Vec<InspectEnt
     Ok(out)
 }
 
+/// The session's original trace records, one compact JSON object per string.
+///
+/// JSONL traces are passed through line by line. OpenCode's pretty-printed
+/// session, message and part files are collapsed onto one line each without
+/// being re-parsed, so field order and number formatting survive as written.
+fn raw_records(session: &tracekit_core::CanonicalSession, redact: bool) -> Result<Vec<String>> {
+    let mut records = Vec::new();
+    if session.source_agent == tracekit_core::Agent::Opencode {
+        records.push(std::fs::read_to_string(&session.source_path)?);
+        if let Some(root) = ingest::opencode::storage_root(&session.source_path) {
+            for msg_path in sorted_json_files(&root.join("message").join(&session.session_id))? {
+                let raw = match std::fs::read_to_string(&msg_path) {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                let message_id = serde_json::from_str::<Value>(&raw)
+                    .ok()
+                    .and_then(|v| v.get("id").and_then(|x| x.as_str()).map(String::from));
+                records.push(raw);
+                if let Some(id) = message_id {
+                    for part_path in sorted_json_files(&root.join("part").join(id))? {
+                        if let Ok(part) = std::fs::read_to_string(&part_path) {
+                            records.push(part);
+                        }
+                    }
+                }
+            }
+        }
+    } else {
+        let content = std::fs::read_to_string(&session.source_path)?;
+        records.extend(
+            content
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .map(String::from),
+        );
+    }
+
+    Ok(records
+        .into_iter()
+        .map(|raw| {
+            if !redact {
+                return collapse_json(&raw);
+            }
+            // Records that aren't valid JSON are kept as-is rather than dropped
+            match serde_json::from_str::<Value>(&raw) {
+                Ok(v) => compact_json(&redact_record(v)),
+                Err(_) => collapse_json(&raw),
+            }
+        })
+        .collect())
+}
+
+fn sorted_json_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|x| x.path()))
+        .filter(|p| p.extension().and_then(|x| x.to_str()) == Some("json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Strip whitespace outside string literals, putting a JSON document on one line.
+fn collapse_json(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for ch in raw.trim().chars() {
+        if in_string {
+            out.push(ch);
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+        } else if ch == '"' {
+            in_string = true;
+            out.push(ch);
+        } else if !ch.is_whitespace() {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Keep records numbered `from..=to` (1-based), mirroring inspect entry slicing.
+fn slice_records(records: Vec<String>, from: Option<usize>, to: Option<usize>) -> Vec<String> {
+    let start = from.unwrap_or(1).max(1) - 1;
+    let end = to.unwrap_or(usize::MAX);
+    records
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| *i >= start && *i < end)
+        .map(|(_, r)| r)
+        .collect()
+}
+
 fn transform_inspect_entries(entries: &[InspectEntry], mode: InspectMode) -> InspectRender {
     match mode {
-        InspectMode::Forensic => {
+        // Raw mode bypasses inspect entries; treated as forensic if it ever gets here
+        InspectMode::Forensic | InspectMode::Raw => {
             let rendered = entries.to_vec();
             let summary = build_summary(entries.len(), &rendered, 0, 0);
             InspectRender {
                 entries: rendered,
                 summary,
+                offset: 0,
             }
         }
         InspectMode::Analysis => {
@@ -886,6 +1377,7 @@ fn transform_inspect_entries(entries: &[InspectEntry], mode: InspectMode) -> Ins
             InspectRender {
                 entries: deduped,
                 summary,
+                offset: 0,
             }
         }
     }
@@ -905,6 +1397,8 @@ fn is_noise_entry(e: &InspectEntry) -> bool {
             | "codex:event_msg.user_message"
             | "claude:file-history-snapshot"
             | "claude:progress"
+            | "claude:system.hook"
+            | "claude:system.permission"
     )
 }
 
@@ -954,7 +1448,7 @@ fn build_summary(
     }
 
     let mut labels: Vec<(String, usize)> = label_counts.into_iter().collect();
-    labels.sort_by(|a, b| b.1.cmp(&a.1));
+    labels.sort_by_key(|l| std::cmp::Reverse(l.1));
 
     InspectSummary {
         raw_entries,
@@ -1018,6 +1512,9 @@ fn render_inspect_markdown(
         "- tools: calls=`{}`, results=`{}`, errors=`{}`\n",
         rendered.summary.tool_calls, rendered.summary.tool_results, rendered.summary.tool_errors
     ));
+    if let Some(size) = &session.system_prompt {
+        out.push_str(&format!("- system prompt: `{}`\n", fmt_system_prompt(size)));
+    }
     let labels = rendered
         .summary
         .labels
@@ -1030,7 +1527,7 @@ fn render_inspect_markdown(
     for (i, e) in rendered.entries.iter().enumerate() {
         out.push_str(&format!(
             "## {:04}. {} {}{}\n\n",
-            i + 1,
+            rendered.offset + i + 1,
             e.label,
             e.title,
             e.ts.as_ref()
@@ -1083,6 +1580,9 @@ fn print_inspect_terminal(
         "  Tools      : {} calls, {} results, {} errors",
         rendered.summary.tool_calls, rendered.summary.tool_results, rendered.summary.tool_errors
     );
+    if let Some(size) = &session.system_prompt {
+        println!("  Sys prompt : {}", fmt_system_prompt(size));
+    }
     println!();
 
     for (i, e) in rendered.entries.iter().enumerate() {
@@ -1093,13 +1593,14 @@ fn print_inspect_terminal(
             "TOOL_CALL" => e.label.yellow().bold(),
             "TOOL_RESULT" => e.label.yellow().bold(),
             "SYSTEM" | "CONTEXT" => e.label.cyan().bold(),
+            "HOOK" | "PERMISSION" => e.label.bright_cyan().bold(),
             "METRICS" => e.label.bright_black().bold(),
             _ => e.label.normal(),
         };
         let ts = e.ts.as_deref().unwrap_or("-").dimmed();
         println!(
             "{}  {}  {}  {}",
-            format!("[{:04}]", i + 1).dimmed(),
+            format!("[{:04}]", rendered.offset + i + 1).dimmed(),
             ts,
             tag,
             e.title.bold()
@@ -1130,10 +1631,11 @@ fn inspect_mode_str(mode: InspectMode) -> &'static str {
     match mode {
         InspectMode::Analysis => "analysis",
         InspectMode::Forensic => "forensic",
+        InspectMode::Raw => "raw",
     }
 }
 
-fn extract_codex_message_text(payload: &Value) -> Option<String> {
+fn extract_codex_message_text(payload: &Value, limit: &TextLimit) -> Option<String> {
     let arr = payload.get("content")?.as_array()?;
     let mut chunks = Vec::new();
     for item in arr {
@@ -1150,7 +1652,7 @@ fn extract_codex_message_text(payload: &Value) -> Option<String> {
     if chunks.is_empty() {
         None
     } else {
-        Some(limit_text(&chunks.join("\n"), 8000))
+        Some(limit.apply(&chunks.join("\n"), 8000))
     }
 }
 
@@ -1177,12 +1679,14 @@ fn extract_text(v: Option<&Value>) -> Option<String> {
     }
 }
 
-fn ms_to_iso(ms: u64) -> String {
+/// RFC 3339 time for an OpenCode `time` value (seconds or milliseconds).
+fn ms_to_iso(t: u64) -> String {
+    let ms = ingest::opencode::epoch_millis(t);
     let secs = (ms / 1000) as i64;
     let nsec = ((ms % 1000) * 1_000_000) as u32;
     chrono::DateTime::<chrono::Utc>::from_timestamp(secs, nsec)
-        .map(|t| t.to_rfc3339())
-        .unwrap_or_else(|| ms.to_string())
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| t.to_string())
 }
 
 fn capitalize(s: &str) -> String {
@@ -1225,29 +1729,105 @@ fn redact_in_place(v: &mut Value) {
                 redact_in_place(item);
             }
         }
-        Value::String(s) => {
-            if s.chars().count() > 1000 {
-                let mut truncated = String::new();
-                for ch in s.chars().take(999) {
-                    truncated.push(ch);
-                }
-                truncated.push('…');
-                *s = truncated;
+        Value::String(s) if s.chars().count() > 1000 => {
+            let mut truncated = String::new();
+            for ch in s.chars().take(999) {
+                truncated.push(ch);
             }
+            truncated.push('…');
+            *s = truncated;
         }
         _ => {}
     }
 }
 
-fn limit_text(s: &str, max: usize) -> String {
-    if s.chars().count() <= max {
-        s.to_string()
-    } else {
-        let mut out = String::new();
-        for ch in s.chars().take(max.saturating_sub(1)) {
-            out.push(ch);
+/// How inspect text is shortened once it exceeds an entry's size budget.
+#[derive(Debug, Clone)]
+struct TextLimit {
+    ellipsis: String,
+    word_boundary: bool,
+}
+
+impl TextLimit {
+    /// Truncate `s` to at most `max` chars, ellipsis included.
+    fn apply(&self, s: &str, max: usize) -> String {
+        if s.chars().count() <= max {
+            return s.to_string();
         }
-        out.push('…');
+        let keep = max.saturating_sub(self.ellipsis.chars().count());
+        let mut out: String = s.chars().take(keep).collect();
+        if self.word_boundary {
+            // A single unbroken word is cut mid-word rather than dropped entirely
+            if let Some(idx) = out.rfind(char::is_whitespace) {
+                if idx > 0 {
+                    out.truncate(idx);
+                    out.truncate(out.trim_end().len());
+                }
+            }
+        }
+        out.push_str(&self.ellipsis);
         out
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_mode_keeps_fields_analysis_mode_drops() {
+        let top = std::env::temp_dir().join(format!("tracekit-raw-{}", std::process::id()));
+        let storage = top.join("backups/laptop/storage");
+        let write = |rel: &str, content: &str| {
+            let path = storage.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "session/proj/ses_raw.json",
+            r#"{"id": "ses_raw", "time": {"created": 1700000000000}}"#,
+        );
+        write(
+            "message/ses_raw/msg_1.json",
+            r#"{"id": "msg_1", "role": "user", "time": {"created": 1700000000000}}"#,
+        );
+        write(
+            "part/msg_1/prt_1.json",
+            "{\n  \"id\": \"prt_1\",\n  \"type\": \"text\",\n  \"text\": \"hello\",\n  \"synthetic\": \"keep-me\"\n}\n",
+        );
+
+        // Found in a copied tree nested deeper than OpenCode's own layout
+        let session = ingest::opencode::discover_sessions_under(&top).remove(0);
+        let limit = TextLimit {
+            ellipsis: "…".to_string(),
+            word_boundary: false,
+        };
+        let entries = build_inspect_entries(&session, &limit, false).unwrap();
+        let analysis = transform_inspect_entries(&entries, InspectMode::Analysis);
+        assert!(analysis
+            .entries
+            .iter()
+            .any(|e| e.body.as_deref() == Some("hello")));
+        assert!(!format!("{:?}", analysis.entries).contains("keep-me"));
+
+        let raw = raw_records(&session, false).unwrap();
+        assert_eq!(raw.len(), 3);
+        // Collapsed onto one line, in the order the fields were written
+        assert_eq!(
+            raw[2],
+            r#"{"id":"prt_1","type":"text","text":"hello","synthetic":"keep-me"}"#
+        );
+
+        std::fs::remove_dir_all(top).unwrap();
+    }
+
+    #[test]
+    fn opencode_times_in_seconds_or_milliseconds_render_alike() {
+        assert_eq!(ms_to_iso(1_748_779_200), "2025-06-01T12:00:00+00:00");
+        assert_eq!(ms_to_iso(1_748_779_200_000), "2025-06-01T12:00:00+00:00");
+        assert_eq!(
+            ms_to_iso(1_748_779_200_500),
+            "2025-06-01T12:00:00.500+00:00"
+        );
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use colored::Colorize;
+use tracekit_core::FINDING_CATALOG;
+
+#[derive(Args)]
+pub struct FindingsArgs {
+    #[command(subcommand)]
+    pub subcommand: FindingsSubcommand,
+}
+
+#[derive(Subcommand)]
+pub enum FindingsSubcommand {
+    /// List every built-in finding kind with its threshold and remediation
+    List {
+        /// Output format: table, json
+        #[arg(long, default_value = "table")]
+        format: String,
+    },
+}
+
+pub fn run(args: FindingsArgs) -> Result<()> {
+    match args.subcommand {
+        FindingsSubcommand::List { format } => match format.as_str() {
+            "json" => {
+                let catalog: Vec<serde_json::Value> = FINDING_CATALOG
+                    .iter()
+                    .map(|info| {
+                        serde_json::json!({
+                            "kind": info.kind.as_str(),
+                            "label": info.kind.to_string(),
+                            "description": info.description,
+                            "default_confidence": info.default_confidence,
+                            "threshold": info.threshold,
+                            "remediation": info.kind.suggestion(),
+                        })
+                    })
+                    .collect();
+                println!("{}", serde_json::to_string_pretty(&catalog)?);
+            }
+            _ => {
+                for info in FINDING_CATALOG {
+                    println!(
+                        "{} {}",
+                        info.kind.to_string().red().bold(),
+                        format!("(conf {:.0}%)", info.default_confidence * 100.0).dimmed()
+                    );
+                    println!("  {}", info.description);
+                    println!("  {} {}", "when:".cyan(), info.threshold);
+                    println!("  {} {}\n", "fix:".green(), info.kind.suggestion());
+                }
+            }
+        },
+    }
+    Ok(())
+}
@@ -1,9 +1,13 @@
 use anyhow::Result;
 use clap::{Args, Subcommand};
-use tracekit_ingest as ingest;
+use colored::Colorize;
+use tracekit_core::{
+    detect_inefficiencies, efficiency_score, AnalysisResult, CanonicalSession, SessionKind,
+};
+use tracekit_ingest::{self as ingest, DiscoveryFilter};
 use tracekit_report::terminal;
 
-use super::{parse_agents, parse_datetime};
+use super::{parse_agents, parse_datetime, parse_session, retain_tagged, retain_titled, AssumeCwd};
 
 #[derive(Args)]
 pub struct ListArgs {
@@ -31,6 +35,23 @@ pub enum ListSubcommand {
         #[arg(long)]
         cwd: Option<String>,
 
+        /// For sessions that don't record a CWD, infer it from the session's
+        /// storage path (path) or the enclosing git repository (git)
+        #[arg(long, value_enum)]
+        assume_cwd_from: Option<AssumeCwd>,
+
+        /// Only sessions with this tag, e.g. repo:tracekit (repeat to require several)
+        #[arg(long)]
+        tag: Vec<String>,
+
+        /// Only sessions whose title contains this substring (case-insensitive)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Title untitled sessions (Claude, Codex) after their first user prompt
+        #[arg(long)]
+        title_from_prompt: bool,
+
         /// Filter by model ID substring
         #[arg(long)]
         model_id: Option<String>,
@@ -39,13 +60,27 @@ pub enum ListSubcommand {
         #[arg(long)]
         limit: Option<usize>,
 
-        /// Sort by: date (default), cost, messages, agent
+        /// Skip sessions with fewer than N messages (use 0 to include everything)
+        #[arg(long, default_value_t = ingest::DEFAULT_MIN_MESSAGES)]
+        min_messages: usize,
+
+        /// Sort by: date (default), cost, messages, agent, efficiency (least efficient
+        /// first; analyzes every session, so slower)
         #[arg(long, default_value = "date")]
         sort: String,
 
         /// Output format: table, json
         #[arg(long, default_value = "table")]
         format: String,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// With --format json, wrap the sessions in an object with count, total cost,
+        /// per-agent totals and date range
+        #[arg(long)]
+        summary: bool,
     },
 }
 
@@ -56,23 +91,41 @@ pub fn run(args: ListArgs) -> Result<()> {
             since,
             until,
             cwd,
+            assume_cwd_from,
+            tag,
+            title,
+            title_from_prompt,
             model_id,
             limit,
+            min_messages,
             sort,
             format,
+            redact_paths,
+            summary,
         } => {
             let agents = parse_agents(&agent)?;
             let since_dt = since.as_deref().map(parse_datetime).transpose()?;
             let until_dt = until.as_deref().map(parse_datetime).transpose()?;
 
+            // Limit is applied after sorting
             let mut sessions = ingest::discover_sessions(
                 &agents,
-                since_dt,
-                until_dt,
-                cwd.as_deref(),
-                None, // apply limit after sort
+                &DiscoveryFilter {
+                    since: since_dt,
+                    until: until_dt,
+                    cwd,
+                    assume_cwd: assume_cwd_from.map(Into::into),
+                    min_messages,
+                    limit: None,
+                },
             )?;
 
+            retain_tagged(&mut sessions, &tag);
+            if title_from_prompt {
+                ingest::title_from_prompt(&mut sessions);
+            }
+            retain_titled(&mut sessions, title.as_deref());
+
             // Model filter (post-discovery)
             if let Some(mid) = &model_id {
                 let mid_lower = mid.to_lowercase();
@@ -87,7 +140,7 @@ pub fn run(args: ListArgs) -> Result<()> {
             // Sort
             match sort.as_str() {
                 "messages" | "msgs" => {
-                    sessions.sort_by(|a, b| b.message_count.cmp(&a.message_count));
+                    sessions.sort_by_key(|s| std::cmp::Reverse(s.message_count));
                 }
                 "cost" => {
                     sessions.sort_by(|a, b| {
@@ -102,6 +155,11 @@ pub fn run(args: ListArgs) -> Result<()> {
                         a.source_agent.to_string().cmp(&b.source_agent.to_string())
                     });
                 }
+                "efficiency" => {
+                    score_sessions(&mut sessions);
+                    // Unscored (unparseable) sessions last
+                    sessions.sort_by_key(|s| (s.efficiency_score.is_none(), s.efficiency_score));
+                }
                 _ => {} // "date" — already sorted newest-first by discover_sessions
             }
 
@@ -109,7 +167,19 @@ pub fn run(args: ListArgs) -> Result<()> {
                 sessions.truncate(n);
             }
 
+            if let Some(mode) = redact_paths {
+                for s in &mut sessions {
+                    ingest::redact_session_paths(s, mode);
+                }
+            }
+
             match format.as_str() {
+                "json" if summary => {
+                    println!(
+                        "{}",
+                        tracekit_report::json::render_session_list_summary(&sessions)?
+                    );
+                }
                 "json" => {
                     println!("{}", tracekit_report::json::render_session_list(&sessions)?);
                 }
@@ -121,3 +191,24 @@ pub fn run(args: ListArgs) -> Result<()> {
     }
     Ok(())
 }
+
+/// Analyze each session to fill in its efficiency score. Sessions that fail to
+/// parse, or parse to nothing, are left unscored.
+fn score_sessions(sessions: &mut [CanonicalSession]) {
+    eprintln!("{} Scoring {} sessions...", "→".cyan(), sessions.len());
+    for s in sessions {
+        match parse_session(s, None) {
+            Ok(parsed) if parsed.session.is_empty() => {}
+            Ok(parsed) => {
+                let result = AnalysisResult {
+                    findings: detect_inefficiencies(&parsed),
+                    session_kind: SessionKind::of(&parsed),
+                    session: parsed.session,
+                    top_expensive_messages: Vec::new(),
+                };
+                s.efficiency_score = Some(efficiency_score(&result));
+            }
+            Err(e) => eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e),
+        }
+    }
+}
@@ -1,10 +1,375 @@
 pub mod analyze;
 pub mod capture;
+pub mod findings;
 pub mod list;
 pub mod report;
 
 use anyhow::Result;
-use tracekit_core::Agent;
+use clap::ValueEnum;
+use colored::Colorize;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracekit_core::{
+    detect_inefficiencies, top_expensive_messages, Agent, AnalysisResult, CanonicalSession,
+    ParsedSession, Role, SessionKind,
+};
+use tracekit_ingest::{CwdSource, PathRedaction};
+
+/// Breakdown for `report aggregate --group-by`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum GroupBy {
+    Day,
+    Week,
+    /// Model family (opus, sonnet, gpt-4o, ...) regardless of version string
+    ModelFamily,
+    /// Every tag, including ones added with `capture tag`
+    Tag,
+}
+
+/// Ordering of the expensive-turns list for `--rank`.
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum Rank {
+    /// Most expensive turns first
+    #[default]
+    Cost,
+    /// Cost weighted by the confidence of findings that cite the turn
+    Actionable,
+}
+
+/// Where `--assume-cwd-from` infers a missing session CWD.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum AssumeCwd {
+    /// The project directory encoded in the session file's path (Claude)
+    Path,
+    /// The nearest enclosing git repository root
+    Git,
+}
+
+impl From<AssumeCwd> for CwdSource {
+    fn from(a: AssumeCwd) -> Self {
+        match a {
+            AssumeCwd::Path => CwdSource::Path,
+            AssumeCwd::Git => CwdSource::Git,
+        }
+    }
+}
+
+/// Clap value parser for path options: expands a leading `~` and `$VAR` /
+/// `${VAR}` references, so quoted paths behave as they would unquoted in a shell.
+pub fn expand_path(raw: &str) -> Result<PathBuf, String> {
+    let mut out = String::new();
+    let mut rest = raw;
+    if rest == "~" || rest.starts_with("~/") {
+        let home = dirs::home_dir().ok_or("cannot expand `~`: no home directory")?;
+        out.push_str(&home.to_string_lossy());
+        rest = &rest[1..];
+    }
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let (name, tail) = match after.strip_prefix('{') {
+            Some(braced) => {
+                let end = braced
+                    .find('}')
+                    .ok_or_else(|| format!("unterminated `${{` in {}", raw))?;
+                (&braced[..end], &braced[end + 1..])
+            }
+            None => {
+                let end = after
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(after.len());
+                (&after[..end], &after[end..])
+            }
+        };
+        if name.is_empty() {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let value = std::env::var(name)
+            .map_err(|_| format!("environment variable `{}` is not set", name))?;
+        out.push_str(&value);
+        rest = tail;
+    }
+    out.push_str(rest);
+    Ok(PathBuf::from(out))
+}
+
+/// Write a report file, echoing the resolved path and size. With `dry_run`,
+/// only report what would be written.
+pub fn write_output(path: &Path, content: &str, dry_run: bool) -> Result<()> {
+    let resolved = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    if dry_run {
+        eprintln!(
+            "{} Would write {} bytes to {} (dry run)",
+            "→".cyan(),
+            content.len(),
+            resolved.display()
+        );
+    } else {
+        std::fs::write(path, content)?;
+        eprintln!(
+            "{} Written to {} ({} bytes)",
+            "✓".green(),
+            resolved.display(),
+            content.len()
+        );
+    }
+    Ok(())
+}
+
+/// Print the files discovery skipped, and why, for `--explain-discovery`.
+pub fn print_discovery_skips() {
+    let skipped = tracekit_ingest::take_skipped();
+    if skipped.is_empty() {
+        eprintln!("{} Discovery skipped no files", "✓".green());
+        return;
+    }
+    eprintln!(
+        "{} Discovery skipped {} file{}:",
+        "→".cyan(),
+        skipped.len(),
+        if skipped.len() == 1 { "" } else { "s" }
+    );
+    for s in &skipped {
+        eprintln!(
+            "  {:<8}  {}  {}",
+            s.agent.to_string().cyan(),
+            s.path.display(),
+            s.reason.dimmed()
+        );
+    }
+}
+
+/// Set in the re-run child so it prints directly instead of paging again.
+const NO_PAGE_ENV: &str = "TRACEKIT_NO_PAGE";
+
+/// For `--page`: re-run this command with stdout piped through `$PAGER`
+/// (default `less -R`). Returns true if the pager showed the output, false if
+/// the caller should print directly (stdout isn't a terminal, or no pager).
+pub fn run_paged(page: bool) -> Result<bool> {
+    if !page || std::env::var_os(NO_PAGE_ENV).is_some() || !std::io::stdout().is_terminal() {
+        return Ok(false);
+    }
+
+    let mut cmd = Command::new(std::env::current_exe()?);
+    cmd.args(std::env::args_os().skip(1))
+        .env(NO_PAGE_ENV, "1")
+        .stdout(Stdio::piped());
+    // Keep colors through the pipe unless the user turned them off
+    if std::env::var_os("NO_COLOR").is_none() {
+        cmd.env("CLICOLOR_FORCE", "1");
+    }
+    let mut child = cmd.spawn()?;
+    let Some(child_out) = child.stdout.take() else {
+        return Ok(false);
+    };
+
+    let pager = std::env::var("PAGER")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or_else(|| "less -R".to_string());
+    let mut pager_cmd = if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.args(["/C", &pager]);
+        c
+    } else {
+        let mut c = Command::new("sh");
+        c.args(["-c", &pager]);
+        c
+    };
+
+    match pager_cmd.stdin(Stdio::from(child_out)).spawn() {
+        Ok(mut p) => {
+            p.wait()?;
+            // The user may quit the pager before all output was read
+            let _ = child.kill();
+            let _ = child.wait();
+            Ok(true)
+        }
+        Err(e) => {
+            eprintln!(
+                "  {} Could not start pager '{}': {}",
+                "!".yellow(),
+                pager,
+                e
+            );
+            let _ = child.kill();
+            let _ = child.wait();
+            Ok(false)
+        }
+    }
+}
+
+/// Keep sessions carrying every tag in `tags` (`--tag repo:foo --tag branch:main`).
+pub fn retain_tagged(sessions: &mut Vec<CanonicalSession>, tags: &[String]) {
+    if !tags.is_empty() {
+        sessions.retain(|s| tags.iter().all(|t| s.tags.contains(t)));
+    }
+}
+
+/// Keep sessions whose title contains `title` (case-insensitive); untitled
+/// sessions never match.
+pub fn retain_titled(sessions: &mut Vec<CanonicalSession>, title: Option<&str>) {
+    if let Some(needle) = title.map(str::to_lowercase) {
+        sessions.retain(|s| {
+            s.title
+                .as_deref()
+                .is_some_and(|t| t.to_lowercase().contains(&needle))
+        });
+    }
+}
+
+/// Apply `--count-roles` (e.g. `user,assistant`): redefine `message_count` as the
+/// number of messages with those roles, so counts compare across agents.
+pub fn apply_role_counts(results: &mut [AnalysisResult], count_roles: Option<&str>) -> Result<()> {
+    let Some(spec) = count_roles else {
+        return Ok(());
+    };
+    let roles = spec
+        .split(',')
+        .map(|r| r.trim().parse::<Role>())
+        .collect::<Result<Vec<_>>>()?;
+    for r in results {
+        r.session.message_count = r.session.role_count(&roles);
+    }
+    Ok(())
+}
+
+/// Apply `--redact-paths` (if given) to a batch of analysis results.
+pub fn redact_results(results: &mut [AnalysisResult], redact: Option<PathRedaction>) {
+    if let Some(mode) = redact {
+        for r in results {
+            tracekit_ingest::redact_analysis_paths(r, mode);
+        }
+    }
+}
+
+/// Apply `--pseudonymize`: alias every session ID in the results, printing the
+/// mapping to stderr only so it never ends up in the report itself.
+pub fn apply_pseudonyms(results: &mut [AnalysisResult], enabled: bool) {
+    if !enabled {
+        return;
+    }
+    let mapping = tracekit_ingest::pseudonymize_results(results);
+    eprintln!(
+        "{} Session aliases (not included in the report):",
+        "→".cyan()
+    );
+    for (alias, real) in mapping {
+        eprintln!("  {}  {}", alias, real);
+    }
+}
+
+/// Characters per token for text-length estimates when `--estimate-tokens` isn't set.
+pub const DEFAULT_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Parse a session (from the parse cache when `capture import` has stored an
+/// up-to-date copy), filling heuristic usage for messages without recorded usage
+/// when `--estimate-tokens` is set (value is characters per token).
+pub fn parse_session(
+    session: &CanonicalSession,
+    estimate_tokens: Option<f64>,
+) -> Result<ParsedSession> {
+    let mut parsed = tracekit_ingest::cache::parse_session_cached(session)?;
+    if let Some(chars_per_token) = estimate_tokens {
+        parsed.estimate_missing_usage(chars_per_token);
+    }
+    Ok(parsed)
+}
+
+/// Parse and analyze each session, listing its `top_n` most expensive turns. A
+/// session that fails to parse is warned about and kept as an empty result (no
+/// messages), so `analyze` and `report` count it the same way: marked empty and
+/// left out of averages, rather than dropped or mistaken for a clean session.
+///
+/// Sessions are split across one worker thread per core; results keep the
+/// order of `sessions`. OpenCode reads are capped by `--max-open-files`.
+pub fn analyze_sessions(
+    sessions: &[CanonicalSession],
+    estimate_tokens: Option<f64>,
+    top_n: usize,
+) -> Vec<AnalysisResult> {
+    let workers = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(sessions.len())
+        .max(1);
+    let per_worker = sessions.len().div_ceil(workers).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = sessions
+            .chunks(per_worker)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|s| analyze_or_mark_empty(s, estimate_tokens, top_n))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("session analysis thread panicked"))
+            .collect()
+    })
+}
+
+fn analyze_or_mark_empty(
+    s: &CanonicalSession,
+    estimate_tokens: Option<f64>,
+    top_n: usize,
+) -> AnalysisResult {
+    let parsed = match parse_session(s, estimate_tokens) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e);
+            let mut session = s.clone();
+            session.message_count = 0;
+            return AnalysisResult {
+                session,
+                session_kind: SessionKind::default(),
+                findings: Vec::new(),
+                top_expensive_messages: Vec::new(),
+            };
+        }
+    };
+    AnalysisResult {
+        session_kind: SessionKind::of(&parsed),
+        findings: detect_inefficiencies(&parsed),
+        top_expensive_messages: top_expensive_messages(&parsed, top_n),
+        session: parsed.session,
+    }
+}
+
+/// First 8 characters of a session id, for progress lines (char-safe, so a
+/// multibyte id never splits mid-character).
+pub fn short_id(session_id: &str) -> &str {
+    match session_id.char_indices().nth(8) {
+        Some((i, _)) => &session_id[..i],
+        None => session_id,
+    }
+}
+
+/// Default report file name for a session: the short id plus a hash of the full
+/// id, so sessions sharing an 8-character prefix never overwrite each other.
+pub fn report_file_name(session_id: &str, ext: &str) -> String {
+    // FNV-1a: stable across runs and Rust versions, unlike `DefaultHasher`
+    let hash = session_id.bytes().fold(0x811c_9dc5u32, |h, b| {
+        (h ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    let prefix: String = short_id(session_id)
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("report-{}-{:08x}.{}", prefix, hash, ext)
+}
 
 /// Parse an agent filter string into a list of agents.
 pub fn parse_agents(agent: &str) -> Result<Vec<Agent>> {
@@ -27,3 +392,102 @@ pub fn parse_datetime(s: &str) -> Result<chrono::DateTime<chrono::Utc>> {
         })
         .map_err(|e: anyhow::Error| e)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_names_differ_for_ids_sharing_a_prefix() {
+        let a = report_file_name("ses_4a1b2c3d0001", "html");
+        let b = report_file_name("ses_4a1b2c3d0002", "html");
+        assert!(a.starts_with("report-ses_4a1b-"), "{}", a);
+        assert!(b.starts_with("report-ses_4a1b-"), "{}", b);
+        assert_ne!(a, b);
+        assert_eq!(a, report_file_name("ses_4a1b2c3d0001", "html"));
+    }
+
+    #[test]
+    fn multibyte_ids_are_cut_on_char_boundaries() {
+        assert_eq!(short_id("séance-été-2026"), "séance-é");
+        assert_eq!(short_id("日本"), "日本");
+        // Characters outside the safe set become underscores, letters stay
+        let name = report_file_name("séance/été 2026", "json");
+        assert!(name.starts_with("report-séance_é-"), "{}", name);
+        assert!(name.ends_with(".json"));
+    }
+
+    #[test]
+    fn paths_expand_tilde_and_env_vars() {
+        let home = std::env::var("HOME").unwrap();
+        let expand = |raw| expand_path(raw).unwrap();
+        let home_dir = dirs::home_dir().unwrap();
+        assert_eq!(expand("~/foo"), home_dir.join("foo"));
+        assert_eq!(expand("~"), home_dir);
+        assert_eq!(expand("$HOME/bar"), PathBuf::from(format!("{}/bar", home)));
+        assert_eq!(expand("${HOME}bar"), PathBuf::from(format!("{}bar", home)));
+        // Only a leading `~` means home, and a lone `$` is kept as is
+        assert_eq!(expand("a/~/b$"), PathBuf::from("a/~/b$"));
+        assert!(expand_path("$TRACEKIT_TEST_SURELY_UNSET/x").is_err());
+    }
+
+    #[test]
+    fn title_filter_matches_substrings_and_skips_untitled() {
+        let session = |id: &str, title: Option<&str>| -> CanonicalSession {
+            serde_json::from_value(serde_json::json!({
+                "session_id": id,
+                "source_agent": "opencode",
+                "source_path": format!("/tmp/{}.json", id),
+                "cwd": null,
+                "title": title,
+                "started_at": null,
+                "ended_at": null,
+                "model": null,
+                "message_count": 4,
+                "total_cost_usd": null,
+                "total_input_tokens": 0,
+                "total_output_tokens": 0,
+            }))
+            .unwrap()
+        };
+        let all = vec![
+            session("a", Some("Fix flaky Parser tests")),
+            session("b", Some("Add pricing table")),
+            session("c", None),
+        ];
+
+        let mut matched = all.clone();
+        retain_titled(&mut matched, Some("parser"));
+        let ids: Vec<&str> = matched.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(ids, ["a"]);
+
+        let mut unfiltered = all.clone();
+        retain_titled(&mut unfiltered, None);
+        assert_eq!(unfiltered.len(), 3);
+    }
+
+    #[test]
+    fn unparseable_sessions_are_kept_as_empty_results() {
+        let session: CanonicalSession = serde_json::from_value(serde_json::json!({
+            "session_id": "gone",
+            "source_agent": "claude",
+            "source_path": "/tmp/tracekit-surely-missing/gone.jsonl",
+            "cwd": null,
+            "title": null,
+            "started_at": null,
+            "ended_at": null,
+            "model": null,
+            "message_count": 4,
+            "total_cost_usd": null,
+            "total_input_tokens": 0,
+            "total_output_tokens": 0,
+        }))
+        .unwrap();
+
+        let results = analyze_sessions(&[session], None, 3);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].session.session_id, "gone");
+        assert_eq!(results[0].session.message_count, 0);
+        assert!(results[0].findings.is_empty());
+    }
+}
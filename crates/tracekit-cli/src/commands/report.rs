@@ -1,12 +1,23 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Args, Subcommand};
 use colored::Colorize;
-use std::path::PathBuf;
-use tracekit_core::{detect_inefficiencies, top_expensive_messages, AnalysisResult};
-use tracekit_ingest as ingest;
-use tracekit_report::{html as html_report, json as jreport, terminal};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use tracekit_core::{
+    detect_inefficiencies, efficiency_score, top_actionable_turns, top_expensive_messages,
+    AnalysisResult, PricingValidation, SessionKind,
+};
+use tracekit_ingest::{self as ingest, DiscoveryFilter};
+use tracekit_report::tools::{ToolCallWriter, ToolExportFormat};
+use tracekit_report::{
+    html as html_report, json as jreport, sarif, terminal, toml as treport, TimeBucket,
+};
 
-use super::{parse_agents, parse_datetime};
+use super::{
+    analyze_sessions, apply_pseudonyms, apply_role_counts, expand_path, parse_agents,
+    parse_datetime, parse_session, redact_results, retain_tagged, retain_titled, run_paged,
+    short_id, write_output, GroupBy, Rank,
+};
 
 #[derive(Args)]
 pub struct ReportArgs {
@@ -26,13 +37,65 @@ pub enum ReportSubcommand {
         #[arg(long, default_value = "all")]
         agent: String,
 
-        /// Output format: table, json, html
+        /// Output format: table, json, toml, sarif, html
         #[arg(long, default_value = "table")]
         format: String,
 
-        /// Output file (defaults to stdout for table/json, report.html for html)
-        #[arg(long)]
+        /// Output file (defaults to stdout for table/json/toml, report.html for html)
+        #[arg(long, value_parser = expand_path)]
         out: Option<PathBuf>,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// Replace session IDs with stable aliases (S-001, ...); the mapping goes to stderr
+        #[arg(long)]
+        pseudonymize: bool,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+
+        /// Report output-side (generation) cost only, excluding input and cache
+        #[arg(long)]
+        output_tokens_only: bool,
+
+        /// Scale each finding's waste by its confidence (expected rather than maximal waste)
+        #[arg(long)]
+        confidence_weighted_waste: bool,
+
+        /// Compute the report but only print where it would be written
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Open the HTML report in the default browser after writing it
+        #[arg(long)]
+        open: bool,
+
+        /// Roles counted as messages, comma-separated (user, assistant, system; default all)
+        #[arg(long)]
+        count_roles: Option<String>,
+
+        /// How many of the most expensive turns to list
+        #[arg(long, visible_alias = "top-turns", default_value = "10")]
+        top: usize,
+
+        /// Only list turns costing more than this many USD
+        #[arg(long)]
+        over: Option<f64>,
+
+        /// Order the turn list by cost, or by cost weighted by the findings citing each turn
+        #[arg(long, value_enum, default_value = "cost")]
+        rank: Rank,
+
+        /// Also write the JSON report here, from the same analysis
+        #[arg(long, value_parser = expand_path)]
+        also_json: Option<PathBuf>,
+
+        /// Also write the HTML report here, from the same analysis
+        #[arg(long, value_parser = expand_path)]
+        also_html: Option<PathBuf>,
     },
 
     /// Generate an aggregate report across multiple sessions
@@ -49,21 +112,284 @@ pub enum ReportSubcommand {
         #[arg(long)]
         until: Option<String>,
 
-        /// Output format: table, json, html
+        /// Output format: table, json, jsonl (one analysis per line), sarif, html,
+        /// or tools-json / tools-csv (one row per tool call instead of a report)
         #[arg(long, default_value = "table")]
         format: String,
 
         /// Output file
-        #[arg(long)]
+        #[arg(long, value_parser = expand_path)]
         out: Option<PathBuf>,
 
         /// Limit number of sessions included
         #[arg(long)]
         limit: Option<usize>,
+
+        /// Analyze a random N of the matching sessions and scale totals up to
+        /// estimates for all of them
+        #[arg(long, value_name = "N")]
+        sample: Option<usize>,
+
+        /// Seed for --sample; the same seed over the same sessions picks the same sample
+        #[arg(long, default_value_t = 0, requires = "sample")]
+        seed: u64,
+
+        /// Skip sessions with fewer than N messages (use 0 to include everything)
+        #[arg(long, default_value_t = ingest::DEFAULT_MIN_MESSAGES)]
+        min_messages: usize,
+
+        /// Only sessions with this tag, e.g. repo:tracekit (repeat to require several)
+        #[arg(long)]
+        tag: Vec<String>,
+
+        /// Only sessions whose title contains this substring (case-insensitive)
+        #[arg(long)]
+        title: Option<String>,
+
+        /// Title untitled sessions (Claude, Codex) after their first user prompt
+        #[arg(long)]
+        title_from_prompt: bool,
+
+        /// Redact paths in output: home (collapse $HOME to ~) or full (file names only)
+        #[arg(long, num_args = 0..=1, default_missing_value = "home")]
+        redact_paths: Option<ingest::PathRedaction>,
+
+        /// Replace session IDs with stable aliases (S-001, ...); the mapping goes to stderr
+        #[arg(long)]
+        pseudonymize: bool,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+
+        /// Report output-side (generation) cost only, excluding input and cache
+        #[arg(long)]
+        output_tokens_only: bool,
+
+        /// Scale each finding's waste by its confidence (expected rather than maximal waste)
+        #[arg(long)]
+        confidence_weighted_waste: bool,
+
+        /// Compute the report but only print where it would be written
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Open the HTML report in the default browser after writing it
+        #[arg(long)]
+        open: bool,
+
+        /// With --format html, add an in-page search box that filters sessions
+        #[arg(long)]
+        interactive: bool,
+
+        /// With --format html, embed each session's findings in a collapsible row
+        #[arg(long)]
+        inline_sessions: bool,
+
+        /// With table output, chart cost per day or week, or split it by model family or tag
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+
+        /// With table output, also show cost per 1k messages, to compare sessions of
+        /// different lengths
+        #[arg(long)]
+        normalize_costs: bool,
+
+        /// Order sessions: date (default) or efficiency (least efficient first; table
+        /// output adds a ranking)
+        #[arg(long, default_value = "date")]
+        sort: String,
+
+        /// Also write the JSON report here, from the same analysis
+        #[arg(long, value_parser = expand_path)]
+        also_json: Option<PathBuf>,
+
+        /// Also write the HTML report here, from the same analysis
+        #[arg(long, value_parser = expand_path)]
+        also_html: Option<PathBuf>,
+
+        /// Roles counted as messages, comma-separated (user, assistant, system; default all)
+        #[arg(long)]
+        count_roles: Option<String>,
+        /// Show table output through $PAGER (default `less -R`) when on a terminal
+        #[arg(long)]
+        page: bool,
+
+        /// Instead of a report, compare estimated with observed per-turn costs (OpenCode)
+        /// and flag models whose estimates are off by more than PCT percent (default 10)
+        #[arg(long, value_name = "PCT", num_args = 0..=1, default_missing_value = "10")]
+        validate_pricing: Option<f64>,
+    },
+    /// Compare aggregate stats for sessions before one date and after another,
+    /// e.g. to see whether a workflow change on March 1 paid off
+    Compare {
+        /// Agent filter
+        #[arg(long, default_value = "all")]
+        agent: String,
+
+        /// The earlier window: sessions before this time
+        #[arg(long)]
+        before: String,
+
+        /// The later window: sessions after this time (often the same as --before)
+        #[arg(long)]
+        after: String,
+
+        /// Output format: table, json
+        #[arg(long, default_value = "table")]
+        format: String,
+
+        /// Skip sessions with fewer than N messages (use 0 to include everything)
+        #[arg(long, default_value_t = ingest::DEFAULT_MIN_MESSAGES)]
+        min_messages: usize,
+
+        /// Estimate tokens from text length where usage is missing (chars per token, default 4)
+        #[arg(long, num_args = 0..=1, default_missing_value = "4")]
+        estimate_tokens: Option<f64>,
+    },
+    /// Re-render analyses exported with `report aggregate --format jsonl`,
+    /// without re-parsing the source traces
+    FromJsonl {
+        /// JSON Lines file of exported analysis results (one per line)
+        #[arg(long, value_parser = expand_path)]
+        path: PathBuf,
+
+        /// Render one session's report (prefix match) instead of the aggregate
+        #[arg(long)]
+        session_id: Option<String>,
+
+        /// Output format: table, json, jsonl, sarif, html (toml with --session-id)
+        #[arg(long, default_value = "table")]
+        format: String,
+
+        /// Output file
+        #[arg(long, value_parser = expand_path)]
+        out: Option<PathBuf>,
+
+        /// Compute the report but only print where it would be written
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Open the HTML report in the default browser after writing it
+        #[arg(long)]
+        open: bool,
+
+        /// With --format html, add an in-page search box that filters sessions
+        #[arg(long)]
+        interactive: bool,
+
+        /// With --format html, embed each session's findings in a collapsible row
+        #[arg(long)]
+        inline_sessions: bool,
+
+        /// Replace session IDs with stable aliases (S-001, ...); the mapping goes to stderr
+        #[arg(long)]
+        pseudonymize: bool,
     },
 }
 
-fn analyze_one(session_id: &str, agent: &str) -> Result<AnalysisResult> {
+/// Compare observed and estimated cost for every turn that records both.
+fn check_pricing(
+    sessions: &[tracekit_core::CanonicalSession],
+    threshold_pct: f64,
+    format: &str,
+) -> Result<()> {
+    eprintln!(
+        "{} Checking pricing against {} sessions...",
+        "→".cyan(),
+        sessions.len()
+    );
+    let mut validation = PricingValidation::default();
+    for s in sessions {
+        match parse_session(s, None) {
+            Ok(parsed) => {
+                for m in &parsed.messages {
+                    if let (Some(model), Some(usage)) = (&m.model, &m.usage) {
+                        validation.add(model, usage);
+                    }
+                }
+            }
+            Err(e) => eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e),
+        }
+    }
+    let checks = validation.checks();
+    if format == "json" {
+        let out = serde_json::json!({
+            "threshold_pct": threshold_pct,
+            "models": checks,
+            "diverging": checks
+                .iter()
+                .filter(|c| c.diverges(threshold_pct))
+                .map(|c| &c.model)
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+    } else {
+        terminal::print_pricing_checks(&checks, threshold_pct);
+    }
+    Ok(())
+}
+
+/// Write one row per tool call across `sessions`, parsing one session at a time.
+fn export_tool_calls(
+    sessions: &[tracekit_core::CanonicalSession],
+    estimate_tokens: Option<f64>,
+    format: ToolExportFormat,
+    out: Option<&Path>,
+    dry_run: bool,
+) -> Result<()> {
+    if dry_run {
+        let target = out.map_or_else(|| "stdout".to_string(), |p| p.display().to_string());
+        eprintln!(
+            "{} Would write tool calls from {} sessions to {} (dry run)",
+            "→".cyan(),
+            sessions.len(),
+            target
+        );
+        return Ok(());
+    }
+    let sink: Box<dyn std::io::Write> = match out {
+        Some(path) => Box::new(std::io::BufWriter::new(
+            std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path.display()))?,
+        )),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    eprintln!(
+        "{} Exporting tool calls from {} sessions...",
+        "→".cyan(),
+        sessions.len()
+    );
+    let mut writer = ToolCallWriter::new(sink, format)?;
+    for s in sessions {
+        match parse_session(s, estimate_tokens) {
+            Ok(parsed) => writer.write_session(&parsed)?,
+            Err(e) => eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e),
+        }
+    }
+    let rows = writer.finish()?;
+    match out {
+        Some(path) => eprintln!(
+            "{} Written {} tool calls to {}",
+            "✓".green(),
+            rows,
+            std::path::absolute(path)
+                .unwrap_or_else(|_| path.to_path_buf())
+                .display()
+        ),
+        None => eprintln!("{} {} tool calls", "✓".green(), rows),
+    }
+    Ok(())
+}
+
+fn analyze_one(
+    session_id: &str,
+    agent: &str,
+    estimate_tokens: Option<f64>,
+    top_n: usize,
+    over: Option<f64>,
+    rank: Rank,
+) -> Result<AnalysisResult> {
     let agents = parse_agents(agent)?;
     let session = ingest::find_session(session_id, &agents)?
         .ok_or_else(|| anyhow::anyhow!("No session found matching '{}'", session_id))?;
@@ -71,34 +397,94 @@ fn analyze_one(session_id: &str, agent: &str) -> Result<AnalysisResult> {
     eprintln!(
         "{} Parsing {}...",
         "→".cyan(),
-        &session.session_id[..8.min(session.session_id.len())]
+        short_id(&session.session_id)
     );
-    let parsed = ingest::parse_session(&session)?;
+    let parsed = parse_session(&session, estimate_tokens)?;
     let findings = detect_inefficiencies(&parsed);
-    let top = top_expensive_messages(&parsed, 10);
+    let mut top = match rank {
+        Rank::Cost => top_expensive_messages(&parsed, top_n),
+        Rank::Actionable => top_actionable_turns(&parsed, &findings, top_n),
+    };
+    if let Some(min) = over {
+        top.retain(|m| m.cost_usd > min);
+    }
 
     Ok(AnalysisResult {
+        session_kind: SessionKind::of(&parsed),
         session: parsed.session,
         findings,
         top_expensive_messages: top,
     })
 }
 
-fn write_or_print(content: &str, out: Option<&PathBuf>, default_file: &str) -> Result<()> {
-    match out {
-        Some(path) => {
-            std::fs::write(path, content)?;
-            eprintln!("{} Written to {}", "✓".green(), path.display());
+/// Replace each session's total cost with its output-side component, scaling
+/// finding waste by the same share so Waste % still compares like with like.
+/// Sessions whose models have no known price are left without a cost or waste.
+fn apply_output_cost_view(results: &mut [AnalysisResult]) {
+    eprintln!("{} Cost view: output-side only", "→".cyan());
+    for r in results {
+        let output = r.session.cost_breakdown.map(|b| b.output_usd);
+        let share = match (output, r.session.total_cost_usd) {
+            (Some(o), Some(total)) if total > 0.0 => Some(o / total),
+            _ => None,
+        };
+        for f in &mut r.findings {
+            f.wasted_cost_usd = f.wasted_cost_usd.zip(share).map(|(w, s)| w * s);
         }
-        None if content.starts_with("<!DOCTYPE") => {
-            // HTML: write to default file
-            let path = PathBuf::from(default_file);
-            std::fs::write(&path, content)?;
-            eprintln!("{} Written to {}", "✓".green(), path.display());
+        r.session.total_cost_usd = output;
+    }
+}
+
+/// Scale each finding's wasted tokens and cost by its confidence, so totals show
+/// expected rather than maximal waste.
+fn apply_confidence_weighting(results: &mut [AnalysisResult]) {
+    eprintln!("{} Waste view: confidence-weighted", "→".cyan());
+    for f in results.iter_mut().flat_map(|r| r.findings.iter_mut()) {
+        f.wasted_tokens = f
+            .wasted_tokens
+            .map(|t| (t as f64 * f.confidence).round() as u64);
+        f.wasted_cost_usd = f.wasted_cost_usd.map(|c| c * f.confidence);
+    }
+}
+
+fn write_or_print(
+    content: &str,
+    out: Option<&PathBuf>,
+    default_file: &str,
+    dry_run: bool,
+) -> Result<Option<PathBuf>> {
+    let path = match out {
+        Some(path) => path.clone(),
+        // HTML: write to default file
+        None if content.starts_with("<!DOCTYPE") => PathBuf::from(default_file),
+        None => {
+            print!("{}", content);
+            return Ok(None);
         }
-        None => print!("{}", content),
+    };
+    write_output(&path, content, dry_run)?;
+    Ok((!dry_run).then_some(path))
+}
+
+/// Open a written report in the default browser. Skipped when stderr isn't a
+/// terminal or `CI` is set, so scripted runs never try to launch one.
+fn open_report(path: &Path) {
+    if !std::io::stderr().is_terminal() || std::env::var_os("CI").is_some() {
+        eprintln!(
+            "{} Not opening {} (non-interactive)",
+            "→".cyan(),
+            path.display()
+        );
+        return;
+    }
+    if let Err(e) = open::that(path) {
+        eprintln!(
+            "  {} Could not open {}: {}",
+            "!".yellow(),
+            path.display(),
+            e
+        );
     }
-    Ok(())
 }
 
 pub fn run(args: ReportArgs) -> Result<()> {
@@ -108,21 +494,34 @@ pub fn run(args: ReportArgs) -> Result<()> {
             agent,
             format,
             out,
+            redact_paths,
+            pseudonymize,
+            estimate_tokens,
+            output_tokens_only,
+            confidence_weighted_waste,
+            dry_run,
+            open,
+            count_roles,
+            top,
+            over,
+            rank,
+            also_json,
+            also_html,
         } => {
-            let result = analyze_one(&session_id, &agent)?;
-            match format.as_str() {
-                "json" => {
-                    let content = jreport::render_analysis(&result)?;
-                    write_or_print(&content, out.as_ref(), "report.json")?;
-                }
-                "html" => {
-                    let content = html_report::render_analysis(&result)?;
-                    write_or_print(&content, out.as_ref(), "report.html")?;
-                }
-                _ => {
-                    terminal::print_analysis(&result);
-                }
+            let mut result = analyze_one(&session_id, &agent, estimate_tokens, top, over, rank)?;
+            // Scored on the session as detected, before any cost or waste view
+            result.session.efficiency_score = Some(efficiency_score(&result));
+            apply_role_counts(std::slice::from_mut(&mut result), count_roles.as_deref())?;
+            redact_results(std::slice::from_mut(&mut result), redact_paths);
+            apply_pseudonyms(std::slice::from_mut(&mut result), pseudonymize);
+            if output_tokens_only {
+                apply_output_cost_view(std::slice::from_mut(&mut result));
+            }
+            if confidence_weighted_waste {
+                apply_confidence_weighting(std::slice::from_mut(&mut result));
             }
+            emit_session(&result, &format, out.as_ref(), dry_run, open)?;
+            emit_session_extras(&result, also_json.as_ref(), also_html.as_ref(), dry_run)?;
         }
 
         ReportSubcommand::Aggregate {
@@ -132,53 +531,513 @@ pub fn run(args: ReportArgs) -> Result<()> {
             format,
             out,
             limit,
+            sample,
+            seed,
+            min_messages,
+            tag,
+            title,
+            title_from_prompt,
+            redact_paths,
+            pseudonymize,
+            estimate_tokens,
+            output_tokens_only,
+            confidence_weighted_waste,
+            dry_run,
+            open,
+            count_roles,
+            page,
+            validate_pricing,
+            interactive,
+            inline_sessions,
+            group_by,
+            normalize_costs,
+            sort,
+            also_json,
+            also_html,
         } => {
+            if format == "table" && out.is_none() && run_paged(page)? {
+                return Ok(());
+            }
             let agents = parse_agents(&agent)?;
             let since_dt = since.as_deref().map(parse_datetime).transpose()?;
             let until_dt = until.as_deref().map(parse_datetime).transpose()?;
 
-            let sessions = ingest::discover_sessions(&agents, since_dt, until_dt, None, limit)?;
+            // Tags and titles are matched after discovery, so the limit has to wait until then
+            let post_filter = !tag.is_empty() || title.is_some();
+            let mut sessions = ingest::discover_sessions(
+                &agents,
+                &DiscoveryFilter {
+                    since: since_dt,
+                    until: until_dt,
+                    min_messages,
+                    limit: if post_filter { None } else { limit },
+                    ..Default::default()
+                },
+            )?;
+            retain_tagged(&mut sessions, &tag);
+            if title_from_prompt {
+                ingest::title_from_prompt(&mut sessions);
+            }
+            retain_titled(&mut sessions, title.as_deref());
+            if let Some(n) = limit {
+                sessions.truncate(n);
+            }
+            let population = sessions.len();
+            let sample_info = sample.filter(|&n| n < population).map(|n| {
+                ingest::sample_sessions(&mut sessions, n, seed);
+                terminal::Sample {
+                    sampled: sessions.len(),
+                    population,
+                    seed,
+                }
+            });
+            if let Some(s) = &sample_info {
+                eprintln!(
+                    "{} Sampled {} of {} sessions (seed {})",
+                    "→".cyan(),
+                    s.sampled,
+                    s.population,
+                    s.seed
+                );
+            }
 
             if sessions.is_empty() {
                 println!("{}", "No sessions found.".yellow());
                 return Ok(());
             }
 
-            eprintln!("{} Analyzing {} sessions...", "→".cyan(), sessions.len());
+            let tool_format = match format.as_str() {
+                "tools-json" => Some(ToolExportFormat::Json),
+                "tools-csv" => Some(ToolExportFormat::Csv),
+                _ => None,
+            };
+            if let Some(threshold) = validate_pricing {
+                return check_pricing(&sessions, threshold, &format);
+            }
+            if let Some(tool_format) = tool_format {
+                return export_tool_calls(
+                    &sessions,
+                    estimate_tokens,
+                    tool_format,
+                    out.as_deref(),
+                    dry_run,
+                );
+            }
 
-            let results: Vec<AnalysisResult> = sessions
-                .iter()
-                .filter_map(|s| match ingest::parse_session(s) {
-                    Ok(parsed) => {
-                        let findings = detect_inefficiencies(&parsed);
-                        let top = top_expensive_messages(&parsed, 5);
-                        Some(AnalysisResult {
-                            session: parsed.session,
-                            findings,
-                            top_expensive_messages: top,
-                        })
-                    }
-                    Err(e) => {
-                        eprintln!("  {} {}: {}", "!".yellow(), s.session_id, e);
-                        None
-                    }
-                })
-                .collect();
+            eprintln!("{} Analyzing {} sessions...", "→".cyan(), sessions.len());
 
-            match format.as_str() {
-                "json" => {
-                    let content = jreport::render_aggregate(&results)?;
-                    write_or_print(&content, out.as_ref(), "report.json")?;
+            let mut results = analyze_sessions(&sessions, estimate_tokens, 5);
+            for r in &mut results {
+                r.session.efficiency_score = Some(efficiency_score(r));
+            }
+            apply_role_counts(&mut results, count_roles.as_deref())?;
+            redact_results(&mut results, redact_paths);
+            apply_pseudonyms(&mut results, pseudonymize);
+            if output_tokens_only {
+                apply_output_cost_view(&mut results);
+            }
+            if confidence_weighted_waste {
+                apply_confidence_weighting(&mut results);
+            }
+            if sort == "efficiency" {
+                terminal::sort_by_efficiency(&mut results);
+            }
+            let view = AggregateView {
+                interactive,
+                inline_sessions,
+                sample: sample_info.as_ref(),
+            };
+            emit_aggregate(&results, &format, out.as_ref(), dry_run, open, view)?;
+            if let Some(path) = &also_json {
+                write_output(
+                    path,
+                    &jreport::render_aggregate(&results, view.sample)?,
+                    dry_run,
+                )?;
+            }
+            if let Some(path) = &also_html {
+                let content = html_report::render_aggregate(
+                    &results,
+                    interactive,
+                    inline_sessions,
+                    view.sample,
+                )?;
+                write_output(path, &content, dry_run)?;
+            }
+            if let Some(group_by) = group_by.filter(|_| format == "table") {
+                match group_by {
+                    GroupBy::Day => terminal::print_cost_timeline(&results, TimeBucket::Day),
+                    GroupBy::Week => terminal::print_cost_timeline(&results, TimeBucket::Week),
+                    GroupBy::ModelFamily => terminal::print_model_families(&results),
+                    GroupBy::Tag => terminal::print_tags(&results),
                 }
-                "html" => {
-                    let content = html_report::render_aggregate(&results)?;
-                    write_or_print(&content, out.as_ref(), "report.html")?;
+            }
+            if normalize_costs && format == "table" {
+                println!();
+                terminal::print_normalized_costs(&results);
+            }
+            if sort == "efficiency" && format == "table" {
+                println!();
+                terminal::print_least_efficient(&results);
+            }
+        }
+
+        ReportSubcommand::Compare {
+            agent,
+            before,
+            after,
+            format,
+            min_messages,
+            estimate_tokens,
+        } => {
+            let agents = parse_agents(&agent)?;
+            let before_dt = parse_datetime(&before)?;
+            let after_dt = parse_datetime(&after)?;
+            if after_dt < before_dt {
+                anyhow::bail!("--after ({}) is earlier than --before ({})", after, before);
+            }
+
+            let window = |since, until| -> Result<terminal::AggregateStats> {
+                let sessions = ingest::discover_sessions(
+                    &agents,
+                    &DiscoveryFilter {
+                        since,
+                        until,
+                        min_messages,
+                        ..Default::default()
+                    },
+                )?;
+                eprintln!("{} Analyzing {} sessions...", "→".cyan(), sessions.len());
+                Ok(terminal::AggregateStats::from_results(&analyze_sessions(
+                    &sessions,
+                    estimate_tokens,
+                    5,
+                )))
+            };
+            let before_stats = window(None, Some(before_dt))?;
+            let after_stats = window(Some(after_dt), None)?;
+
+            if format == "json" {
+                println!(
+                    "{}",
+                    jreport::render_comparison(&before_stats, &after_stats)?
+                );
+            } else {
+                terminal::print_comparison(
+                    &format!("sessions before {}", before),
+                    &before_stats,
+                    &format!("sessions after {}", after),
+                    &after_stats,
+                );
+            }
+        }
+
+        ReportSubcommand::FromJsonl {
+            path,
+            session_id,
+            format,
+            out,
+            dry_run,
+            open,
+            interactive,
+            inline_sessions,
+            pseudonymize,
+        } => {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            let mut results = jreport::parse_jsonl(&text)
+                .with_context(|| format!("Failed to parse {}", path.display()))?;
+            eprintln!(
+                "{} Loaded {} analyses from {}",
+                "→".cyan(),
+                results.len(),
+                path.display()
+            );
+            match session_id {
+                Some(id) => {
+                    let mut result = results
+                        .into_iter()
+                        .find(|r| r.session.session_id.starts_with(&id))
+                        .ok_or_else(|| {
+                            anyhow::anyhow!("No session matching '{}' in {}", id, path.display())
+                        })?;
+                    apply_pseudonyms(std::slice::from_mut(&mut result), pseudonymize);
+                    emit_session(&result, &format, out.as_ref(), dry_run, open)?;
                 }
-                _ => {
-                    terminal::print_aggregate(&results);
+                None => {
+                    if results.is_empty() {
+                        println!("{}", "No sessions found.".yellow());
+                        return Ok(());
+                    }
+                    apply_pseudonyms(&mut results, pseudonymize);
+                    let view = AggregateView {
+                        interactive,
+                        inline_sessions,
+                        sample: None,
+                    };
+                    emit_aggregate(&results, &format, out.as_ref(), dry_run, open, view)?;
                 }
             }
         }
     }
     Ok(())
 }
+
+/// Render one session's analysis in `format` (table, json, toml, sarif, html).
+fn emit_session(
+    result: &AnalysisResult,
+    format: &str,
+    out: Option<&PathBuf>,
+    dry_run: bool,
+    open: bool,
+) -> Result<()> {
+    match format {
+        "json" => {
+            let content = jreport::render_analysis(result)?;
+            write_or_print(&content, out, "report.json", dry_run)?;
+        }
+        "toml" => {
+            let content = treport::render_analysis(result)?;
+            write_or_print(&content, out, "report.toml", dry_run)?;
+        }
+        "sarif" => {
+            let content = sarif::render_sarif(std::slice::from_ref(result))?;
+            write_or_print(&content, out, "report.sarif", dry_run)?;
+        }
+        "html" => {
+            let content = html_report::render_analysis(result)?;
+            let written = write_or_print(&content, out, "report.html", dry_run)?;
+            if let Some(path) = written.filter(|_| open) {
+                open_report(&path);
+            }
+        }
+        _ => {
+            terminal::print_analysis(result);
+        }
+    }
+    Ok(())
+}
+
+/// Write the `--also-json`/`--also-html` side outputs for a session, from the
+/// same analysis `emit_session` rendered.
+fn emit_session_extras(
+    result: &AnalysisResult,
+    also_json: Option<&PathBuf>,
+    also_html: Option<&PathBuf>,
+    dry_run: bool,
+) -> Result<()> {
+    if let Some(path) = also_json {
+        write_output(path, &jreport::render_analysis(result)?, dry_run)?;
+    }
+    if let Some(path) = also_html {
+        write_output(path, &html_report::render_analysis(result)?, dry_run)?;
+    }
+    Ok(())
+}
+
+/// Render an aggregate over `results` in `format` (table, json, jsonl, sarif, html).
+/// How an aggregate is presented beyond its format: the HTML extras, and the
+/// sample it was drawn from, if any.
+#[derive(Clone, Copy)]
+struct AggregateView<'a> {
+    interactive: bool,
+    inline_sessions: bool,
+    sample: Option<&'a terminal::Sample>,
+}
+
+fn emit_aggregate(
+    results: &[AnalysisResult],
+    format: &str,
+    out: Option<&PathBuf>,
+    dry_run: bool,
+    open: bool,
+    view: AggregateView,
+) -> Result<()> {
+    match format {
+        "json" => {
+            let content = jreport::render_aggregate(results, view.sample)?;
+            write_or_print(&content, out, "report.json", dry_run)?;
+        }
+        "jsonl" => {
+            let content = jreport::render_jsonl(results)?;
+            write_or_print(&content, out, "report.jsonl", dry_run)?;
+        }
+        "sarif" => {
+            let content = sarif::render_sarif(results)?;
+            write_or_print(&content, out, "report.sarif", dry_run)?;
+        }
+        "html" => {
+            let content = html_report::render_aggregate(
+                results,
+                view.interactive,
+                view.inline_sessions,
+                view.sample,
+            )?;
+            let written = write_or_print(&content, out, "report.html", dry_run)?;
+            if let Some(path) = written.filter(|_| open) {
+                open_report(&path);
+            }
+        }
+        _ => {
+            terminal::print_aggregate(results);
+            if let Some(sample) = view.sample {
+                terminal::print_sample_estimate(results, sample);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracekit_core::session_score;
+
+    #[test]
+    fn only_written_reports_are_opened() {
+        let dir = std::env::temp_dir().join(format!("tracekit-open-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let html = "<!DOCTYPE html><html></html>";
+        let out = dir.join("r.html");
+
+        // --open gets the path only when a file was actually written
+        let written = write_or_print(html, Some(&out), "report.html", false).unwrap();
+        assert_eq!(written.as_deref(), Some(out.as_path()));
+        assert!(out.exists());
+
+        let dry = dir.join("dry.html");
+        assert_eq!(
+            write_or_print(html, Some(&dry), "report.html", true).unwrap(),
+            None
+        );
+        assert!(!dry.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn result(findings: serde_json::Value) -> AnalysisResult {
+        serde_json::from_value(serde_json::json!({
+            "session": {
+                "session_id": "s",
+                "source_agent": "claude",
+                "source_path": "/tmp/s.jsonl",
+                "cwd": null,
+                "title": null,
+                "started_at": null,
+                "ended_at": null,
+                "model": null,
+                "message_count": 4,
+                "total_cost_usd": 10.0,
+                "total_input_tokens": 0,
+                "total_output_tokens": 0,
+                "cost_breakdown": {"input_usd": 8.0, "output_usd": 2.0},
+            },
+            "findings": findings,
+            "top_expensive_messages": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn output_cost_view_keeps_waste_within_cost() {
+        let mut result = result(serde_json::json!([{
+            "kind": "retry_loop",
+            "description": "retried",
+            "evidence": [],
+            "wasted_tokens": 1000,
+            "wasted_cost_usd": 5.0,
+            "confidence": 0.8,
+        }]));
+
+        apply_output_cost_view(std::slice::from_mut(&mut result));
+        assert_eq!(result.session.total_cost_usd, Some(2.0));
+        // Half the full cost was waste; still half of the output-side cost
+        assert_eq!(result.findings[0].wasted_cost_usd, Some(1.0));
+    }
+
+    #[test]
+    fn confidence_weighting_scales_waste_totals_by_confidence() {
+        let waste = |r: &AnalysisResult| -> (u64, f64) {
+            let tokens = r.findings.iter().filter_map(|f| f.wasted_tokens).sum();
+            let cost = r.findings.iter().filter_map(|f| f.wasted_cost_usd).sum();
+            (tokens, cost)
+        };
+        let mut result = result(serde_json::json!([
+            {
+                "kind": "retry_loop",
+                "description": "retried",
+                "evidence": [],
+                "wasted_tokens": 1000,
+                "wasted_cost_usd": 4.0,
+                "confidence": 0.75,
+            },
+            {
+                "kind": "context_bloat",
+                "description": "bloated",
+                "evidence": [],
+                "wasted_tokens": 3000,
+                "wasted_cost_usd": 2.0,
+                "confidence": 0.5,
+            },
+        ]));
+        assert_eq!(waste(&result), (4000, 6.0));
+
+        apply_confidence_weighting(std::slice::from_mut(&mut result));
+        // 1000 * 0.75 + 3000 * 0.5 tokens; 4.0 * 0.75 + 2.0 * 0.5 dollars
+        assert_eq!(waste(&result), (2250, 4.0));
+        // Only the waste is scaled; the confidence shown stays as detected
+        assert_eq!(result.findings[0].confidence, 0.75);
+    }
+
+    #[test]
+    fn efficiency_score_is_taken_before_waste_views() {
+        let mut results = vec![result(serde_json::json!([{
+            "kind": "retry_loop",
+            "description": "retried",
+            "evidence": [],
+            "wasted_tokens": 1000,
+            "wasted_cost_usd": 5.0,
+            "confidence": 0.5,
+        }]))];
+        let scored = efficiency_score(&results[0]);
+        results[0].session.efficiency_score = Some(scored);
+
+        apply_output_cost_view(&mut results);
+        apply_confidence_weighting(&mut results);
+        // The views shrink the waste, which would raise a freshly computed
+        // score; the stored one is what every renderer shows
+        assert_ne!(efficiency_score(&results[0]), scored);
+        assert_eq!(session_score(&results[0]), scored);
+    }
+
+    #[test]
+    fn side_outputs_come_from_the_same_session_analysis() {
+        let dir = std::env::temp_dir().join(format!("tracekit-also-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (json, html) = (dir.join("s.json"), dir.join("s.html"));
+        let result = result(serde_json::json!([{
+            "kind": "retry_loop",
+            "description": "Bash retried 3 times after failure",
+            "evidence": [],
+            "wasted_tokens": 1000,
+            "wasted_cost_usd": 1.5,
+            "confidence": 0.85,
+        }]));
+
+        // The table goes to the terminal and the JSON/HTML to their paths
+        emit_session(&result, "table", None, false, false).unwrap();
+        emit_session_extras(&result, Some(&json), Some(&html), false).unwrap();
+        let written: AnalysisResult =
+            serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+        assert_eq!(written.session.session_id, "s");
+        assert_eq!(
+            written.findings[0].description,
+            result.findings[0].description
+        );
+        assert!(std::fs::read_to_string(&html)
+            .unwrap()
+            .contains("Bash retried 3 times after failure"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
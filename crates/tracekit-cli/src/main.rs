@@ -3,7 +3,7 @@ use clap::{Parser, Subcommand};
 use colored::Colorize;
 
 mod commands;
-use commands::{analyze, capture, list, report};
+use commands::{analyze, capture, findings, list, report};
 
 #[derive(Parser)]
 #[command(
@@ -26,6 +26,30 @@ Quick start:
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Currency for displayed costs (costs are stored in USD)
+    #[arg(long, global = true, default_value = "USD")]
+    pub currency: String,
+
+    /// Units of --currency per 1 USD (required for non-USD currencies)
+    #[arg(long, global = true)]
+    pub fx_rate: Option<f64>,
+
+    /// Disable colored output (the NO_COLOR environment variable works too)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Price tokens as of this month (YYYY-MM) instead of the current catalog
+    #[arg(long, global = true)]
+    pub pricing_date: Option<tracekit_core::PricingDate>,
+
+    /// Most OpenCode trace files read at once (each session spans many small files)
+    #[arg(long, global = true, default_value_t = tracekit_ingest::opencode::DEFAULT_MAX_OPEN_FILES)]
+    pub max_open_files: usize,
+
+    /// After the command, list the trace files discovery skipped and why (to stderr)
+    #[arg(long, global = true)]
+    pub explain_discovery: bool,
 }
 
 #[derive(Subcommand)]
@@ -41,6 +65,9 @@ pub enum Commands {
 
     /// Generate reports (terminal/JSON/HTML)
     Report(report::ReportArgs),
+
+    /// Describe the finding kinds tracekit can report
+    Findings(findings::FindingsArgs),
 }
 
 fn main() {
@@ -53,10 +80,31 @@ fn main() {
 }
 
 fn run(cli: Cli) -> Result<()> {
-    match cli.command {
+    if cli.no_color {
+        colored::control::set_override(false);
+    }
+    let rate = match cli.fx_rate {
+        Some(r) => r,
+        None if cli.currency.eq_ignore_ascii_case("USD") => 1.0,
+        None => anyhow::bail!(
+            "--currency {} needs --fx-rate (units per 1 USD)",
+            cli.currency
+        ),
+    };
+    tracekit_report::set_display_currency(tracekit_report::Currency::new(&cli.currency, rate)?);
+    tracekit_core::set_pricing_date(cli.pricing_date);
+    tracekit_ingest::opencode::set_max_open_files(cli.max_open_files);
+    tracekit_ingest::set_explain_discovery(cli.explain_discovery);
+
+    let result = match cli.command {
         Commands::Capture(args) => capture::run(args),
         Commands::List(args) => list::run(args),
         Commands::Analyze(args) => analyze::run(args),
         Commands::Report(args) => report::run(args),
+        Commands::Findings(args) => findings::run(args),
+    };
+    if cli.explain_discovery {
+        commands::print_discovery_skips();
     }
+    result
 }
@@ -1,41 +1,452 @@
+use crate::pricing::lookup_price;
 use crate::schema::*;
 use std::collections::{HashMap, HashSet};
 
-/// Run all detectors on a parsed session and return findings.
+/// An inefficiency detector. Implement this to add custom rules without forking,
+/// then pass it alongside [`default_detectors`] to [`detect_with`].
+pub trait Detector {
+    fn detect(&self, parsed: &ParsedSession) -> Vec<Finding>;
+
+    /// Short name shown in `--explain` output.
+    fn name(&self) -> &str {
+        "custom"
+    }
+
+    /// Describe the signal this detector measured and the threshold it compares
+    /// against, so users can see why it did or didn't fire.
+    fn explain(&self, _parsed: &ParsedSession) -> Option<String> {
+        None
+    }
+
+    /// Findings and diagnostic together, as [`explain_with`] wants them. Built-in
+    /// detectors override this to derive both from one pass over the session.
+    fn run(&self, parsed: &ParsedSession) -> (Vec<Finding>, Option<String>) {
+        (self.detect(parsed), self.explain(parsed))
+    }
+
+    /// Whether the detector only looks at tool calls, and so is skipped for
+    /// [`SessionKind::Chat`] sessions.
+    fn tool_centric(&self) -> bool {
+        false
+    }
+}
+
+/// Diagnostic trace for one detector run, produced by [`explain_with`].
+#[derive(Debug, Clone)]
+pub struct DetectorTrace {
+    pub detector: String,
+    pub fired: usize,
+    pub detail: Option<String>,
+}
+
+/// The built-in detectors, in the order they run.
+pub fn default_detectors() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(RetryLoopDetector::default()),
+        Box::new(EditCascadeDetector),
+        Box::new(ToolFanoutDetector),
+        Box::new(RedundantRereadDetector),
+        Box::new(ContextBloatDetector::default()),
+        Box::new(ErrorRepromptChurnDetector),
+        Box::new(SubagentOverheadDetector),
+        Box::new(MissingFileLoopDetector),
+        Box::new(OutputTruncationDetector),
+        Box::new(UnreusedCacheWriteDetector),
+        Box::new(CostConcentrationDetector),
+        Box::new(ExplorationOverheadDetector),
+        Box::new(SlowToolDetector),
+        Box::new(RunawayOutputDetector),
+        Box::new(LowYieldTurnDetector),
+        Box::new(AbortedSessionDetector),
+        Box::new(RedundantOutputDetector),
+        Box::new(RepeatedPasteDetector),
+        Box::new(ToolHeavyTurnDetector),
+        Box::new(CostlyModelSwitchDetector),
+    ]
+}
+
+/// Static description of a built-in finding kind, for catalogs and dashboards.
+#[derive(Debug, Clone)]
+pub struct FindingInfo {
+    pub kind: FindingKind,
+    pub description: &'static str,
+    /// Confidence the detector assigns (the highest, where it scales with severity)
+    pub default_confidence: f64,
+    /// Default trigger condition, in words
+    pub threshold: &'static str,
+}
+
+/// Every built-in finding kind, in detector order.
+pub static FINDING_CATALOG: &[FindingInfo] = &[
+    FindingInfo {
+        kind: FindingKind::RetryLoop,
+        description: "Same tool called again after an error, without corrective input",
+        default_confidence: 0.85,
+        threshold:
+            "a failed tool call retried within 2 turns, followed to success (0.30-0.85 if it succeeds, 0.20 if every error was transient)",
+    },
+    FindingInfo {
+        kind: FindingKind::EditCascade,
+        description: "Repeated failed edits on the same file",
+        default_confidence: 0.80,
+        threshold: "2+ failed edits/writes on one file",
+    },
+    FindingInfo {
+        kind: FindingKind::ToolFanout,
+        description: "Many calls to the same tool in one turn that could be batched",
+        default_confidence: 0.70,
+        threshold: "4+ calls to one tool in a turn",
+    },
+    FindingInfo {
+        kind: FindingKind::RedundantReread,
+        description: "Same file read repeatedly with no writes in between",
+        default_confidence: 0.75,
+        threshold: "3+ reads of one file with no intervening write",
+    },
+    FindingInfo {
+        kind: FindingKind::ContextBloat,
+        description: "Billed input spike well above the session average",
+        default_confidence: 0.70,
+        threshold: "billed input > 2.5x session average and > 200k tokens (3+ priced turns)",
+    },
+    FindingInfo {
+        kind: FindingKind::ErrorRepromptChurn,
+        description: "Same tool error repeated across consecutive turns",
+        default_confidence: 0.80,
+        threshold: "3+ consecutive turns repeating the same tool error",
+    },
+    FindingInfo {
+        kind: FindingKind::SubagentOverhead,
+        description: "Sidechain/subagent usage that could possibly be inlined",
+        default_confidence: 0.50,
+        threshold: "any sidechain message",
+    },
+    FindingInfo {
+        kind: FindingKind::MissingFileLoop,
+        description: "Same nonexistent path targeted repeatedly",
+        default_confidence: 0.85,
+        threshold: "2+ not-found errors on one path",
+    },
+    FindingInfo {
+        kind: FindingKind::OutputTruncation,
+        description: "Turns that stopped at the output token limit",
+        default_confidence: 0.90,
+        threshold: "any turn finishing with max_tokens/length",
+    },
+    FindingInfo {
+        kind: FindingKind::UnreusedCacheWrite,
+        description: "Large cache writes that no later turn reads back",
+        default_confidence: 0.60,
+        threshold: "cache write >= 10k tokens never read by a later turn",
+    },
+    FindingInfo {
+        kind: FindingKind::CostConcentration,
+        description: "One turn accounts for most of the session cost",
+        default_confidence: 0.60,
+        threshold: "one turn > 60% of session cost (3+ priced turns)",
+    },
+    FindingInfo {
+        kind: FindingKind::ExplorationOverhead,
+        description: "Long run of read/search calls before the first edit or write",
+        default_confidence: 0.50,
+        threshold: "15+ consecutive read/search calls ended by an edit/write",
+    },
+    FindingInfo {
+        kind: FindingKind::SlowTool,
+        description: "A tool call far slower than the rest of the session's calls",
+        default_confidence: 0.55,
+        threshold: "> p95 of timed calls, >= 3x median and >= 10s (5+ timed calls)",
+    },
+    FindingInfo {
+        kind: FindingKind::RunawayOutput,
+        description: "A text-only turn that generated far more output than the session's norm",
+        default_confidence: 0.45,
+        threshold: "no tool calls, output >= 10x session median and >= 4k tokens (5+ turns)",
+    },
+    FindingInfo {
+        kind: FindingKind::LowYieldTurn,
+        description:
+            "An expensive turn that re-read the context to produce a few tokens and no tool calls",
+        default_confidence: 0.35,
+        threshold:
+            "billed input >= 100k tokens, output <= 50 tokens, no tool calls (final turn exempt)",
+    },
+    FindingInfo {
+        kind: FindingKind::AbortedSession,
+        description: "The session was interrupted or aborted before the agent finished",
+        default_confidence: 0.3,
+        threshold: "trace ends mid-exchange (interrupt, abort event, or unfinished turn)",
+    },
+    FindingInfo {
+        kind: FindingKind::RedundantOutput,
+        description: "The same large tool output was returned, and injected into context, again",
+        default_confidence: 0.6,
+        threshold: "identical successful output >= 2 KB returned 2+ times",
+    },
+    FindingInfo {
+        kind: FindingKind::RepeatedPaste,
+        description: "Large text pasted into a prompt that an earlier prompt already contained",
+        default_confidence: 0.5,
+        threshold: ">= 1k chars and >= half of a prompt's lines repeat earlier prompts",
+    },
+    FindingInfo {
+        kind: FindingKind::ToolHeavyTurn,
+        description: "A turn that fired many different tools but wrote almost nothing",
+        default_confidence: 0.4,
+        threshold: "8+ tool calls across 3+ tools, <= 200 output tokens",
+    },
+    FindingInfo {
+        kind: FindingKind::CostlyModelSwitch,
+        description: "A mid-session model change that raised cost without fewer errors",
+        default_confidence: 0.4,
+        threshold: "3+ turns each side, cost per output token up 1.5x+, errors per turn not down",
+    },
+];
+
+/// Run all built-in detectors on a parsed session and return findings.
 pub fn detect_inefficiencies(parsed: &ParsedSession) -> Vec<Finding> {
-    let mut findings = Vec::new();
-    let msgs = &parsed.messages;
+    detect_with(parsed, &default_detectors())
+}
 
-    // Build per-sequence cost lookup for waste estimation
-    let cost_map: HashMap<usize, f64> = msgs
+/// Run the given detectors on a parsed session and return findings,
+/// sorted by wasted cost descending.
+pub fn detect_with(parsed: &ParsedSession, detectors: &[Box<dyn Detector>]) -> Vec<Finding> {
+    let kind = SessionKind::of(parsed);
+    let mut findings: Vec<Finding> = detectors
         .iter()
-        .filter_map(|m| {
-            let cost = m.usage.as_ref()?.effective_cost()?;
-            Some((m.sequence, cost))
-        })
+        .filter(|d| applies(d.as_ref(), kind))
+        .flat_map(|d| d.detect(parsed))
         .collect();
+    sort_by_waste(&mut findings);
+    findings
+}
+
+/// Run the given detectors and report, for each, how many findings it produced
+/// and its signal-vs-threshold diagnostic.
+pub fn explain_with(parsed: &ParsedSession, detectors: &[Box<dyn Detector>]) -> Vec<DetectorTrace> {
+    detect_and_explain(parsed, detectors).1
+}
 
-    findings.extend(detect_retry_loops(msgs, &cost_map));
-    findings.extend(detect_edit_cascades(msgs, &cost_map));
-    findings.extend(detect_tool_fanout(msgs));
-    findings.extend(detect_redundant_rereads(msgs));
-    findings.extend(detect_context_bloat(msgs));
-    findings.extend(detect_error_reprompt_churn(msgs, &cost_map));
-    findings.extend(detect_subagent_overhead(msgs));
+/// [`detect_with`] and [`explain_with`] from a single [`Detector::run`] per
+/// detector: the sorted findings, and the trace that accounts for them.
+pub fn detect_and_explain(
+    parsed: &ParsedSession,
+    detectors: &[Box<dyn Detector>],
+) -> (Vec<Finding>, Vec<DetectorTrace>) {
+    let kind = SessionKind::of(parsed);
+    let mut findings = Vec::new();
+    let traces = detectors
+        .iter()
+        .map(|d| {
+            if !applies(d.as_ref(), kind) {
+                return DetectorTrace {
+                    detector: d.name().to_string(),
+                    fired: 0,
+                    detail: Some("skipped: chat session (no tool calls)".to_string()),
+                };
+            }
+            let (fired, detail) = d.run(parsed);
+            let trace = DetectorTrace {
+                detector: d.name().to_string(),
+                fired: fired.len(),
+                detail,
+            };
+            findings.extend(fired);
+            trace
+        })
+        .collect();
+    sort_by_waste(&mut findings);
+    (findings, traces)
+}
 
-    // Sort by wasted cost descending
+fn sort_by_waste(findings: &mut [Finding]) {
     findings.sort_by(|a, b| {
         let ca = a.wasted_cost_usd.unwrap_or(0.0);
         let cb = b.wasted_cost_usd.unwrap_or(0.0);
         cb.partial_cmp(&ca).unwrap_or(std::cmp::Ordering::Equal)
     });
+}
 
-    findings
+fn applies(detector: &dyn Detector, kind: SessionKind) -> bool {
+    kind == SessionKind::Agentic || !detector.tool_centric()
+}
+
+/// One pass of a built-in detector: its findings, and the signal-vs-threshold
+/// line `--explain` shows, measured against the same constants.
+struct Scan {
+    findings: Vec<Finding>,
+    detail: String,
+}
+
+impl Scan {
+    fn into_parts(self) -> (Vec<Finding>, Option<String>) {
+        (self.findings, Some(self.detail))
+    }
+}
+
+/// Build per-sequence cost lookup for waste estimation
+fn cost_map(msgs: &[CanonicalMessage]) -> HashMap<usize, f64> {
+    msgs.iter()
+        .filter_map(|m| {
+            let cost = m.usage.as_ref()?.effective_cost()?;
+            Some((m.sequence, cost))
+        })
+        .collect()
+}
+
+pub struct EditCascadeDetector;
+pub struct ToolFanoutDetector;
+pub struct RedundantRereadDetector;
+pub struct ErrorRepromptChurnDetector;
+pub struct SubagentOverheadDetector;
+pub struct MissingFileLoopDetector;
+pub struct OutputTruncationDetector;
+pub struct UnreusedCacheWriteDetector;
+pub struct CostConcentrationDetector;
+pub struct ExplorationOverheadDetector;
+pub struct SlowToolDetector;
+pub struct RunawayOutputDetector;
+pub struct LowYieldTurnDetector;
+pub struct AbortedSessionDetector;
+pub struct RedundantOutputDetector;
+pub struct RepeatedPasteDetector;
+pub struct ToolHeavyTurnDetector;
+pub struct CostlyModelSwitchDetector;
+
+/// Follows a failed tool through every later turn that calls it again, until a
+/// retry succeeds. Up to `max_gap` consecutive turns without the tool (a note,
+/// a look at something else) don't end the chain; `max_chain` bounds how many
+/// turns one finding covers.
+pub struct RetryLoopDetector {
+    pub max_gap: usize,
+    pub max_chain: usize,
+}
+
+impl Default for RetryLoopDetector {
+    fn default() -> Self {
+        Self {
+            max_gap: 1,
+            max_chain: 50,
+        }
+    }
+}
+
+/// Flags billed-input spikes. By default the session's first cache-write-heavy
+/// turn is exempt: writing the whole context to cache once is amortized setup,
+/// not waste. Set `exempt_first_cache_write: false` to flag it too.
+pub struct ContextBloatDetector {
+    pub exempt_first_cache_write: bool,
+}
+
+impl Default for ContextBloatDetector {
+    fn default() -> Self {
+        Self {
+            exempt_first_cache_write: true,
+        }
+    }
+}
+
+/// Implement [`Detector`] for a built-in detector from its name, whether it is
+/// tool-centric, and the scan that yields its findings and `--explain` detail in
+/// one pass. `|d, parsed|` binds the detector (for its settings) and the session.
+macro_rules! scan_detector {
+    ($ty:ty, $name:literal, tool_centric: $tool_centric:literal, |$d:pat_param, $parsed:ident| $scan:expr) => {
+        impl Detector for $ty {
+            fn detect(&self, parsed: &ParsedSession) -> Vec<Finding> {
+                self.run(parsed).0
+            }
+
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn explain(&self, parsed: &ParsedSession) -> Option<String> {
+                self.run(parsed).1
+            }
+
+            fn run(&self, $parsed: &ParsedSession) -> (Vec<Finding>, Option<String>) {
+                let $d = self;
+                $scan.into_parts()
+            }
+
+            fn tool_centric(&self) -> bool {
+                $tool_centric
+            }
+        }
+    };
 }
 
+scan_detector!(RetryLoopDetector, "retry_loop", tool_centric: true, |d, parsed| {
+    scan_retry_loops(&parsed.messages, &cost_map(&parsed.messages), d.max_gap, d.max_chain)
+});
+scan_detector!(EditCascadeDetector, "edit_cascade", tool_centric: true, |_, parsed| {
+    scan_edit_cascades(&parsed.messages, &cost_map(&parsed.messages))
+});
+scan_detector!(ToolFanoutDetector, "tool_fanout", tool_centric: true, |_, parsed| {
+    scan_tool_fanout(&parsed.messages)
+});
+scan_detector!(RedundantRereadDetector, "redundant_reread", tool_centric: true, |_, parsed| {
+    scan_redundant_rereads(&parsed.messages)
+});
+scan_detector!(ContextBloatDetector, "context_bloat", tool_centric: false, |d, parsed| {
+    scan_context_bloat(&parsed.messages, d.exempt_first_cache_write)
+});
+scan_detector!(ErrorRepromptChurnDetector, "error_reprompt_churn", tool_centric: true, |_, parsed| {
+    scan_error_reprompt_churn(&parsed.messages, &cost_map(&parsed.messages))
+});
+scan_detector!(SubagentOverheadDetector, "subagent_overhead", tool_centric: true, |_, parsed| {
+    scan_subagent_overhead(&parsed.messages)
+});
+scan_detector!(MissingFileLoopDetector, "missing_file_loop", tool_centric: true, |_, parsed| {
+    scan_missing_file_loops(&parsed.messages, &cost_map(&parsed.messages))
+});
+scan_detector!(OutputTruncationDetector, "output_truncation", tool_centric: false, |_, parsed| {
+    scan_output_truncation(&parsed.messages, &cost_map(&parsed.messages))
+});
+scan_detector!(UnreusedCacheWriteDetector, "unreused_cache_write", tool_centric: false, |_, parsed| {
+    scan_unreused_cache_writes(&parsed.messages)
+});
+scan_detector!(CostConcentrationDetector, "cost_concentration", tool_centric: false, |_, parsed| {
+    scan_cost_concentration(&parsed.messages)
+});
+scan_detector!(ExplorationOverheadDetector, "exploration_overhead", tool_centric: true, |_, parsed| {
+    scan_exploration_overhead(&parsed.messages)
+});
+scan_detector!(SlowToolDetector, "slow_tool", tool_centric: true, |_, parsed| {
+    scan_slow_tools(&parsed.messages)
+});
+scan_detector!(RunawayOutputDetector, "runaway_output", tool_centric: false, |_, parsed| {
+    scan_runaway_output(&parsed.messages)
+});
+scan_detector!(LowYieldTurnDetector, "low_yield_turn", tool_centric: false, |_, parsed| {
+    scan_low_yield_turns(&parsed.messages)
+});
+scan_detector!(AbortedSessionDetector, "aborted_session", tool_centric: false, |_, parsed| {
+    scan_aborted_session(parsed)
+});
+scan_detector!(RedundantOutputDetector, "redundant_output", tool_centric: true, |_, parsed| {
+    scan_redundant_outputs(&parsed.messages)
+});
+scan_detector!(RepeatedPasteDetector, "repeated_paste", tool_centric: false, |_, parsed| {
+    scan_repeated_pastes(&parsed.messages)
+});
+scan_detector!(ToolHeavyTurnDetector, "tool_heavy_turn", tool_centric: true, |_, parsed| {
+    scan_tool_heavy_turns(&parsed.messages)
+});
+scan_detector!(CostlyModelSwitchDetector, "costly_model_switch", tool_centric: false, |_, parsed| {
+    scan_model_switches(&parsed.messages)
+});
+
 /// Detect tool calls that fail and are immediately retried (same tool, similar args).
-fn detect_retry_loops(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>) -> Vec<Finding> {
+fn scan_retry_loops(
+    msgs: &[CanonicalMessage],
+    cost_map: &HashMap<usize, f64>,
+    max_gap: usize,
+    max_chain: usize,
+) -> Scan {
     let mut findings = Vec::new();
+    let mut failed = 0usize;
+    let mut transient_failures = 0usize;
 
     let assistant_msgs: Vec<&CanonicalMessage> =
         msgs.iter().filter(|m| m.role == Role::Assistant).collect();
@@ -49,9 +460,11 @@ fn detect_retry_loops(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>)
             .filter(|t| t.status == ToolStatus::Error)
             .collect();
 
-        if error_calls.is_empty() {
-            continue;
-        }
+        failed += error_calls.len();
+        transient_failures += error_calls
+            .iter()
+            .filter(|t| t.is_retryable_error())
+            .count();
 
         for err_tool in &error_calls {
             let key = (amsg.sequence, err_tool.tool_name.clone());
@@ -59,68 +472,155 @@ fn detect_retry_loops(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>)
                 continue;
             }
 
-            let mut chain = vec![(amsg.sequence, err_tool.tool_name.clone())];
+            let tool_name = err_tool.tool_name.clone();
+            let mut chain = vec![(amsg.sequence, ToolStatus::Error)];
+            // Whether every failure so far was transient, so retrying was reasonable
+            let mut transient = err_tool.is_retryable_error();
 
-            for next in assistant_msgs.iter().skip(i + 1).take(5) {
-                let retry = next
+            // Follow the chain until a retry succeeds or the tool stops being called
+            // for more than `max_gap` turns
+            let mut gap = 0;
+            for next in assistant_msgs.iter().skip(i + 1) {
+                if chain.len() >= max_chain {
+                    break;
+                }
+                let statuses: Vec<&ToolStatus> = next
+                    .tool_calls
+                    .iter()
+                    .filter(|t| t.tool_name == tool_name)
+                    .map(|t| &t.status)
+                    .collect();
+                if statuses.is_empty() {
+                    gap += 1;
+                    if gap > max_gap {
+                        break;
+                    }
+                    continue;
+                }
+                gap = 0;
+                transient &= next
                     .tool_calls
                     .iter()
-                    .any(|t| t.tool_name == err_tool.tool_name);
-                if retry {
-                    chain.push((next.sequence, err_tool.tool_name.clone()));
+                    .filter(|t| t.tool_name == tool_name && t.status == ToolStatus::Error)
+                    .all(|t| t.is_retryable_error());
+                let status = if statuses.contains(&&ToolStatus::Success) {
+                    ToolStatus::Success
+                } else if statuses.contains(&&ToolStatus::Error) {
+                    ToolStatus::Error
                 } else {
+                    ToolStatus::Unknown
+                };
+                let done = status == ToolStatus::Success;
+                chain.push((next.sequence, status));
+                if done {
                     break;
                 }
             }
 
             if chain.len() >= 2 {
-                for item in &chain {
-                    reported.insert(item.clone());
+                for (seq, _) in &chain {
+                    reported.insert((*seq, tool_name.clone()));
                 }
 
-                // Waste = cost of all retry turns (skip first — that was the initial attempt)
-                let wasted: f64 = chain[1..]
+                let succeeded = chain.last().map(|(_, st)| st) == Some(&ToolStatus::Success);
+                let failures = chain
+                    .iter()
+                    .filter(|(_, st)| *st == ToolStatus::Error)
+                    .count();
+
+                // Waste = cost of all retry turns (skip first — that was the initial attempt).
+                // A retry that eventually succeeds did necessary work, so only the failed
+                // retries before it count.
+                let retries = if succeeded {
+                    &chain[1..chain.len() - 1]
+                } else {
+                    &chain[1..]
+                };
+                let wasted: f64 = retries
                     .iter()
                     .filter_map(|(seq, _)| cost_map.get(seq))
                     .sum();
 
-                let tool_name = chain[0].1.clone();
                 let evidence: Vec<String> = chain
                     .iter()
-                    .map(|(seq, name)| format!("turn {}: {}", seq, name))
+                    .map(|(seq, st)| format!("turn {}: {} ({:?})", seq, tool_name, st))
                     .collect();
 
+                let (description, confidence) = if transient {
+                    // Timeouts, network and rate-limit errors can clear on their
+                    // own; retrying them is what the agent should do
+                    (
+                        format!(
+                            "{} retried {} time{} after transient errors{}",
+                            tool_name,
+                            chain.len() - 1,
+                            plural(chain.len() - 1),
+                            if succeeded { " before succeeding" } else { "" }
+                        ),
+                        0.2,
+                    )
+                } else if succeeded {
+                    // One failure then success is normal; confidence grows with each
+                    // additional failure before the eventual success.
+                    (
+                        format!(
+                            "{} failed {} time{} before succeeding",
+                            tool_name,
+                            failures,
+                            if failures == 1 { "" } else { "s" }
+                        ),
+                        (0.3 + 0.15 * (failures - 1) as f64).min(0.85),
+                    )
+                } else {
+                    (
+                        format!(
+                            "{} retried {} times after failure",
+                            tool_name,
+                            chain.len() - 1
+                        ),
+                        0.85,
+                    )
+                };
+
                 findings.push(Finding {
                     kind: FindingKind::RetryLoop,
-                    description: format!(
-                        "{} retried {} times after failure",
-                        tool_name,
-                        chain.len() - 1
-                    ),
+                    description,
                     evidence,
+                    evidence_refs: turn_refs(
+                        msgs,
+                        chain.iter().map(|(seq, _)| *seq),
+                        Some(&tool_name),
+                    ),
                     wasted_tokens: None,
-                    wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
-                    confidence: 0.85,
+                    wasted_cost_usd: if wasted > 0.0 && !transient {
+                        Some(wasted)
+                    } else {
+                        None
+                    },
+                    confidence,
                 });
             }
         }
     }
 
-    findings
+    let detail = format!(
+        "{} failed tool call{} ({} transient), {} retried within {} turn{} (fires on any retry; transient-only chains at low confidence)",
+        failed,
+        plural(failed),
+        transient_failures,
+        findings.len(),
+        max_gap + 1,
+        plural(max_gap + 1)
+    );
+    Scan { findings, detail }
 }
 
+/// Fewest failed edits on one file that make a cascade.
+const EDIT_CASCADE_MIN_FAILURES: usize = 2;
+
 /// Detect repeated failed Edit/Write/Patch calls on the same file.
-fn detect_edit_cascades(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>) -> Vec<Finding> {
+fn scan_edit_cascades(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>) -> Scan {
     let mut findings = Vec::new();
-    let edit_tools = [
-        "edit",
-        "write",
-        "str_replace_based_edit",
-        "apply_patch",
-        "str_replace_editor",
-        "replace_in_file",
-    ];
-
     let assistant_msgs: Vec<&CanonicalMessage> =
         msgs.iter().filter(|m| m.role == Role::Assistant).collect();
 
@@ -128,8 +628,7 @@ fn detect_edit_cascades(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64
 
     for amsg in &assistant_msgs {
         for tool in &amsg.tool_calls {
-            let name_lower = tool.tool_name.to_lowercase();
-            let is_edit = edit_tools.iter().any(|e| name_lower.contains(e));
+            let is_edit = matches!(tool.category, ToolCategory::Edit | ToolCategory::Write);
             if is_edit && tool.status == ToolStatus::Error {
                 if let Some(ref args) = tool.args_summary {
                     file_edits
@@ -142,7 +641,7 @@ fn detect_edit_cascades(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64
     }
 
     for (path, seqs) in &file_edits {
-        if seqs.len() >= 2 {
+        if seqs.len() >= EDIT_CASCADE_MIN_FAILURES {
             // Waste = cost of all repeat turns after the first
             let wasted: f64 = seqs[1..].iter().filter_map(|seq| cost_map.get(seq)).sum();
 
@@ -154,6 +653,7 @@ fn detect_edit_cascades(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64
                     seqs.len()
                 ),
                 evidence: seqs.iter().map(|s| format!("turn {}", s)).collect(),
+                evidence_refs: turn_refs(msgs, seqs.iter().copied(), None),
                 wasted_tokens: None,
                 wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
                 confidence: 0.80,
@@ -161,13 +661,23 @@ fn detect_edit_cascades(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64
         }
     }
 
-    findings
+    let max = file_edits.values().map(Vec::len).max().unwrap_or(0);
+    Scan {
+        findings,
+        detail: format!(
+            "max failed edits on one file: {} (threshold {})",
+            max, EDIT_CASCADE_MIN_FAILURES
+        ),
+    }
 }
 
+/// Calls to one tool in a single turn at which batching is suggested.
+const TOOL_FANOUT_MIN_CALLS: usize = 4;
+
 /// Detect many adjacent calls to the same tool (could be batched).
-fn detect_tool_fanout(msgs: &[CanonicalMessage]) -> Vec<Finding> {
+fn scan_tool_fanout(msgs: &[CanonicalMessage]) -> Scan {
     let mut findings = Vec::new();
-    let batch_threshold = 4usize;
+    let mut max = 0usize;
 
     let assistant_msgs: Vec<&CanonicalMessage> =
         msgs.iter().filter(|m| m.role == Role::Assistant).collect();
@@ -178,7 +688,8 @@ fn detect_tool_fanout(msgs: &[CanonicalMessage]) -> Vec<Finding> {
             *counts.entry(tool.tool_name.as_str()).or_default() += 1;
         }
         for (name, count) in counts {
-            if count >= batch_threshold {
+            max = max.max(count);
+            if count >= TOOL_FANOUT_MIN_CALLS {
                 findings.push(Finding {
                     kind: FindingKind::ToolFanout,
                     description: format!(
@@ -186,6 +697,7 @@ fn detect_tool_fanout(msgs: &[CanonicalMessage]) -> Vec<Finding> {
                         count, name
                     ),
                     evidence: vec![format!("turn {}", amsg.sequence)],
+                    evidence_refs: vec![EvidenceRef::new(amsg, Some(name))],
                     wasted_tokens: None,
                     wasted_cost_usd: None,
                     confidence: 0.70,
@@ -194,23 +706,21 @@ fn detect_tool_fanout(msgs: &[CanonicalMessage]) -> Vec<Finding> {
         }
     }
 
-    findings
+    Scan {
+        findings,
+        detail: format!(
+            "max calls to one tool in a turn: {} (threshold {})",
+            max, TOOL_FANOUT_MIN_CALLS
+        ),
+    }
 }
 
+/// Reads of one file, with no write between them, that count as redundant.
+const REREAD_MIN_READS: usize = 3;
+
 /// Detect the same file/resource being read multiple times with no writes in between.
-fn detect_redundant_rereads(msgs: &[CanonicalMessage]) -> Vec<Finding> {
+fn scan_redundant_rereads(msgs: &[CanonicalMessage]) -> Scan {
     let mut findings = Vec::new();
-    let read_tools = ["read", "cat", "view", "open", "read_file"];
-    let write_tools = [
-        "write",
-        "edit",
-        "str_replace",
-        "apply_patch",
-        "replace_in_file",
-        "create_file",
-        "delete_file",
-    ];
-
     let mut last_written: HashMap<String, usize> = HashMap::new();
     let mut read_count: HashMap<String, Vec<usize>> = HashMap::new();
 
@@ -219,17 +729,17 @@ fn detect_redundant_rereads(msgs: &[CanonicalMessage]) -> Vec<Finding> {
 
     for amsg in &assistant_msgs {
         for tool in &amsg.tool_calls {
-            let name_lower = tool.tool_name.to_lowercase();
-            let is_read = read_tools.iter().any(|r| name_lower.contains(r));
-            let is_write = write_tools.iter().any(|w| name_lower.contains(w));
+            let is_read = tool.category == ToolCategory::Read;
+            let is_write = matches!(tool.category, ToolCategory::Edit | ToolCategory::Write);
 
-            if let Some(ref key) = tool.args_summary {
+            // Group by target file, so ranged reads of one file count together
+            if let Some(key) = tool.args_summary.as_deref().map(file_key) {
                 if is_write {
-                    last_written.insert(key.clone(), amsg.sequence);
-                    read_count.remove(key);
+                    read_count.remove(&key);
+                    last_written.insert(key, amsg.sequence);
                 } else if is_read {
-                    let last_write = last_written.get(key).copied().unwrap_or(0);
-                    let reads = read_count.entry(key.clone()).or_default();
+                    let last_write = last_written.get(&key).copied().unwrap_or(0);
+                    let reads = read_count.entry(key).or_default();
                     let all_after_write = reads.iter().all(|&s| s > last_write);
                     if all_after_write {
                         reads.push(amsg.sequence);
@@ -242,7 +752,7 @@ fn detect_redundant_rereads(msgs: &[CanonicalMessage]) -> Vec<Finding> {
     }
 
     for (path, seqs) in &read_count {
-        if seqs.len() >= 3 {
+        if seqs.len() >= REREAD_MIN_READS {
             findings.push(Finding {
                 kind: FindingKind::RedundantReread,
                 description: format!(
@@ -251,6 +761,7 @@ fn detect_redundant_rereads(msgs: &[CanonicalMessage]) -> Vec<Finding> {
                     seqs.len()
                 ),
                 evidence: seqs.iter().map(|s| format!("turn {}", s)).collect(),
+                evidence_refs: turn_refs(msgs, seqs.iter().copied(), None),
                 wasted_tokens: None,
                 wasted_cost_usd: None,
                 confidence: 0.75,
@@ -258,12 +769,29 @@ fn detect_redundant_rereads(msgs: &[CanonicalMessage]) -> Vec<Finding> {
         }
     }
 
-    findings
+    let max = read_count.values().map(Vec::len).max().unwrap_or(0);
+    Scan {
+        findings,
+        detail: format!(
+            "max reads of one file: {} (threshold {} with no write between)",
+            max, REREAD_MIN_READS
+        ),
+    }
 }
 
+/// Fewest priced turns for a meaningful billed-input average.
+const CONTEXT_BLOAT_MIN_TURNS: usize = 3;
+/// A bloated turn bills more than this multiple of the session average...
+const CONTEXT_BLOAT_FACTOR: f64 = 2.5;
+/// ...and more than this many input tokens.
+const CONTEXT_BLOAT_FLOOR: u64 = 200_000;
+
 /// Detect unusually high total-billed-input spikes (context bloat / over-injection).
-fn detect_context_bloat(msgs: &[CanonicalMessage]) -> Vec<Finding> {
+fn scan_context_bloat(msgs: &[CanonicalMessage], exempt_first_cache_write: bool) -> Scan {
     let mut findings = Vec::new();
+    let exempt = exempt_first_cache_write
+        .then(|| first_cache_write_turn(msgs))
+        .flatten();
 
     // Use total_billed_input (input + cache_read + cache_write) as the signal —
     // this catches both massive cache writes (initial injections) and cache reads
@@ -278,18 +806,30 @@ fn detect_context_bloat(msgs: &[CanonicalMessage]) -> Vec<Finding> {
         })
         .collect();
 
-    if billed_counts.len() < 3 {
-        return findings;
+    if billed_counts.len() < CONTEXT_BLOAT_MIN_TURNS {
+        return Scan {
+            findings,
+            detail: format!(
+                "{} priced turns (needs at least {})",
+                billed_counts.len(),
+                CONTEXT_BLOAT_MIN_TURNS
+            ),
+        };
     }
 
     let mean: f64 =
         billed_counts.iter().map(|(_, t, _)| *t as f64).sum::<f64>() / billed_counts.len() as f64;
 
-    // Flag turns with >2.5x average billed input and a minimum absolute threshold
-    let threshold = (mean * 2.5) as u64;
+    // Flag turns well above the average billed input and a minimum absolute threshold
+    let threshold = ((mean * CONTEXT_BLOAT_FACTOR) as u64).max(CONTEXT_BLOAT_FLOOR);
+    let mut max = 0u64;
 
     for (seq, total_billed, cost) in &billed_counts {
-        if *total_billed > threshold && *total_billed > 200_000 {
+        if Some(*seq) == exempt {
+            continue;
+        }
+        max = max.max(*total_billed);
+        if *total_billed > threshold {
             let excess = total_billed.saturating_sub(mean as u64);
             // Attribute the fraction of cost proportional to excess tokens
             let wasted = if *total_billed > 0 {
@@ -307,11 +847,11 @@ fn detect_context_bloat(msgs: &[CanonicalMessage]) -> Vec<Finding> {
                     *total_billed as f64 / mean,
                 ),
                 evidence: vec![format!(
-                    "turn {}: {} billed input tokens (${:.4})",
+                    "turn {}: {} billed input tokens",
                     seq,
-                    fmt_tokens_plain(*total_billed),
-                    cost
+                    fmt_tokens(*total_billed)
                 )],
+                evidence_refs: turn_refs(msgs, [*seq], None),
                 wasted_tokens: Some(excess),
                 wasted_cost_usd: wasted,
                 confidence: 0.70,
@@ -319,15 +859,29 @@ fn detect_context_bloat(msgs: &[CanonicalMessage]) -> Vec<Finding> {
         }
     }
 
-    findings
+    let detail = format!(
+        "max billed input {} {} threshold {} ({}x avg {}, floor {}k){}",
+        fmt_tokens(max),
+        if max > threshold { ">" } else { "<=" },
+        fmt_tokens(threshold),
+        CONTEXT_BLOAT_FACTOR,
+        fmt_tokens(mean as u64),
+        CONTEXT_BLOAT_FLOOR / 1000,
+        match exempt {
+            Some(seq) => format!("; turn {} exempt as initial cache write", seq),
+            None => String::new(),
+        }
+    );
+    Scan { findings, detail }
 }
 
+/// Consecutive turns repeating the same tool error that count as churn.
+const CHURN_MIN_TURNS: usize = 3;
+
 /// Detect repeated error→reprompt cycles without new corrective context.
-fn detect_error_reprompt_churn(
-    msgs: &[CanonicalMessage],
-    cost_map: &HashMap<usize, f64>,
-) -> Vec<Finding> {
+fn scan_error_reprompt_churn(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>) -> Scan {
     let mut findings = Vec::new();
+    let mut longest = 0usize;
 
     let mut consecutive_errors = 0usize;
     let mut error_start_seq = 0usize;
@@ -361,9 +915,10 @@ fn detect_error_reprompt_churn(
                 error_end_seq = amsg.sequence;
                 churn_seqs = vec![amsg.sequence];
             }
+            longest = longest.max(consecutive_errors);
             prev_error_tools = error_tools;
         } else {
-            if consecutive_errors >= 3 && !reported_churn.contains(&error_start_seq) {
+            if consecutive_errors >= CHURN_MIN_TURNS && !reported_churn.contains(&error_start_seq) {
                 reported_churn.insert(error_start_seq);
                 // Waste = cost of all churn turns beyond the first
                 let wasted: f64 = churn_seqs[1..]
@@ -377,6 +932,7 @@ fn detect_error_reprompt_churn(
                         consecutive_errors, error_start_seq, error_end_seq
                     ),
                     evidence: vec![format!("turns {}-{}", error_start_seq, error_end_seq)],
+                    evidence_refs: turn_refs(msgs, churn_seqs.iter().copied(), None),
                     wasted_tokens: None,
                     wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
                     confidence: 0.80,
@@ -389,7 +945,7 @@ fn detect_error_reprompt_churn(
     }
 
     // Flush at end
-    if consecutive_errors >= 3 && !reported_churn.contains(&error_start_seq) {
+    if consecutive_errors >= CHURN_MIN_TURNS && !reported_churn.contains(&error_start_seq) {
         let wasted: f64 = churn_seqs[1..]
             .iter()
             .filter_map(|seq| cost_map.get(seq))
@@ -401,20 +957,31 @@ fn detect_error_reprompt_churn(
                 consecutive_errors, error_start_seq, error_end_seq
             ),
             evidence: vec![format!("turns {}-{}", error_start_seq, error_end_seq)],
+            evidence_refs: turn_refs(msgs, churn_seqs.iter().copied(), None),
             wasted_tokens: None,
             wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
             confidence: 0.80,
         });
     }
 
-    findings
+    Scan {
+        findings,
+        detail: format!(
+            "longest run of turns repeating the same tool error: {} (threshold {})",
+            longest, CHURN_MIN_TURNS
+        ),
+    }
 }
 
 /// Detect sidechain/subagent usage that adds overhead.
-fn detect_subagent_overhead(msgs: &[CanonicalMessage]) -> Vec<Finding> {
+fn scan_subagent_overhead(msgs: &[CanonicalMessage]) -> Scan {
     let sidechain_count = msgs.iter().filter(|m| m.is_sidechain).count();
+    let detail = format!("{} sidechain messages (fires on any)", sidechain_count);
     if sidechain_count == 0 {
-        return Vec::new();
+        return Scan {
+            findings: Vec::new(),
+            detail,
+        };
     }
 
     let sidechain_cost: f64 = msgs
@@ -430,18 +997,22 @@ fn detect_subagent_overhead(msgs: &[CanonicalMessage]) -> Vec<Finding> {
         .map(|u| u.total_billed_input() + u.output_tokens)
         .sum();
 
-    vec![Finding {
+    let findings = vec![Finding {
         kind: FindingKind::SubagentOverhead,
         description: format!(
             "{} sidechain/subagent messages — check if tasks could be inlined",
             sidechain_count
         ),
         evidence: vec![format!(
-            "{} subagent turns, {} tokens, ${:.4} cost",
+            "{} subagent turns, {} tokens",
             sidechain_count,
-            fmt_tokens_plain(sidechain_tokens),
-            sidechain_cost
+            fmt_tokens(sidechain_tokens)
         )],
+        evidence_refs: msgs
+            .iter()
+            .filter(|m| m.is_sidechain)
+            .map(|m| EvidenceRef::new(m, None))
+            .collect(),
         wasted_tokens: Some(sidechain_tokens / 4),
         wasted_cost_usd: if sidechain_cost > 0.0 {
             Some(sidechain_cost * 0.25)
@@ -449,12 +1020,72 @@ fn detect_subagent_overhead(msgs: &[CanonicalMessage]) -> Vec<Finding> {
             None
         },
         confidence: 0.50,
-    }]
+    }];
+    Scan { findings, detail }
 }
 
 /// Build top-N expensive messages list
 pub fn top_expensive_messages(parsed: &ParsedSession, top_n: usize) -> Vec<ExpensiveMessage> {
-    let mut messages: Vec<ExpensiveMessage> = parsed
+    let mut messages = expensive_messages(parsed);
+    messages.sort_by(|a, b| {
+        b.cost_usd
+            .partial_cmp(&a.cost_usd)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    messages.truncate(top_n);
+    messages
+}
+
+/// Top-N turns worth fixing first: each turn's cost is weighted by one plus the
+/// highest confidence of any finding whose evidence cites it, so a turn behind a
+/// confident finding outranks a slightly costlier clean one.
+pub fn top_actionable_turns(
+    parsed: &ParsedSession,
+    findings: &[Finding],
+    top_n: usize,
+) -> Vec<ExpensiveMessage> {
+    let cited = finding_turns(findings);
+    let score =
+        |m: &ExpensiveMessage| m.cost_usd * (1.0 + cited.get(&m.sequence).copied().unwrap_or(0.0));
+    let mut messages = expensive_messages(parsed);
+    messages.sort_by(|a, b| {
+        score(b)
+            .partial_cmp(&score(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    messages.truncate(top_n);
+    messages
+}
+
+/// Evidence refs for the messages at `seqs`, naming `tool` when the finding is
+/// about one. Sequences with no message are skipped.
+fn turn_refs(
+    msgs: &[CanonicalMessage],
+    seqs: impl IntoIterator<Item = usize>,
+    tool: Option<&str>,
+) -> Vec<EvidenceRef> {
+    seqs.into_iter()
+        .filter_map(|seq| msgs.iter().find(|m| m.sequence == seq))
+        .map(|m| EvidenceRef::new(m, tool))
+        .collect()
+}
+
+/// Turns the findings point at, each mapped to the highest confidence among the
+/// findings citing it.
+fn finding_turns(findings: &[Finding]) -> HashMap<usize, f64> {
+    let mut turns: HashMap<usize, f64> = HashMap::new();
+    for f in findings {
+        for r in &f.evidence_refs {
+            let conf = turns.entry(r.sequence).or_insert(0.0);
+            *conf = conf.max(f.confidence);
+        }
+    }
+    turns
+}
+
+/// Every assistant turn with a known cost, unsorted.
+fn expensive_messages(parsed: &ParsedSession) -> Vec<ExpensiveMessage> {
+    parsed
         .messages
         .iter()
         .filter(|m| m.role == Role::Assistant)
@@ -470,33 +1101,2062 @@ pub fn top_expensive_messages(parsed: &ParsedSession, top_n: usize) -> Vec<Expen
                 input_tokens: u.total_billed_input(),
                 output_tokens: u.output_tokens,
                 tool_count: m.tool_calls.len(),
+                finish_reason: m.finish_reason.clone(),
             })
         })
+        .collect()
+}
+
+/// Not-found errors on one path that make a loop.
+const MISSING_FILE_MIN_HITS: usize = 2;
+
+/// Detect tool calls that keep targeting a path which does not exist.
+fn scan_missing_file_loops(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>) -> Scan {
+    let mut findings = Vec::new();
+    let mut missing: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+
+    for msg in msgs.iter().filter(|m| m.role == Role::Assistant) {
+        for tool in &msg.tool_calls {
+            if tool.error_class.as_deref() != Some(ERROR_CLASS_NOT_FOUND) {
+                continue;
+            }
+            if let Some(ref path) = tool.args_summary {
+                missing
+                    .entry(path.as_str())
+                    .or_default()
+                    .push((msg.sequence, tool.tool_name.as_str()));
+            }
+        }
+    }
+
+    let max = missing.values().map(Vec::len).max().unwrap_or(0);
+    let mut paths: Vec<_> = missing
+        .into_iter()
+        .filter(|(_, hits)| hits.len() >= MISSING_FILE_MIN_HITS)
         .collect();
+    paths.sort_by_key(|(path, _)| *path);
 
-    messages.sort_by(|a, b| {
-        b.cost_usd
-            .partial_cmp(&a.cost_usd)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    messages.truncate(top_n);
-    messages
+    for (path, hits) in paths {
+        // Every not-found turn is wasted: none of them could have succeeded
+        let mut seqs: Vec<usize> = hits.iter().map(|(seq, _)| *seq).collect();
+        seqs.dedup();
+        let wasted: f64 = seqs.iter().filter_map(|seq| cost_map.get(seq)).sum();
+
+        findings.push(Finding {
+            kind: FindingKind::MissingFileLoop,
+            description: format!("'{}' not found {} times", truncate(path, 60), hits.len()),
+            evidence: hits
+                .iter()
+                .map(|(seq, name)| format!("turn {}: {}", seq, name))
+                .collect(),
+            evidence_refs: hits
+                .iter()
+                .flat_map(|(seq, name)| turn_refs(msgs, [*seq], Some(name)))
+                .collect(),
+            wasted_tokens: None,
+            wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
+            confidence: 0.85,
+        });
+    }
+
+    Scan {
+        findings,
+        detail: format!(
+            "max not-found errors on one path: {} (threshold {})",
+            max, MISSING_FILE_MIN_HITS
+        ),
+    }
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}…", &s[..max.saturating_sub(1)])
+/// Detect turns whose output was cut off at the token limit.
+fn scan_output_truncation(msgs: &[CanonicalMessage], cost_map: &HashMap<usize, f64>) -> Scan {
+    let reasons: Vec<(usize, &str)> = msgs
+        .iter()
+        .filter(|m| m.role == Role::Assistant)
+        .filter_map(|m| Some((m.sequence, m.finish_reason.as_deref()?)))
+        .collect();
+    let truncated: Vec<(usize, &str)> = reasons
+        .iter()
+        .copied()
+        .filter(|(_, reason)| is_truncation_reason(reason))
+        .collect();
+
+    let detail = format!(
+        "{} of {} turns with a finish reason hit the token limit (fires on any)",
+        truncated.len(),
+        reasons.len()
+    );
+    if truncated.is_empty() {
+        return Scan {
+            findings: Vec::new(),
+            detail,
+        };
     }
+
+    // A truncated turn usually has to be regenerated or continued
+    let wasted: f64 = truncated
+        .iter()
+        .filter_map(|(seq, _)| cost_map.get(seq))
+        .sum();
+
+    let findings = vec![Finding {
+        kind: FindingKind::OutputTruncation,
+        description: format!(
+            "{} turn{} stopped at the output token limit",
+            truncated.len(),
+            if truncated.len() == 1 { "" } else { "s" }
+        ),
+        evidence: truncated
+            .iter()
+            .map(|(seq, reason)| format!("turn {}: {}", seq, reason))
+            .collect(),
+        evidence_refs: turn_refs(msgs, truncated.iter().map(|(seq, _)| *seq), None),
+        wasted_tokens: None,
+        wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
+        confidence: 0.90,
+    }];
+    Scan { findings, detail }
 }
 
-fn fmt_tokens_plain(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}k", n as f64 / 1_000.0)
+/// Smallest cache write, in tokens, worth checking for reuse.
+const CACHE_WRITE_MIN_TOKENS: u64 = 10_000;
+
+/// Detect large cache writes that no later turn reads back (the conversation
+/// diverged before the cached prefix could be reused).
+fn scan_unreused_cache_writes(msgs: &[CanonicalMessage]) -> Scan {
+    let turns: Vec<&CanonicalMessage> = msgs
+        .iter()
+        .filter(|m| m.role == Role::Assistant && m.usage.is_some())
+        .collect();
+
+    let mut large = 0usize;
+    let mut unreused: Vec<(usize, u64, Option<f64>)> = Vec::new();
+    for (i, m) in turns.iter().enumerate() {
+        let Some(ref u) = m.usage else { continue };
+        // The final turn's write can't be judged — the session simply ended
+        if u.cache_write_tokens < CACHE_WRITE_MIN_TOKENS || i + 1 == turns.len() {
+            continue;
+        }
+        large += 1;
+        let reused = turns[i + 1..].iter().any(|later| {
+            later
+                .usage
+                .as_ref()
+                .is_some_and(|lu| lu.cache_read_tokens >= u.cache_write_tokens)
+        });
+        if !reused {
+            // Only the write premium over plain input is wasted
+            let premium = m.model.as_deref().and_then(lookup_price).map(|p| {
+                (u.cache_write_tokens as f64 / 1_000_000.0)
+                    * (p.cache_write_per_mtok - p.input_per_mtok).max(0.0)
+            });
+            unreused.push((m.sequence, u.cache_write_tokens, premium));
+        }
+    }
+
+    let detail = format!(
+        "{} of {} cache writes >= {}k tokens never read back (final turn excluded)",
+        unreused.len(),
+        large,
+        CACHE_WRITE_MIN_TOKENS / 1000
+    );
+    if unreused.is_empty() {
+        return Scan {
+            findings: Vec::new(),
+            detail,
+        };
+    }
+
+    let tokens: u64 = unreused.iter().map(|(_, t, _)| t).sum();
+    let wasted: f64 = unreused.iter().filter_map(|(_, _, c)| *c).sum();
+
+    let findings = vec![Finding {
+        kind: FindingKind::UnreusedCacheWrite,
+        description: format!(
+            "{} cache write{} ({} tokens) never read by a later turn",
+            unreused.len(),
+            if unreused.len() == 1 { "" } else { "s" },
+            fmt_tokens(tokens)
+        ),
+        evidence: unreused
+            .iter()
+            .map(|(seq, t, _)| format!("turn {}: {} tokens written", seq, fmt_tokens(*t)))
+            .collect(),
+        evidence_refs: turn_refs(msgs, unreused.iter().map(|(seq, _, _)| *seq), None),
+        wasted_tokens: Some(tokens),
+        wasted_cost_usd: if wasted > 0.0 { Some(wasted) } else { None },
+        confidence: 0.60,
+    }];
+    Scan { findings, detail }
+}
+
+/// The first turn whose billed input is mostly cache writes — the one-time cost
+/// of caching the initial context.
+fn first_cache_write_turn(msgs: &[CanonicalMessage]) -> Option<usize> {
+    msgs.iter()
+        .filter(|m| m.role == Role::Assistant)
+        .find(|m| {
+            m.usage.as_ref().is_some_and(|u| {
+                u.cache_write_tokens > 0 && u.cache_write_tokens * 2 >= u.total_billed_input()
+            })
+        })
+        .map(|m| m.sequence)
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
     } else {
-        n.to_string()
+        "s"
+    }
+}
+
+/// Share of session cost above which a single turn is flagged.
+const COST_CONCENTRATION_SHARE: f64 = 0.6;
+/// Fewest priced turns to judge concentration; a short session is trivially
+/// concentrated.
+const COST_CONCENTRATION_MIN_TURNS: usize = 3;
+
+/// The most expensive priced turn, its cost and the session total; `None` with
+/// too few priced turns or no cost at all.
+fn dominant_turn(msgs: &[CanonicalMessage]) -> Option<(usize, f64, f64)> {
+    let costs: Vec<(usize, f64)> = msgs
+        .iter()
+        .filter_map(|m| Some((m.sequence, m.usage.as_ref()?.effective_cost()?)))
+        .collect();
+    if costs.len() < COST_CONCENTRATION_MIN_TURNS {
+        return None;
+    }
+    let total: f64 = costs.iter().map(|(_, c)| c).sum();
+    let (seq, top) = costs
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+    (total > 0.0).then_some((seq, top, total))
+}
+
+/// Detect sessions where one turn accounts for most of the cost.
+fn scan_cost_concentration(msgs: &[CanonicalMessage]) -> Scan {
+    let Some((seq, top, total)) = dominant_turn(msgs) else {
+        return Scan {
+            findings: Vec::new(),
+            detail: format!(
+                "fewer than {} priced turns (needs at least {})",
+                COST_CONCENTRATION_MIN_TURNS, COST_CONCENTRATION_MIN_TURNS
+            ),
+        };
+    };
+    let share = top / total;
+    let detail = format!(
+        "top turn {} is {:.0}% of cost (threshold {:.0}%)",
+        seq,
+        share * 100.0,
+        COST_CONCENTRATION_SHARE * 100.0
+    );
+    if share <= COST_CONCENTRATION_SHARE {
+        return Scan {
+            findings: Vec::new(),
+            detail,
+        };
+    }
+
+    // Not waste in itself — it points at where other findings (bloat, big tool
+    // output) are most likely to pay off
+    let findings = vec![Finding {
+        kind: FindingKind::CostConcentration,
+        description: format!(
+            "Turn {} accounts for {:.0}% of session cost",
+            seq,
+            share * 100.0
+        ),
+        evidence: vec![format!(
+            "turn {}: {:.0}% of session cost",
+            seq,
+            share * 100.0
+        )],
+        evidence_refs: turn_refs(msgs, [seq], None),
+        wasted_tokens: None,
+        wasted_cost_usd: None,
+        confidence: 0.60,
+    }];
+    Scan { findings, detail }
+}
+
+/// Consecutive read/search calls before an edit or write at which exploration
+/// is flagged.
+const EXPLORATION_RUN_THRESHOLD: usize = 15;
+
+/// Runs of consecutive read/search calls (the turn of each call), paired with the
+/// turn of the edit/write that ended the run, or `None` if the session ended
+/// first. Exec and other tools neither extend nor break a run.
+fn exploration_runs(msgs: &[CanonicalMessage]) -> Vec<(Vec<usize>, Option<usize>)> {
+    let mut runs = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    for amsg in msgs.iter().filter(|m| m.role == Role::Assistant) {
+        for tool in &amsg.tool_calls {
+            match tool.category {
+                ToolCategory::Read | ToolCategory::Search => current.push(amsg.sequence),
+                ToolCategory::Edit | ToolCategory::Write if !current.is_empty() => {
+                    runs.push((std::mem::take(&mut current), Some(amsg.sequence)));
+                }
+                _ => {}
+            }
+        }
+    }
+    if !current.is_empty() {
+        runs.push((current, None));
+    }
+    runs
+}
+
+/// Detect long read/search streaks before the agent acts. Only runs that end in
+/// an edit or write count: a session that never edits is usually a question or
+/// review, where reading is the job.
+fn scan_exploration_overhead(msgs: &[CanonicalMessage]) -> Scan {
+    let by_seq: HashMap<usize, &CanonicalMessage> = msgs.iter().map(|m| (m.sequence, m)).collect();
+    let mut findings = Vec::new();
+    let mut longest = 0usize;
+
+    for (calls, edit_seq) in exploration_runs(msgs) {
+        let Some(edit_seq) = edit_seq else { continue };
+        longest = longest.max(calls.len());
+        if calls.len() < EXPLORATION_RUN_THRESHOLD {
+            continue;
+        }
+        let (first, last) = (calls[0], calls[calls.len() - 1]);
+
+        // Every turn spent exploring past the threshold re-reads the cached context
+        let threshold_turn = calls[EXPLORATION_RUN_THRESHOLD - 1];
+        let mut excess_turns: Vec<usize> = calls
+            .iter()
+            .copied()
+            .filter(|&seq| seq > threshold_turn)
+            .collect();
+        excess_turns.dedup();
+
+        let mut wasted_tokens = 0u64;
+        let mut wasted_cost = 0.0f64;
+        for seq in &excess_turns {
+            let Some(m) = by_seq.get(seq) else { continue };
+            let Some(ref u) = m.usage else { continue };
+            wasted_tokens += u.cache_read_tokens;
+            if let Some(p) = m.model.as_deref().and_then(lookup_price) {
+                wasted_cost += (u.cache_read_tokens as f64 / 1_000_000.0) * p.cache_read_per_mtok;
+            }
+        }
+
+        findings.push(Finding {
+            kind: FindingKind::ExplorationOverhead,
+            description: format!(
+                "{} consecutive read/search calls (turns {}–{}) before the edit at turn {}",
+                calls.len(),
+                first,
+                last,
+                edit_seq
+            ),
+            evidence: vec![
+                format!(
+                    "turns {}–{}: {} read/search calls",
+                    first,
+                    last,
+                    calls.len()
+                ),
+                format!(
+                    "{} turn{} past the {}-call threshold",
+                    excess_turns.len(),
+                    plural(excess_turns.len()),
+                    EXPLORATION_RUN_THRESHOLD
+                ),
+            ],
+            evidence_refs: {
+                let mut seqs = calls.clone();
+                seqs.dedup();
+                turn_refs(msgs, seqs, None)
+            },
+            wasted_tokens: (wasted_tokens > 0).then_some(wasted_tokens),
+            wasted_cost_usd: (wasted_cost > 0.0).then_some(wasted_cost),
+            confidence: 0.50,
+        });
+    }
+
+    Scan {
+        findings,
+        detail: format!(
+            "longest read/search run before an edit: {} call{} (threshold {})",
+            longest,
+            plural(longest),
+            EXPLORATION_RUN_THRESHOLD
+        ),
+    }
+}
+
+/// Fewest timed tool calls for a meaningful latency distribution.
+const SLOW_TOOL_MIN_CALLS: usize = 5;
+/// A slow call must also take at least this long...
+const SLOW_TOOL_FLOOR_MS: u64 = 10_000;
+/// ...and at least this many times the session's median call.
+const SLOW_TOOL_MEDIAN_FACTOR: u64 = 3;
+
+/// Every tool call with a recorded duration, as (turn, tool name, ms).
+fn tool_timings(msgs: &[CanonicalMessage]) -> Vec<(usize, &str, u64)> {
+    msgs.iter()
+        .filter(|m| m.role == Role::Assistant)
+        .flat_map(|m| {
+            m.tool_calls
+                .iter()
+                .filter_map(move |t| Some((m.sequence, t.tool_name.as_str(), t.duration_ms?)))
+        })
+        .collect()
+}
+
+/// p95 (linearly interpolated) and median of the call durations; `None` with
+/// too few timed calls.
+fn timing_stats(timings: &[(usize, &str, u64)]) -> Option<(f64, f64)> {
+    if timings.len() < SLOW_TOOL_MIN_CALLS {
+        return None;
+    }
+    let mut ms: Vec<f64> = timings.iter().map(|(_, _, d)| *d as f64).collect();
+    ms.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let percentile = |p: f64| {
+        let rank = p * (ms.len() - 1) as f64;
+        let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+        ms[lo] + (ms[hi] - ms[lo]) * (rank - lo as f64)
+    };
+    Some((percentile(0.95), percentile(0.5)))
+}
+
+/// Detect individual tool calls whose duration is a strong outlier for the
+/// session. Only agents that record tool timings (currently OpenCode) produce these.
+fn scan_slow_tools(msgs: &[CanonicalMessage]) -> Scan {
+    let timings = tool_timings(msgs);
+    let Some((p95, median)) = timing_stats(&timings) else {
+        return Scan {
+            findings: Vec::new(),
+            detail: format!(
+                "{} timed tool call{} (needs at least {})",
+                timings.len(),
+                plural(timings.len()),
+                SLOW_TOOL_MIN_CALLS
+            ),
+        };
+    };
+
+    let slowest = timings.iter().map(|(_, _, ms)| *ms).max().unwrap_or(0);
+    let detail = format!(
+        "slowest call {:.1}s vs p95 {:.1}s / median {:.1}s (needs > p95, >= {}x median, >= {:.0}s)",
+        slowest as f64 / 1000.0,
+        p95 / 1000.0,
+        median / 1000.0,
+        SLOW_TOOL_MEDIAN_FACTOR,
+        SLOW_TOOL_FLOOR_MS as f64 / 1000.0
+    );
+    let findings = timings
+        .iter()
+        .filter(|(_, _, ms)| {
+            *ms as f64 > p95
+                && *ms >= SLOW_TOOL_FLOOR_MS
+                && *ms as f64 >= median * SLOW_TOOL_MEDIAN_FACTOR as f64
+        })
+        .map(|(seq, name, ms)| Finding {
+            kind: FindingKind::SlowTool,
+            description: format!(
+                "'{}' took {:.1}s (session median {:.1}s)",
+                name,
+                *ms as f64 / 1000.0,
+                median / 1000.0
+            ),
+            evidence: vec![format!(
+                "turn {}: {} {:.1}s (p95 {:.1}s)",
+                seq,
+                name,
+                *ms as f64 / 1000.0,
+                p95 / 1000.0
+            )],
+            evidence_refs: turn_refs(msgs, [*seq], Some(name)),
+            wasted_tokens: None,
+            wasted_cost_usd: None,
+            confidence: 0.55,
+        })
+        .collect();
+    Scan { findings, detail }
+}
+
+/// Fewest assistant turns with usage for a meaningful output median.
+const RUNAWAY_MIN_TURNS: usize = 5;
+/// A runaway turn must output at least this many times the session median...
+const RUNAWAY_OUTPUT_FACTOR: u64 = 10;
+/// ...and at least this many tokens.
+const RUNAWAY_OUTPUT_FLOOR: u64 = 4_000;
+
+/// Median output tokens over assistant turns with usage; `None` with too few turns.
+fn median_output_tokens(msgs: &[CanonicalMessage]) -> Option<f64> {
+    let mut out: Vec<u64> = msgs
+        .iter()
+        .filter(|m| m.role == Role::Assistant)
+        .filter_map(|m| Some(m.usage.as_ref()?.output_tokens))
+        .collect();
+    if out.len() < RUNAWAY_MIN_TURNS {
+        return None;
+    }
+    out.sort_unstable();
+    let mid = out.len() / 2;
+    Some(if out.len().is_multiple_of(2) {
+        (out[mid - 1] + out[mid]) as f64 / 2.0
+    } else {
+        out[mid] as f64
+    })
+}
+
+/// Detect text-only assistant turns whose output dwarfs the session's typical
+/// turn — usually the model dumping a whole file or log into its reply. The
+/// turn's output tokens are counted as waste.
+fn scan_runaway_output(msgs: &[CanonicalMessage]) -> Scan {
+    let Some(median) = median_output_tokens(msgs) else {
+        return Scan {
+            findings: Vec::new(),
+            detail: format!(
+                "fewer than {} assistant turns with usage (needs at least {})",
+                RUNAWAY_MIN_TURNS, RUNAWAY_MIN_TURNS
+            ),
+        };
+    };
+    let threshold = (median * RUNAWAY_OUTPUT_FACTOR as f64).max(RUNAWAY_OUTPUT_FLOOR as f64);
+
+    let text_only: Vec<(&CanonicalMessage, &CanonicalUsage)> = msgs
+        .iter()
+        .filter(|m| m.role == Role::Assistant && m.tool_calls.is_empty())
+        .filter_map(|m| Some((m, m.usage.as_ref()?)))
+        .collect();
+    let largest = text_only
+        .iter()
+        .map(|(_, u)| u.output_tokens)
+        .max()
+        .unwrap_or(0);
+    let detail = format!(
+        "largest text-only output {} tokens vs median {:.0} (needs >= {}x median and >= {})",
+        largest, median, RUNAWAY_OUTPUT_FACTOR, RUNAWAY_OUTPUT_FLOOR
+    );
+
+    let findings = text_only
+        .into_iter()
+        .filter_map(|(m, u)| {
+            if (u.output_tokens as f64) < threshold {
+                return None;
+            }
+            let cost = m
+                .model
+                .as_deref()
+                .and_then(lookup_price)
+                .map(|p| (u.output_tokens as f64 / 1_000_000.0) * p.output_per_mtok);
+            Some(Finding {
+                kind: FindingKind::RunawayOutput,
+                description: format!(
+                    "Turn {} generated {} output tokens with no tool calls ({:.0}x the session median)",
+                    m.sequence,
+                    u.output_tokens,
+                    u.output_tokens as f64 / median.max(1.0)
+                ),
+                evidence: vec![format!(
+                    "turn {}: {} output tokens (median {:.0})",
+                    m.sequence, u.output_tokens, median
+                )],
+                evidence_refs: vec![EvidenceRef::new(m, None)],
+                wasted_tokens: Some(u.output_tokens),
+                wasted_cost_usd: cost,
+                confidence: 0.45,
+            })
+        })
+        .collect();
+    Scan { findings, detail }
+}
+
+/// Billed input tokens at which a text-only turn counts as expensive...
+const LOW_YIELD_MIN_INPUT: u64 = 100_000;
+/// ...and output tokens at or below which it produced next to nothing.
+const LOW_YIELD_MAX_OUTPUT: u64 = 50;
+
+/// Assistant turns with usage and no tool calls, excluding the session's final
+/// assistant turn (a short closing reply is expected, not waste).
+fn low_yield_candidates(
+    msgs: &[CanonicalMessage],
+) -> impl Iterator<Item = (&CanonicalMessage, &CanonicalUsage)> {
+    let last = msgs
+        .iter()
+        .rev()
+        .find(|m| m.role == Role::Assistant)
+        .map(|m| m.sequence);
+    msgs.iter()
+        .filter(move |m| m.role == Role::Assistant && Some(m.sequence) != last)
+        .filter(|m| m.tool_calls.is_empty())
+        .filter_map(|m| Some((m, m.usage.as_ref()?)))
+}
+
+/// Detect turns that paid to re-read a large context and produced a sentence or
+/// two with no tool calls — acknowledgements, "let me check" preambles, status
+/// notes. The turn's cost is counted as waste; some of it usually bought a
+/// needed reply, hence the low confidence.
+fn scan_low_yield_turns(msgs: &[CanonicalMessage]) -> Scan {
+    let heavy: Vec<(&CanonicalMessage, &CanonicalUsage)> = low_yield_candidates(msgs)
+        .filter(|(_, u)| u.total_billed_input() >= LOW_YIELD_MIN_INPUT)
+        .collect();
+    let findings: Vec<Finding> = heavy
+        .iter()
+        .filter(|(_, u)| u.output_tokens <= LOW_YIELD_MAX_OUTPUT)
+        .map(|&(m, u)| {
+            let cost = u.effective_cost();
+            Finding {
+                kind: FindingKind::LowYieldTurn,
+                description: format!(
+                    "Turn {} billed {}k input tokens to produce {} output tokens and no tool calls",
+                    m.sequence,
+                    u.total_billed_input() / 1000,
+                    u.output_tokens
+                ),
+                evidence: vec![format!(
+                    "turn {}: in {} / out {}",
+                    m.sequence,
+                    u.total_billed_input(),
+                    u.output_tokens
+                )],
+                evidence_refs: vec![EvidenceRef::new(m, None)],
+                wasted_tokens: Some(u.total_billed_input() + u.output_tokens),
+                wasted_cost_usd: cost,
+                confidence: 0.35,
+            }
+        })
+        .collect();
+    let detail = format!(
+        "{} of {} text-only turn{} with >= {}k billed input produced <= {} output tokens",
+        findings.len(),
+        heavy.len(),
+        plural(heavy.len()),
+        LOW_YIELD_MIN_INPUT / 1000,
+        LOW_YIELD_MAX_OUTPUT
+    );
+    Scan { findings, detail }
+}
+
+/// Flag a session whose trace stops mid-exchange, so "cheap because aborted"
+/// isn't read as "cheap because efficient". Waste is the abandoned final
+/// exchange: the assistant turns answering the last prompt that got a reply
+/// (a trailing interrupt notice is itself recorded as a user message).
+fn scan_aborted_session(parsed: &ParsedSession) -> Scan {
+    if !parsed.session.ended_abnormally {
+        return Scan {
+            findings: Vec::new(),
+            detail: "trace ends normally".to_string(),
+        };
+    }
+    let msgs = &parsed.messages;
+    let is_reply = |m: &CanonicalMessage| m.role == Role::Assistant && !m.is_sidechain;
+    let last_reply = msgs.iter().rposition(is_reply).map_or(0, |i| i + 1);
+    let last_prompt = msgs[..last_reply]
+        .iter()
+        .rposition(|m| m.role == Role::User)
+        .map_or(0, |i| i + 1);
+    let abandoned: Vec<&CanonicalMessage> = msgs[last_prompt..last_reply]
+        .iter()
+        .filter(|m| is_reply(m))
+        .collect();
+    let usages = || abandoned.iter().filter_map(|m| m.usage.as_ref());
+    let tokens: u64 = usages()
+        .map(|u| u.total_billed_input() + u.output_tokens)
+        .sum();
+    let cost = usages()
+        .filter_map(|u| u.effective_cost())
+        .fold(None, |acc: Option<f64>, c| Some(acc.unwrap_or(0.0) + c));
+
+    let findings = vec![Finding {
+        kind: FindingKind::AbortedSession,
+        description: format!(
+            "Session ended abnormally after {} message{}; the final exchange was abandoned",
+            msgs.len(),
+            plural(msgs.len())
+        ),
+        evidence: vec![format!(
+            "{} assistant turn{} answering the last prompt",
+            abandoned.len(),
+            plural(abandoned.len())
+        )],
+        evidence_refs: abandoned
+            .iter()
+            .map(|m| EvidenceRef::new(m, None))
+            .collect(),
+        wasted_tokens: (tokens > 0).then_some(tokens),
+        wasted_cost_usd: cost,
+        confidence: 0.3,
+    }];
+    Scan {
+        findings,
+        detail: "trace ends mid-exchange".to_string(),
+    }
+}
+
+/// Smallest tool output, in bytes, whose verbatim repeat is worth flagging.
+const REDUNDANT_OUTPUT_MIN_BYTES: u64 = 2048;
+
+/// One distinct large tool output: the tool and args that first produced it, its
+/// size, and every turn that received it.
+struct RepeatedOutput<'a> {
+    tool: &'a CanonicalTool,
+    bytes: u64,
+    turns: Vec<&'a CanonicalMessage>,
+}
+
+/// Large successful tool outputs grouped by content hash, in order of first
+/// appearance.
+fn large_outputs(msgs: &[CanonicalMessage]) -> Vec<RepeatedOutput<'_>> {
+    let mut outputs: Vec<RepeatedOutput> = Vec::new();
+    let mut index: HashMap<u64, usize> = HashMap::new();
+    for msg in msgs.iter().filter(|m| m.role == Role::Assistant) {
+        for tool in &msg.tool_calls {
+            let (Some(hash), Some(bytes)) = (tool.output_hash, tool.output_bytes) else {
+                continue;
+            };
+            if tool.status != ToolStatus::Success || bytes < REDUNDANT_OUTPUT_MIN_BYTES {
+                continue;
+            }
+            match index.get(&hash) {
+                Some(&i) => outputs[i].turns.push(msg),
+                None => {
+                    index.insert(hash, outputs.len());
+                    outputs.push(RepeatedOutput {
+                        tool,
+                        bytes,
+                        turns: vec![msg],
+                    });
+                }
+            }
+        }
+    }
+    outputs
+}
+
+/// Detect large tool outputs returned verbatim more than once (the same file
+/// read twice, a command re-run with the same result). Each repeat injects the
+/// output into context again; waste is the repeats' tokens (bytes / 4) at the
+/// receiving turn's input price.
+fn scan_redundant_outputs(msgs: &[CanonicalMessage]) -> Scan {
+    let outputs = large_outputs(msgs);
+    let distinct = outputs.len();
+    let findings: Vec<Finding> = outputs
+        .into_iter()
+        .filter(|o| o.turns.len() >= 2)
+        .map(|o| {
+            let tokens_each = o.bytes / 4;
+            let repeats = &o.turns[1..];
+            let cost: f64 = repeats
+                .iter()
+                .filter_map(|m| m.model.as_deref().and_then(lookup_price))
+                .map(|p| tokens_each as f64 / 1_000_000.0 * p.input_per_mtok)
+                .sum();
+            let target = o
+                .tool
+                .args_summary
+                .as_deref()
+                .map(|a| format!(" '{}'", truncate(&file_key(a), 60)))
+                .unwrap_or_default();
+            Finding {
+                kind: FindingKind::RedundantOutput,
+                description: format!(
+                    "{}{} returned the same {} tokens of output {} times",
+                    o.tool.tool_name,
+                    target,
+                    fmt_tokens(tokens_each),
+                    o.turns.len()
+                ),
+                evidence: o
+                    .turns
+                    .iter()
+                    .map(|m| format!("turn {}: {} bytes", m.sequence, o.bytes))
+                    .collect(),
+                evidence_refs: o
+                    .turns
+                    .iter()
+                    .map(|m| EvidenceRef::new(m, Some(&o.tool.tool_name)))
+                    .collect(),
+                wasted_tokens: Some(tokens_each * repeats.len() as u64),
+                wasted_cost_usd: if cost > 0.0 { Some(cost) } else { None },
+                confidence: 0.6,
+            }
+        })
+        .collect();
+    let detail = format!(
+        "{} of {} distinct output{} >= {} KB returned more than once",
+        findings.len(),
+        distinct,
+        plural(distinct),
+        REDUNDANT_OUTPUT_MIN_BYTES / 1024
+    );
+    Scan { findings, detail }
+}
+
+/// Main-thread user prompts with line fingerprints, and for each the characters
+/// of its lines already seen in earlier prompts.
+fn prompt_repeats(msgs: &[CanonicalMessage]) -> Vec<(&CanonicalMessage, usize, usize)> {
+    let mut seen: HashSet<u64> = HashSet::new();
+    let mut prompts = Vec::new();
+    for msg in msgs
+        .iter()
+        .filter(|m| m.role == Role::User && !m.is_sidechain && !m.prompt_lines.is_empty())
+    {
+        let mut counted: HashSet<u64> = HashSet::new();
+        let total: usize = msg.prompt_lines.iter().map(|l| l.chars as usize).sum();
+        let repeated: usize = msg
+            .prompt_lines
+            .iter()
+            .filter(|l| seen.contains(&l.hash) && counted.insert(l.hash))
+            .map(|l| l.chars as usize)
+            .sum();
+        seen.extend(msg.prompt_lines.iter().map(|l| l.hash));
+        prompts.push((msg, repeated, total));
+    }
+    prompts
+}
+
+/// Whether a prompt repeats enough earlier text to count as a re-paste: at
+/// least [`PROMPT_LINES_MIN_CHARS`] characters, making up half its lines or more.
+fn is_repaste(repeated: usize, total: usize) -> bool {
+    repeated >= PROMPT_LINES_MIN_CHARS && repeated * 2 >= total
+}
+
+/// Detect large blocks of text (a file, a log) pasted into one prompt after
+/// another instead of being read once and referred back to. Lines are matched
+/// after whitespace normalization, so re-indented copies count. Waste is the
+/// repeated text's tokens (chars / 4) at the input price of the turn answering it.
+fn scan_repeated_pastes(msgs: &[CanonicalMessage]) -> Scan {
+    let prompts = prompt_repeats(msgs);
+    let repastes: Vec<(&CanonicalMessage, usize)> = prompts
+        .iter()
+        .filter(|&&(_, repeated, total)| is_repaste(repeated, total))
+        .map(|&(m, repeated, _)| (m, repeated))
+        .collect();
+    let detail = format!(
+        "{} of {} prompt{} repeat earlier prompts (threshold {} chars and half the prompt)",
+        repastes.len(),
+        prompts.len(),
+        plural(prompts.len()),
+        PROMPT_LINES_MIN_CHARS
+    );
+    if repastes.is_empty() {
+        return Scan {
+            findings: Vec::new(),
+            detail,
+        };
+    }
+
+    let tokens_of = |chars: usize| chars as u64 / 4;
+    let tokens: u64 = repastes.iter().map(|&(_, c)| tokens_of(c)).sum();
+    let cost: f64 = repastes
+        .iter()
+        .filter_map(|&(m, chars)| {
+            // Priced at the model of the reply that read it
+            let reply = msgs
+                .iter()
+                .find(|r| r.sequence > m.sequence && r.role == Role::Assistant)?;
+            let price = reply.model.as_deref().and_then(lookup_price)?;
+            Some(tokens_of(chars) as f64 / 1_000_000.0 * price.input_per_mtok)
+        })
+        .sum();
+
+    let findings = vec![Finding {
+        kind: FindingKind::RepeatedPaste,
+        description: format!(
+            "{} prompt{} re-pasted text from earlier prompts (~{} tokens)",
+            repastes.len(),
+            plural(repastes.len()),
+            fmt_tokens(tokens)
+        ),
+        evidence: repastes
+            .iter()
+            .map(|&(m, chars)| {
+                format!(
+                    "turn {}: {} chars repeated from earlier prompts",
+                    m.sequence, chars
+                )
+            })
+            .collect(),
+        evidence_refs: repastes
+            .iter()
+            .map(|&(m, _)| EvidenceRef::new(m, None))
+            .collect(),
+        wasted_tokens: Some(tokens),
+        wasted_cost_usd: if cost > 0.0 { Some(cost) } else { None },
+        confidence: 0.5,
+    }];
+    Scan { findings, detail }
+}
+
+/// Fewest tool calls in one turn that count as tool-heavy.
+const TOOL_HEAVY_MIN_CALLS: usize = 8;
+/// Fewest distinct tools among them; fewer is same-tool fan-out instead.
+const TOOL_HEAVY_MIN_TOOLS: usize = 3;
+/// Most output tokens a tool-heavy turn may produce.
+const TOOL_HEAVY_MAX_OUTPUT: u64 = 200;
+
+/// Assistant turns with recorded usage, their tool call count and distinct tools.
+fn tool_heavy_candidates(
+    msgs: &[CanonicalMessage],
+) -> impl Iterator<Item = (&CanonicalMessage, u64, usize, usize)> {
+    msgs.iter()
+        .filter(|m| m.role == Role::Assistant)
+        .filter_map(|m| {
+            let usage = m.usage.as_ref().filter(|u| !u.heuristic)?;
+            let tools: HashSet<&str> = m.tool_calls.iter().map(|t| t.tool_name.as_str()).collect();
+            Some((m, usage.output_tokens, m.tool_calls.len(), tools.len()))
+        })
+}
+
+/// Detect turns that fire many different tools while writing almost nothing —
+/// the agent probing (read, grep, list, run...) rather than acting on a plan.
+/// Same-tool bursts are [`FindingKind::ToolFanout`]'s; this catches diverse
+/// churn. No waste is estimated: the calls may have been needed.
+fn scan_tool_heavy_turns(msgs: &[CanonicalMessage]) -> Scan {
+    let diverse: Vec<(&CanonicalMessage, u64, usize, usize)> = tool_heavy_candidates(msgs)
+        .filter(|&(_, output, _, tools)| {
+            tools >= TOOL_HEAVY_MIN_TOOLS && output <= TOOL_HEAVY_MAX_OUTPUT
+        })
+        .collect();
+    let max = diverse
+        .iter()
+        .map(|&(_, _, calls, _)| calls)
+        .max()
+        .unwrap_or(0);
+    let detail = format!(
+        "max tool calls in a turn with {}+ tools and <= {} output tokens: {} (threshold {})",
+        TOOL_HEAVY_MIN_TOOLS, TOOL_HEAVY_MAX_OUTPUT, max, TOOL_HEAVY_MIN_CALLS
+    );
+    let findings = diverse
+        .into_iter()
+        .filter(|&(_, _, calls, _)| calls >= TOOL_HEAVY_MIN_CALLS)
+        .map(|(m, output, calls, tools)| Finding {
+            kind: FindingKind::ToolHeavyTurn,
+            description: format!(
+                "Turn {} made {} tool calls across {} tools but wrote only {} output tokens",
+                m.sequence, calls, tools, output
+            ),
+            evidence: vec![format!(
+                "turn {}: {} calls, {} tools, out {}",
+                m.sequence, calls, tools, output
+            )],
+            evidence_refs: vec![EvidenceRef::new(m, None)],
+            wasted_tokens: None,
+            wasted_cost_usd: None,
+            confidence: 0.4,
+        })
+        .collect();
+    Scan { findings, detail }
+}
+
+/// Fewest priced turns on each side of a model switch for a fair comparison.
+const MODEL_SWITCH_MIN_TURNS: usize = 3;
+/// Factor by which cost per output token must rise across the switch.
+const MODEL_SWITCH_COST_FACTOR: f64 = 1.5;
+
+/// A run of consecutive main-thread assistant turns on one model, priced.
+struct ModelRun<'a> {
+    model: &'a str,
+    turns: Vec<&'a CanonicalMessage>,
+}
+
+impl ModelRun<'_> {
+    fn cost(&self) -> f64 {
+        self.turns
+            .iter()
+            .filter_map(|m| m.usage.as_ref()?.effective_cost())
+            .sum()
+    }
+
+    fn output_tokens(&self) -> u64 {
+        self.turns
+            .iter()
+            .filter_map(|m| m.usage.as_ref())
+            .map(|u| u.output_tokens)
+            .sum()
+    }
+
+    fn cost_per_output(&self) -> Option<f64> {
+        let out = self.output_tokens();
+        (out > 0).then(|| self.cost() / out as f64)
+    }
+
+    fn errors_per_turn(&self) -> f64 {
+        let errors = self
+            .turns
+            .iter()
+            .flat_map(|m| &m.tool_calls)
+            .filter(|t| t.status == ToolStatus::Error)
+            .count();
+        errors as f64 / self.turns.len() as f64
+    }
+}
+
+/// Main-thread assistant turns with a model and a cost, split wherever the
+/// model changes. Subagents often run a cheaper model by design, so they're left out.
+fn model_runs(msgs: &[CanonicalMessage]) -> Vec<ModelRun<'_>> {
+    let mut runs: Vec<ModelRun> = Vec::new();
+    for m in msgs
+        .iter()
+        .filter(|m| m.role == Role::Assistant && !m.is_sidechain)
+    {
+        let Some(model) = m.model.as_deref() else {
+            continue;
+        };
+        if m.usage.as_ref().and_then(|u| u.effective_cost()).is_none() {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.model == model => run.turns.push(m),
+            _ => runs.push(ModelRun {
+                model,
+                turns: vec![m],
+            }),
+        }
+    }
+    runs
+}
+
+/// Adjacent runs long enough to compare, with the after/before ratio of their
+/// cost per output token.
+fn model_switches<'a>(
+    runs: &'a [ModelRun<'a>],
+) -> impl Iterator<Item = (&'a ModelRun<'a>, &'a ModelRun<'a>, f64)> {
+    runs.windows(2).filter_map(|w| {
+        let (before, after) = (&w[0], &w[1]);
+        if before.turns.len() < MODEL_SWITCH_MIN_TURNS || after.turns.len() < MODEL_SWITCH_MIN_TURNS
+        {
+            return None;
+        }
+        let (b, a) = (before.cost_per_output()?, after.cost_per_output()?);
+        (b > 0.0).then_some((before, after, a / b))
+    })
+}
+
+/// Detect a mid-session model change (usually an upgrade) after which each
+/// output token cost noticeably more while tool errors per turn didn't drop —
+/// the pricier model didn't visibly pay for itself. Waste is the premium over
+/// what the post-switch output would have cost at the earlier rate.
+fn scan_model_switches(msgs: &[CanonicalMessage]) -> Scan {
+    let runs = model_runs(msgs);
+    let switches: Vec<_> = model_switches(&runs).collect();
+    let max = switches.iter().map(|&(_, _, r)| r).fold(0.0, f64::max);
+    let detail = format!(
+        "{} model switch{} with {}+ turns each side; largest cost-per-output change {:.1}x (threshold {}x, errors/turn not down)",
+        switches.len(),
+        if switches.len() == 1 { "" } else { "es" },
+        MODEL_SWITCH_MIN_TURNS,
+        max,
+        MODEL_SWITCH_COST_FACTOR
+    );
+    let findings = switches
+        .into_iter()
+        .filter(|&(before, after, ratio)| {
+            ratio >= MODEL_SWITCH_COST_FACTOR && after.errors_per_turn() >= before.errors_per_turn()
+        })
+        .map(|(before, after, ratio)| {
+            let switch = after.turns[0];
+            let at_old_rate =
+                after.output_tokens() as f64 * before.cost_per_output().unwrap_or_default();
+            let premium = after.cost() - at_old_rate;
+            // Cost per output token relative to the earlier model, so the
+            // evidence carries no currency of its own
+            let side = |label: &str, run: &ModelRun, rate: f64| {
+                format!(
+                    "{}: {} turns, {:.1}x cost per output token, {:.2} tool errors/turn",
+                    label,
+                    run.turns.len(),
+                    rate,
+                    run.errors_per_turn()
+                )
+            };
+            Finding {
+                kind: FindingKind::CostlyModelSwitch,
+                description: format!(
+                    "Switching from {} to {} at turn {} made output {:.1}x as expensive without fewer tool errors",
+                    before.model, after.model, switch.sequence, ratio
+                ),
+                evidence: vec![
+                    format!(
+                        "turn {}: {} -> {}",
+                        switch.sequence, before.model, after.model
+                    ),
+                    side("before", before, 1.0),
+                    side("after", after, ratio),
+                ],
+                evidence_refs: vec![EvidenceRef::new(switch, None)],
+                wasted_tokens: None,
+                wasted_cost_usd: (premium > 0.0).then_some(premium),
+                confidence: 0.4,
+            }
+        })
+        .collect();
+    Scan { findings, detail }
+}
+
+/// Normalize a tool's args summary to the file it targets, dropping line ranges
+/// (`src/a.rs:10-20`, `src/a.rs#L10`) and unpacking JSON-shaped summaries, so
+/// ranged reads of one file group together.
+fn file_key(args: &str) -> String {
+    let args = args.trim();
+    if args.starts_with('{') {
+        if let Ok(v) = serde_json::from_str::<serde_json::Value>(args) {
+            for key in ["file_path", "filePath", "path", "file"] {
+                if let Some(p) = v.get(key).and_then(|x| x.as_str()) {
+                    return file_key(p);
+                }
+            }
+        }
+        return args.to_string();
+    }
+    let path = match args.rfind("#L") {
+        Some(i) => &args[..i],
+        None => args,
+    };
+    match path.rsplit_once(':') {
+        Some((file, range))
+            if !file.is_empty()
+                && !range.is_empty()
+                && range
+                    .chars()
+                    .all(|c| c.is_ascii_digit() || c == '-' || c == ',') =>
+        {
+            file.to_string()
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Cut `s` to at most `max` characters, ending with `…` when shortened. Counts
+/// characters, not bytes, so non-ASCII paths never split mid-character.
+fn truncate(s: &str, max: usize) -> String {
+    if s.char_indices().nth(max).is_none() {
+        return s.to_string();
+    }
+    let cut = s
+        .char_indices()
+        .nth(max.saturating_sub(1))
+        .map_or(0, |(i, _)| i);
+    format!("{}…", &s[..cut])
+}
+
+/// Token count for display: `999`, `1.5k`, `2.3M`. The unit is picked after
+/// rounding, so 999_999 reads `1.0M` rather than `1000.0k`. Shared with the
+/// renderers, so finding text and report tables agree.
+pub fn fmt_tokens(n: u64) -> String {
+    if n < 1_000 {
+        return n.to_string();
+    }
+    let thousands = (n as f64 / 100.0).round() / 10.0;
+    if thousands < 1_000.0 {
+        format!("{:.1}k", thousands)
+    } else {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, status: ToolStatus, error_class: Option<&str>) -> CanonicalTool {
+        CanonicalTool {
+            tool_name: name.to_string(),
+            category: canonical_tool_category(name),
+            call_id: format!("call-{}", name),
+            status,
+            error_class: error_class.map(str::to_string),
+            error_message: None,
+            args_summary: Some("/repo/missing.rs".to_string()),
+            output_summary: None,
+            output_bytes: None,
+            output_hash: None,
+            duration_ms: None,
+        }
+    }
+
+    fn turn(sequence: usize, role: Role, tool_calls: Vec<CanonicalTool>) -> CanonicalMessage {
+        CanonicalMessage {
+            message_id: format!("msg-{}", sequence),
+            session_id: "s".to_string(),
+            parent_id: None,
+            sequence,
+            role,
+            model: None,
+            ts: None,
+            usage: None,
+            tool_calls,
+            is_sidechain: false,
+            finish_reason: None,
+            content_chars: 0,
+            prompt_lines: Vec::new(),
+        }
+    }
+
+    fn usage(input_tokens: u64, output_tokens: u64, cost: f64) -> CanonicalUsage {
+        CanonicalUsage {
+            input_tokens,
+            output_tokens,
+            reasoning_tokens: 0,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            cache_write_1h_tokens: 0,
+            cost_observed_usd: None,
+            cost_estimated_usd: Some(cost),
+            latency_ms: None,
+            heuristic: false,
+        }
+    }
+
+    fn parsed(messages: Vec<CanonicalMessage>) -> ParsedSession {
+        let session = serde_json::from_value(serde_json::json!({
+            "session_id": "s",
+            "source_agent": "claude",
+            "source_path": "/tmp/s.jsonl",
+            "cwd": null,
+            "title": null,
+            "started_at": null,
+            "ended_at": null,
+            "model": null,
+            "message_count": messages.len(),
+            "total_cost_usd": null,
+            "total_input_tokens": 0,
+            "total_output_tokens": 0,
+        }))
+        .unwrap();
+        ParsedSession { session, messages }
+    }
+
+    struct FlagEveryTurn {
+        tool_centric: bool,
+    }
+
+    impl Detector for FlagEveryTurn {
+        fn detect(&self, parsed: &ParsedSession) -> Vec<Finding> {
+            parsed
+                .messages
+                .iter()
+                .map(|m| Finding {
+                    kind: FindingKind::Other("every_turn".to_string()),
+                    description: format!("turn {}", m.sequence),
+                    evidence: Vec::new(),
+                    evidence_refs: vec![EvidenceRef::new(m, None)],
+                    wasted_tokens: None,
+                    wasted_cost_usd: Some(m.sequence as f64),
+                    confidence: 1.0,
+                })
+                .collect()
+        }
+
+        fn tool_centric(&self) -> bool {
+            self.tool_centric
+        }
+    }
+
+    #[test]
+    fn registered_detectors_run_alongside_the_defaults() {
+        let chat = parsed(vec![
+            turn(1, Role::User, Vec::new()),
+            turn(2, Role::Assistant, Vec::new()),
+        ]);
+        let mut detectors = default_detectors();
+        detectors.push(Box::new(FlagEveryTurn {
+            tool_centric: false,
+        }));
+        let findings = detect_with(&chat, &detectors);
+        assert_eq!(findings.len(), 2);
+        // Sorted by wasted cost with the built-in findings
+        assert_eq!(findings[0].description, "turn 2");
+        assert_eq!(findings[0].kind.as_str(), "every_turn");
+
+        // Tool-centric detectors are skipped for chat sessions
+        let tool_only: Vec<Box<dyn Detector>> =
+            vec![Box::new(FlagEveryTurn { tool_centric: true })];
+        assert!(detect_with(&chat, &tool_only).is_empty());
+    }
+
+    #[test]
+    fn slicing_turns_limits_detector_scope() {
+        let retried = |seq: usize| {
+            let status = if seq % 10 == 6 {
+                ToolStatus::Success
+            } else {
+                ToolStatus::Error
+            };
+            vec![tool("Bash", status, None)]
+        };
+        let messages = (1..=30)
+            .map(|seq| match seq % 10 {
+                4..=6 => turn(seq, Role::Assistant, retried(seq)),
+                _ => turn(seq, Role::Assistant, Vec::new()),
+            })
+            .collect();
+        let mut session = parsed(messages);
+        let retry_turns = |p: &ParsedSession| -> Vec<usize> {
+            detect_inefficiencies(p)
+                .iter()
+                .filter(|f| f.kind == FindingKind::RetryLoop)
+                .map(|f| f.evidence_refs[0].sequence)
+                .collect()
+        };
+        assert_eq!(retry_turns(&session), vec![4, 14, 24]);
+
+        session.slice_turns(Some(10), Some(20));
+        assert_eq!(session.messages.len(), 11);
+        assert_eq!(retry_turns(&session), vec![14]);
+    }
+
+    #[test]
+    fn every_detector_refs_the_turns_its_evidence_cites() {
+        let assistant = |seq: usize, tools: Vec<CanonicalTool>, u: CanonicalUsage| {
+            let mut m = turn(seq, Role::Assistant, tools);
+            m.usage = Some(u);
+            m
+        };
+        let calls = |names: &[&str], status: ToolStatus, error_class: Option<&str>| {
+            names
+                .iter()
+                .map(|n| tool(n, status, error_class))
+                .collect::<Vec<_>>()
+        };
+
+        // Retries, churn, a missing-file loop, fan-out and rereads
+        let failed_read = || {
+            let mut t = calls(&["Grep"; 4], ToolStatus::Success, None);
+            t.insert(
+                0,
+                tool("Read", ToolStatus::Error, Some(ERROR_CLASS_NOT_FOUND)),
+            );
+            t
+        };
+        let mut loops = vec![turn(1, Role::User, Vec::new())];
+        for seq in [2, 4, 6] {
+            loops.push(turn(seq, Role::Assistant, failed_read()));
+            loops.push(turn(seq + 1, Role::User, Vec::new()));
+        }
+        loops.push(turn(8, Role::Assistant, Vec::new()));
+
+        let edits = vec![
+            turn(
+                1,
+                Role::Assistant,
+                calls(&["Edit"], ToolStatus::Error, None),
+            ),
+            turn(
+                2,
+                Role::Assistant,
+                calls(&["Edit"], ToolStatus::Error, None),
+            ),
+        ];
+
+        // Turn 4 bills a huge context for a near-empty reply and dominates the
+        // cost; turn 2's cache write is never read; turn 10 runs away and is cut off
+        let mut cached = usage(10_000, 100, 0.01);
+        cached.cache_write_tokens = 20_000;
+        let mut runaway = turn(10, Role::Assistant, Vec::new());
+        runaway.usage = Some(usage(10_000, 5_000, 0.01));
+        runaway.finish_reason = Some("max_tokens".to_string());
+        let usage_spikes = vec![
+            turn(1, Role::User, Vec::new()),
+            assistant(2, Vec::new(), cached),
+            assistant(4, Vec::new(), usage(1_000_000, 20, 0.9)),
+            assistant(6, Vec::new(), usage(10_000, 100, 0.01)),
+            assistant(8, Vec::new(), usage(10_000, 100, 0.01)),
+            runaway,
+        ];
+
+        let mut subagents = vec![
+            turn(1, Role::User, Vec::new()),
+            turn(
+                2,
+                Role::Assistant,
+                calls(&["Task"], ToolStatus::Success, None),
+            ),
+            turn(3, Role::User, Vec::new()),
+            turn(4, Role::Assistant, Vec::new()),
+        ];
+        for m in &mut subagents[2..] {
+            m.is_sidechain = true;
+        }
+
+        let aborted = vec![
+            turn(1, Role::User, Vec::new()),
+            turn(2, Role::Assistant, Vec::new()),
+            turn(3, Role::User, Vec::new()),
+            turn(4, Role::Assistant, Vec::new()),
+            turn(5, Role::Assistant, Vec::new()),
+        ];
+
+        let exploration = vec![
+            turn(
+                1,
+                Role::Assistant,
+                calls(&["Read"; 15], ToolStatus::Success, None),
+            ),
+            turn(
+                2,
+                Role::Assistant,
+                calls(&["Edit"], ToolStatus::Success, None),
+            ),
+        ];
+
+        let mut timed = calls(&["Bash"; 6], ToolStatus::Success, None);
+        for (i, t) in timed.iter_mut().enumerate() {
+            t.duration_ms = Some(if i == 5 { 60_000 } else { 1_000 });
+        }
+        let slow = vec![turn(1, Role::Assistant, timed)];
+
+        let big_output = || {
+            let mut t = calls(&["Bash"], ToolStatus::Success, None);
+            t[0].output_bytes = Some(4_096);
+            t[0].output_hash = Some(7);
+            t
+        };
+        let repeated_output = vec![
+            turn(1, Role::Assistant, big_output()),
+            turn(2, Role::Assistant, big_output()),
+        ];
+
+        let log: String = (0..40)
+            .map(|i| format!("log line {} that keeps coming back\n", i))
+            .collect();
+        let pasted = prompt_lines(&log);
+        let mut pastes = vec![
+            turn(1, Role::User, Vec::new()),
+            turn(2, Role::Assistant, Vec::new()),
+            turn(3, Role::User, Vec::new()),
+            turn(4, Role::Assistant, Vec::new()),
+        ];
+        pastes[0].prompt_lines = pasted.clone();
+        pastes[2].prompt_lines = pasted;
+
+        let heavy = calls(
+            &[
+                "Read", "Read", "Read", "Read", "Grep", "Grep", "Bash", "Bash",
+            ],
+            ToolStatus::Success,
+            None,
+        );
+        let tool_heavy = vec![assistant(1, heavy, usage(10_000, 20, 0.05))];
+
+        let switched = |seq: usize, model: &str, cost: f64| {
+            let mut m = assistant(
+                seq,
+                calls(&["Bash"], ToolStatus::Success, None),
+                usage(10_000, 1_000, cost),
+            );
+            m.model = Some(model.to_string());
+            m
+        };
+        let switch = (1..=3)
+            .map(|seq| switched(seq, "claude-sonnet-4-5", 0.045))
+            .chain((4..=6).map(|seq| switched(seq, "claude-opus-4-1", 0.225)))
+            .collect();
+
+        let sessions: Vec<ParsedSession> = [
+            loops,
+            edits,
+            usage_spikes,
+            subagents,
+            aborted,
+            exploration,
+            slow,
+            repeated_output,
+            pastes,
+            tool_heavy,
+            switch,
+        ]
+        .into_iter()
+        .map(parsed)
+        .enumerate()
+        .map(|(i, mut p)| {
+            p.session.ended_abnormally = i == 4;
+            p
+        })
+        .collect();
+
+        let detectors = default_detectors();
+        let mut fired: HashSet<String> = HashSet::new();
+        for session in &sessions {
+            let (findings, traces) = detect_and_explain(session, &detectors);
+            fired.extend(
+                traces
+                    .into_iter()
+                    .filter(|t| t.fired > 0)
+                    .map(|t| t.detector),
+            );
+            for f in &findings {
+                assert!(!f.evidence_refs.is_empty(), "{:?} has no refs", f.kind);
+                for r in &f.evidence_refs {
+                    let m = session
+                        .messages
+                        .iter()
+                        .find(|m| m.sequence == r.sequence)
+                        .unwrap_or_else(|| panic!("{:?} refs missing turn {}", f.kind, r.sequence));
+                    assert_eq!(r.message_id, m.message_id);
+                }
+                // A line about one turn ("turn 12: ...") has a ref to that turn
+                let single = f.evidence.iter().filter_map(|e| {
+                    let digits = e.strip_prefix("turn ")?;
+                    let end = digits.find(|c: char| !c.is_ascii_digit())?;
+                    digits[..end].parse::<usize>().ok()
+                });
+                for seq in single {
+                    assert!(
+                        f.evidence_refs.iter().any(|r| r.sequence == seq),
+                        "{:?} cites turn {} without a ref",
+                        f.kind,
+                        seq
+                    );
+                }
+            }
+        }
+        let mut missing: Vec<&str> = detectors
+            .iter()
+            .map(|d| d.name())
+            .filter(|name| !fired.contains(*name))
+            .collect();
+        missing.sort_unstable();
+        assert!(missing.is_empty(), "no fixture fires {:?}", missing);
+
+        let refs = |i: usize, kind: FindingKind| -> Vec<usize> {
+            detect_inefficiencies(&sessions[i])
+                .iter()
+                .filter(|f| f.kind == kind)
+                .flat_map(|f| f.evidence_refs.iter().map(|r| r.sequence))
+                .collect()
+        };
+        assert_eq!(refs(3, FindingKind::SubagentOverhead), [3, 4]);
+        assert_eq!(refs(4, FindingKind::AbortedSession), [4, 5]);
+        let retry = detect_inefficiencies(&sessions[0]);
+        assert!(retry
+            .iter()
+            .filter(|f| f.kind == FindingKind::RetryLoop)
+            .flat_map(|f| &f.evidence_refs)
+            .all(|r| r.tool_name.as_deref() == Some("Read")));
+    }
+
+    #[test]
+    fn retries_that_succeed_only_count_failed_attempts() {
+        let chain = |last: ToolStatus| {
+            vec![
+                turn(
+                    1,
+                    Role::Assistant,
+                    vec![tool("Bash", ToolStatus::Error, None)],
+                ),
+                turn(
+                    2,
+                    Role::Assistant,
+                    vec![tool("Bash", ToolStatus::Error, None)],
+                ),
+                turn(3, Role::Assistant, vec![tool("Bash", last, None)]),
+            ]
+        };
+        let costs: HashMap<usize, f64> = [(1, 1.0), (2, 2.0), (3, 4.0)].into();
+
+        let soft = &scan_retry_loops(&chain(ToolStatus::Success), &costs, 1, 50).findings[0];
+        assert_eq!(soft.description, "Bash failed 2 times before succeeding");
+        // The successful retry did needed work; only turn 2 was wasted
+        assert_eq!(soft.wasted_cost_usd, Some(2.0));
+        assert!((soft.confidence - 0.45).abs() < 1e-9);
+
+        let hard = &scan_retry_loops(&chain(ToolStatus::Error), &costs, 1, 50).findings[0];
+        assert_eq!(hard.wasted_cost_usd, Some(6.0));
+        assert_eq!(hard.confidence, 0.85);
+    }
+
+    #[test]
+    fn truncation_counts_characters_not_bytes() {
+        assert_eq!(truncate("/tmp/ok.rs", 60), "/tmp/ok.rs");
+        let path = format!("/home/zoë/{}", "é".repeat(80));
+        let short = truncate(&path, 60);
+        assert_eq!(short.chars().count(), 60);
+        assert!(short.ends_with('…'));
+
+        let mut miss = tool("Read", ToolStatus::Error, Some(ERROR_CLASS_NOT_FOUND));
+        miss.args_summary = Some(path);
+        let msgs = vec![
+            turn(1, Role::Assistant, vec![miss.clone()]),
+            turn(2, Role::Assistant, vec![miss]),
+        ];
+        let findings = scan_missing_file_loops(&msgs, &HashMap::new()).findings;
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].description.contains(&short));
+    }
+
+    #[test]
+    fn turns_cut_at_the_token_limit_are_flagged() {
+        let finished = |seq: usize, reason: &str| {
+            let mut m = turn(seq, Role::Assistant, Vec::new());
+            m.finish_reason = Some(reason.to_string());
+            m
+        };
+        let msgs = vec![
+            finished(1, "end_turn"),
+            finished(2, "max_tokens"),
+            finished(3, "tool_use"),
+            finished(4, "length"),
+        ];
+        let costs: HashMap<usize, f64> = [(1, 1.0), (2, 2.0), (4, 0.5)].into();
+
+        let findings = scan_output_truncation(&msgs, &costs).findings;
+        assert_eq!(findings.len(), 1);
+        let f = &findings[0];
+        assert_eq!(f.description, "2 turns stopped at the output token limit");
+        assert_eq!(f.evidence, ["turn 2: max_tokens", "turn 4: length"]);
+        assert_eq!(f.wasted_cost_usd, Some(2.5));
+
+        assert!(scan_output_truncation(&msgs[..1], &costs)
+            .findings
+            .is_empty());
+    }
+
+    #[test]
+    fn cache_writes_never_read_back_are_flagged() {
+        let cached = |seq: usize, write: u64, read: u64| {
+            let mut m = turn(seq, Role::Assistant, Vec::new());
+            m.model = Some("claude-sonnet-4".to_string());
+            let mut u = usage(1_000, 100, 0.1);
+            u.cache_write_tokens = write;
+            u.cache_read_tokens = read;
+            m.usage = Some(u);
+            m
+        };
+        let msgs = vec![
+            cached(1, 20_000, 0),
+            cached(2, 15_000, 20_000),
+            cached(3, 0, 5_000),
+            // The session ends here, so this write can't be judged
+            cached(4, 50_000, 0),
+        ];
+
+        let findings = scan_unreused_cache_writes(&msgs).findings;
+        assert_eq!(findings.len(), 1);
+        let f = &findings[0];
+        assert_eq!(f.evidence_refs.len(), 1);
+        assert_eq!(f.evidence_refs[0].sequence, 2);
+        assert_eq!(f.wasted_tokens, Some(15_000));
+        let price = lookup_price("claude-sonnet-4").unwrap();
+        let premium = 0.015 * (price.cache_write_per_mtok - price.input_per_mtok);
+        assert!((f.wasted_cost_usd.unwrap() - premium).abs() < 1e-9);
+    }
+
+    #[test]
+    fn retrying_transient_errors_is_low_confidence() {
+        let call = |status, message: Option<&str>| {
+            let mut t = tool("Bash", status, None);
+            t.error_message = message.map(str::to_string);
+            t
+        };
+        let session = |message| {
+            vec![
+                turn(
+                    1,
+                    Role::Assistant,
+                    vec![call(ToolStatus::Error, Some(message))],
+                ),
+                turn(
+                    2,
+                    Role::Assistant,
+                    vec![call(ToolStatus::Error, Some(message))],
+                ),
+                turn(3, Role::Assistant, vec![call(ToolStatus::Success, None)]),
+            ]
+        };
+
+        let timeout = session("curl: (28) Operation timed out after 30000 ms");
+        assert!(timeout[0].tool_calls[0].is_retryable_error());
+        let f = &scan_retry_loops(&timeout, &HashMap::new(), 1, 50).findings[0];
+        assert_eq!(f.confidence, 0.2);
+        assert_eq!(f.wasted_cost_usd, None);
+
+        let syntax = session("error: expected `;`, found `}`");
+        assert!(!syntax[0].tool_calls[0].is_retryable_error());
+        let f = &scan_retry_loops(&syntax, &HashMap::new(), 1, 50).findings[0];
+        assert!(f.confidence > 0.2);
+    }
+
+    #[test]
+    fn model_upgrade_without_fewer_errors_is_flagged() {
+        let priced = |seq: usize, model: &str, cost: f64, errors: usize| {
+            let mut m = turn(
+                seq,
+                Role::Assistant,
+                (0..errors)
+                    .map(|_| tool("Bash", ToolStatus::Error, None))
+                    .collect(),
+            );
+            m.model = Some(model.to_string());
+            m.usage = Some(usage(10_000, 1_000, cost));
+            m
+        };
+        let session = |errors_after: usize| -> Vec<CanonicalMessage> {
+            (1..=3)
+                .map(|seq| priced(seq, "claude-sonnet-4-5", 0.045, 1))
+                .chain((4..=6).map(|seq| priced(seq, "claude-opus-4-1", 0.225, errors_after)))
+                .collect()
+        };
+
+        let findings = scan_model_switches(&session(1)).findings;
+        assert_eq!(findings.len(), 1);
+        let f = &findings[0];
+        assert_eq!(f.kind, FindingKind::CostlyModelSwitch);
+        assert_eq!(f.evidence_refs[0].sequence, 4);
+        // 3 opus turns at 5x the sonnet rate: 0.675 spent, 0.135 at the old rate
+        let wasted = f.wasted_cost_usd.unwrap();
+        assert!((wasted - 0.54).abs() < 1e-9, "{}", wasted);
+        // Costs go in wasted_cost_usd for the renderers to convert; the evidence
+        // compares rates without naming a currency
+        assert!(
+            f.evidence[2].contains("5.0x cost per output token"),
+            "{:?}",
+            f.evidence
+        );
+        assert!(
+            f.evidence.iter().all(|e| !e.contains('$')),
+            "{:?}",
+            f.evidence
+        );
+
+        // The upgrade stopped the errors, so it paid for itself
+        assert!(scan_model_switches(&session(0)).findings.is_empty());
+    }
+
+    #[test]
+    fn explanations_show_near_misses_against_detector_thresholds() {
+        let near_miss = parsed(vec![
+            turn(1, Role::User, Vec::new()),
+            turn(
+                2,
+                Role::Assistant,
+                vec![
+                    tool("Read", ToolStatus::Success, None),
+                    tool("Read", ToolStatus::Success, None),
+                    tool("Read", ToolStatus::Success, None),
+                    tool("Edit", ToolStatus::Error, None),
+                ],
+            ),
+        ]);
+        let detectors = default_detectors();
+        let traces = explain_with(&near_miss, &detectors);
+        let trace = |name: &str| traces.iter().find(|t| t.detector == name).unwrap();
+
+        let fanout = trace("tool_fanout");
+        assert_eq!(fanout.fired, 0);
+        assert_eq!(
+            fanout.detail.as_deref(),
+            Some("max calls to one tool in a turn: 3 (threshold 4)")
+        );
+        let cascade = trace("edit_cascade");
+        assert_eq!(cascade.fired, 0);
+        assert_eq!(
+            cascade.detail.as_deref(),
+            Some("max failed edits on one file: 1 (threshold 2)")
+        );
+
+        // The trace counts the same findings a plain run produces
+        for (d, t) in detectors.iter().zip(&traces) {
+            assert_eq!(d.detect(&near_miss).len(), t.fired, "{}", t.detector);
+        }
+        // And one pass yields both, with the findings a plain run returns
+        let (findings, one_pass) = detect_and_explain(&near_miss, &detectors);
+        let kinds = |fs: &[Finding]| fs.iter().map(|f| f.kind.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds(&findings),
+            kinds(&detect_with(&near_miss, &detectors))
+        );
+        assert_eq!(
+            findings.len(),
+            one_pass.iter().map(|t| t.fired).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn one_dominant_turn_is_flagged_as_cost_concentration() {
+        let priced = |costs: &[f64]| -> Vec<CanonicalMessage> {
+            costs
+                .iter()
+                .enumerate()
+                .map(|(i, &cost)| {
+                    let mut m = turn(i + 1, Role::Assistant, Vec::new());
+                    m.usage = Some(usage(1_000, 100, cost));
+                    m
+                })
+                .collect()
+        };
+
+        let scan = scan_cost_concentration(&priced(&[0.1, 0.7, 0.2]));
+        assert_eq!(scan.findings.len(), 1);
+        let f = &scan.findings[0];
+        assert_eq!(f.kind, FindingKind::CostConcentration);
+        assert_eq!(f.evidence_refs[0].sequence, 2);
+        assert!(f.description.contains("70%"), "{}", f.description);
+
+        let even = scan_cost_concentration(&priced(&[0.3, 0.4, 0.3]));
+        assert!(even.findings.is_empty());
+        assert_eq!(even.detail, "top turn 2 is 40% of cost (threshold 60%)");
+
+        // Two turns are too few to call either one dominant
+        assert!(scan_cost_concentration(&priced(&[0.1, 0.9]))
+            .findings
+            .is_empty());
+    }
+
+    #[test]
+    fn every_finding_kind_is_in_the_catalog() {
+        use FindingKind::*;
+        let kinds = [
+            RetryLoop,
+            EditCascade,
+            ToolFanout,
+            RedundantReread,
+            ContextBloat,
+            ErrorRepromptChurn,
+            SubagentOverhead,
+            MissingFileLoop,
+            OutputTruncation,
+            UnreusedCacheWrite,
+            CostConcentration,
+            ExplorationOverhead,
+            SlowTool,
+            RunawayOutput,
+            LowYieldTurn,
+            AbortedSession,
+            RedundantOutput,
+            RepeatedPaste,
+            ToolHeavyTurn,
+            CostlyModelSwitch,
+        ];
+        for kind in &kinds {
+            // No wildcard: a new kind stops this compiling until it's listed above
+            match kind {
+                RetryLoop | EditCascade | ToolFanout | RedundantReread | ContextBloat
+                | ErrorRepromptChurn | SubagentOverhead | MissingFileLoop | OutputTruncation
+                | UnreusedCacheWrite | CostConcentration | ExplorationOverhead | SlowTool
+                | RunawayOutput | LowYieldTurn | AbortedSession | RedundantOutput
+                | RepeatedPaste | ToolHeavyTurn | CostlyModelSwitch => {}
+                Other(_) => unreachable!(),
+            }
+            assert!(
+                FINDING_CATALOG.iter().any(|info| &info.kind == kind),
+                "{} missing from FINDING_CATALOG",
+                kind
+            );
+        }
+        assert_eq!(FINDING_CATALOG.len(), kinds.len());
+    }
+
+    #[test]
+    fn long_read_only_prefix_before_an_edit_is_exploration_overhead() {
+        let session = |edit: bool| -> Vec<CanonicalMessage> {
+            let mut msgs: Vec<CanonicalMessage> = (1..=16)
+                .map(|seq| {
+                    let mut m = turn(
+                        seq,
+                        Role::Assistant,
+                        vec![tool("Read", ToolStatus::Success, None)],
+                    );
+                    m.model = Some("claude-sonnet-4".to_string());
+                    let mut u = usage(100, 20, 0.01);
+                    u.cache_read_tokens = 1_000_000;
+                    m.usage = Some(u);
+                    m
+                })
+                .collect();
+            if edit {
+                msgs.push(turn(
+                    17,
+                    Role::Assistant,
+                    vec![tool("Edit", ToolStatus::Success, None)],
+                ));
+            }
+            msgs
+        };
+
+        let scan = scan_exploration_overhead(&session(true));
+        assert_eq!(scan.findings.len(), 1);
+        let f = &scan.findings[0];
+        assert_eq!(f.kind, FindingKind::ExplorationOverhead);
+        assert_eq!(f.evidence_refs.len(), 16);
+        // Only turn 16 is past the 15-call threshold: 1M cache-read tokens at $0.30/M
+        assert_eq!(f.wasted_tokens, Some(1_000_000));
+        assert!((f.wasted_cost_usd.unwrap() - 0.30).abs() < 1e-9);
+
+        // Reading without ever editing is a review, not over-exploration
+        assert!(scan_exploration_overhead(&session(false))
+            .findings
+            .is_empty());
+    }
+
+    #[test]
+    fn one_slow_call_among_fast_ones_is_flagged() {
+        let timed = |name: &str, ms: u64| {
+            let mut t = tool(name, ToolStatus::Success, None);
+            t.duration_ms = Some(ms);
+            t
+        };
+        let mut msgs: Vec<CanonicalMessage> = (1..=8)
+            .map(|seq| turn(seq, Role::Assistant, vec![timed("Read", 200 + seq as u64)]))
+            .collect();
+        msgs.push(turn(9, Role::Assistant, vec![timed("Bash", 45_000)]));
+
+        let scan = scan_slow_tools(&msgs);
+        assert_eq!(scan.findings.len(), 1);
+        let f = &scan.findings[0];
+        assert_eq!(f.kind, FindingKind::SlowTool);
+        assert_eq!(f.evidence_refs[0].sequence, 9);
+        assert_eq!(f.evidence_refs[0].tool_name.as_deref(), Some("Bash"));
+        assert!(
+            f.description.starts_with("'Bash' took 45.0s"),
+            "{}",
+            f.description
+        );
+
+        // A uniformly slow session has no outlier
+        let slow: Vec<CanonicalMessage> = (1..=8)
+            .map(|seq| turn(seq, Role::Assistant, vec![timed("Bash", 45_000)]))
+            .collect();
+        assert!(scan_slow_tools(&slow).findings.is_empty());
+    }
+
+    #[test]
+    fn one_oversized_text_only_turn_is_runaway_output() {
+        let reply = |seq: usize, output: u64, tools: Vec<CanonicalTool>| {
+            let mut m = turn(seq, Role::Assistant, tools);
+            m.model = Some("claude-sonnet-4".to_string());
+            m.usage = Some(usage(2_000, output, 0.01));
+            m
+        };
+        let mut msgs: Vec<CanonicalMessage> = (1..=6).map(|seq| reply(seq, 300, vec![])).collect();
+        msgs.push(reply(7, 20_000, vec![]));
+        // Just as large, but it wrote through a tool, so it isn't runaway text
+        msgs.push(reply(
+            8,
+            20_000,
+            vec![tool("Write", ToolStatus::Success, None)],
+        ));
+
+        let scan = scan_runaway_output(&msgs);
+        assert_eq!(scan.findings.len(), 1);
+        let f = &scan.findings[0];
+        assert_eq!(f.kind, FindingKind::RunawayOutput);
+        assert_eq!(f.evidence_refs[0].sequence, 7);
+        assert_eq!(f.wasted_tokens, Some(20_000));
+        // Output side only: 20k tokens at $15/Mtok
+        assert!((f.wasted_cost_usd.unwrap() - 0.30).abs() < 1e-9);
+
+        // Without the outlier, nothing clears the floor
+        assert!(scan_runaway_output(&msgs[..6]).findings.is_empty());
+    }
+
+    #[test]
+    fn a_long_retry_storm_is_captured_as_one_chain() {
+        // Twelve failing Bash calls, with one unrelated turn midway (within the gap)
+        let msgs: Vec<CanonicalMessage> = (1..=13)
+            .map(|seq| {
+                let name = if seq == 7 { "Read" } else { "Bash" };
+                let status = if seq == 7 {
+                    ToolStatus::Success
+                } else {
+                    ToolStatus::Error
+                };
+                turn(seq, Role::Assistant, vec![tool(name, status, None)])
+            })
+            .collect();
+        let costs: HashMap<usize, f64> = (1..=13).map(|seq| (seq, 1.0)).collect();
+
+        let scan = scan_retry_loops(&msgs, &costs, 1, 50);
+        assert_eq!(scan.findings.len(), 1);
+        let f = &scan.findings[0];
+        assert_eq!(f.description, "Bash retried 11 times after failure");
+        assert_eq!(f.evidence.len(), 12);
+        assert_eq!(f.evidence_refs.last().unwrap().sequence, 13);
+        // Every retry after the first attempt; the Read turn isn't part of the chain
+        assert_eq!(f.wasted_cost_usd, Some(11.0));
+
+        // The safety cap ends the chain early; the rest starts a new one
+        let capped = scan_retry_loops(&msgs, &costs, 1, 5);
+        let lengths: Vec<usize> = capped.findings.iter().map(|f| f.evidence.len()).collect();
+        assert_eq!(lengths, [5, 5, 2]);
+    }
+
+    #[test]
+    fn tool_heavy_turns_need_many_diverse_calls_and_little_output() {
+        let busy = |seq: usize, names: &[&str], output: u64| -> CanonicalMessage {
+            let calls = names
+                .iter()
+                .map(|n| tool(n, ToolStatus::Success, None))
+                .collect();
+            let mut m = turn(seq, Role::Assistant, calls);
+            m.usage = Some(usage(10_000, output, 0.05));
+            m
+        };
+        let diverse = [
+            "Read", "Read", "Read", "Read", "Grep", "Grep", "Bash", "Bash",
+        ];
+        assert_eq!(diverse.len(), TOOL_HEAVY_MIN_CALLS);
+        let msgs = vec![
+            turn(1, Role::User, Vec::new()),
+            busy(2, &diverse, TOOL_HEAVY_MAX_OUTPUT),
+            // Same-tool fan-out is ToolFanout's finding, not this one
+            busy(3, &["Read"; TOOL_HEAVY_MIN_CALLS], 20),
+            // Diverse calls that came with a real written answer
+            busy(4, &diverse, TOOL_HEAVY_MAX_OUTPUT + 1),
+            // One call short of the threshold
+            busy(5, &diverse[1..], 20),
+        ];
+
+        let scan = scan_tool_heavy_turns(&msgs);
+        let seqs: Vec<usize> = scan
+            .findings
+            .iter()
+            .map(|f| f.evidence_refs[0].sequence)
+            .collect();
+        assert_eq!(seqs, [2]);
+        assert_eq!(scan.findings[0].kind, FindingKind::ToolHeavyTurn);
+        assert!(scan.findings[0].wasted_cost_usd.is_none());
+        assert_eq!(
+            scan.detail,
+            "max tool calls in a turn with 3+ tools and <= 200 output tokens: 8 (threshold 8)"
+        );
+    }
+
+    #[test]
+    fn tool_less_sessions_classify_as_chat() {
+        // Turn 2 re-reads a heavy context to give a near-empty reply
+        let mut heavy = turn(2, Role::Assistant, Vec::new());
+        heavy.usage = Some(usage(LOW_YIELD_MIN_INPUT + 50_000, 20, 0.45));
+        let chat = parsed(vec![
+            turn(1, Role::User, Vec::new()),
+            heavy,
+            turn(3, Role::User, Vec::new()),
+            turn(4, Role::Assistant, Vec::new()),
+        ]);
+        assert_eq!(SessionKind::of(&chat), SessionKind::Chat);
+        assert_eq!(SessionKind::of(&chat).to_string(), "chat");
+
+        // Low-yield turns are text-only by definition, so chats still get them
+        let low_yield: Vec<usize> = detect_inefficiencies(&chat)
+            .iter()
+            .filter(|f| f.kind == FindingKind::LowYieldTurn)
+            .flat_map(|f| f.evidence_refs.iter().map(|r| r.sequence))
+            .collect();
+        assert_eq!(low_yield, [2]);
+
+        // --explain says why tool-centric detectors didn't run
+        let retry = explain_with(&chat, &[Box::new(RetryLoopDetector::default())]);
+        assert_eq!(
+            retry[0].detail.as_deref(),
+            Some("skipped: chat session (no tool calls)")
+        );
+
+        let agentic = parsed(vec![
+            turn(1, Role::User, Vec::new()),
+            turn(
+                2,
+                Role::Assistant,
+                vec![tool("Read", ToolStatus::Success, None)],
+            ),
+        ]);
+        assert_eq!(SessionKind::of(&agentic), SessionKind::Agentic);
     }
 }
@@ -1,7 +1,9 @@
 pub mod detectors;
 pub mod pricing;
 pub mod schema;
+pub mod score;
 
 pub use detectors::*;
 pub use pricing::*;
 pub use schema::*;
+pub use score::*;
@@ -1,6 +1,9 @@
 /// Model pricing catalog (USD per 1M tokens, as of early 2026).
 /// Prices are (input_per_mtok, output_per_mtok, cache_read_per_mtok, cache_write_per_mtok).
 /// cache_read/write may be None if not applicable.
+use crate::schema::CanonicalUsage;
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
 
 #[derive(Debug, Clone, Copy)]
 pub struct ModelPrice {
@@ -20,6 +23,12 @@ impl ModelPrice {
         }
     }
 
+    /// Price of cache writes with a 1-hour TTL: twice the input price, against
+    /// 1.25x for the default 5-minute tier (Anthropic).
+    pub fn cache_write_1h_per_mtok(&self) -> f64 {
+        self.input_per_mtok * 2.0
+    }
+
     pub fn estimate_cost(&self, input: u64, output: u64, cache_read: u64, cache_write: u64) -> f64 {
         let m = 1_000_000.0_f64;
         (input as f64 / m) * self.input_per_mtok
@@ -29,84 +38,298 @@ impl ModelPrice {
     }
 }
 
-/// Look up price by model ID string (case-insensitive prefix match).
+/// A calendar month selecting which price table applies (`--pricing-date 2024-06`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PricingDate {
+    pub year: u16,
+    pub month: u8,
+}
+
+impl PricingDate {
+    pub const fn new(year: u16, month: u8) -> Self {
+        Self { year, month }
+    }
+}
+
+impl std::fmt::Display for PricingDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}", self.year, self.month)
+    }
+}
+
+impl std::str::FromStr for PricingDate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("invalid pricing date '{}' (expected YYYY-MM)", s);
+        let (y, m) = s.split_once('-').ok_or_else(err)?;
+        let year: u16 = y.parse().map_err(|_| err())?;
+        let month: u8 = m.parse().map_err(|_| err())?;
+        if y.len() != 4 || !(1..=12).contains(&month) {
+            return Err(err());
+        }
+        Ok(Self::new(year, month))
+    }
+}
+
+/// Month the current catalog's prices were last checked against provider price lists.
+pub const CATALOG_AS_OF: PricingDate = PricingDate::new(2026, 1);
+
+/// Bump on any change to catalog or snapshot prices; cached parses carry
+/// parse-time costs and are keyed on it.
+pub const CATALOG_VERSION: u32 = 1;
+
+/// A period when some models were priced differently from the current catalog.
+struct PriceSnapshot {
+    /// First month the prices applied
+    from: PricingDate,
+    /// Month they were replaced
+    until: PricingDate,
+    /// Prices for the models that differed; `None` defers to the current catalog
+    lookup: fn(&str) -> Option<ModelPrice>,
+}
+
+/// Historical price tables, for reproducing costs as they were first reported.
+const SNAPSHOTS: &[PriceSnapshot] = &[
+    PriceSnapshot {
+        from: PricingDate::new(2024, 5),
+        until: PricingDate::new(2024, 8),
+        lookup: gpt_4o_launch_prices,
+    },
+    PriceSnapshot {
+        from: PricingDate::new(2024, 11),
+        until: PricingDate::new(2024, 12),
+        lookup: claude_3_5_haiku_launch_prices,
+    },
+];
+
+fn gpt_4o_launch_prices(m: &str) -> Option<ModelPrice> {
+    (m.contains("gpt-4o") && !m.contains("gpt-4o-mini"))
+        .then_some(ModelPrice::new(5.0, 15.0, 5.0, 5.0))
+}
+
+fn claude_3_5_haiku_launch_prices(m: &str) -> Option<ModelPrice> {
+    (m.contains("claude-3-5-haiku") || m.contains("claude-3.5-haiku"))
+        .then_some(ModelPrice::new(1.0, 5.0, 0.10, 1.25))
+}
+
+static PRICING_DATE: RwLock<Option<PricingDate>> = RwLock::new(None);
+
+/// Price every lookup as of `date` instead of the current catalog (default).
+pub fn set_pricing_date(date: Option<PricingDate>) {
+    if let Ok(mut d) = PRICING_DATE.write() {
+        *d = date;
+    }
+}
+
+/// The pricing date set with [`set_pricing_date`], if any.
+pub fn pricing_date() -> Option<PricingDate> {
+    PRICING_DATE.read().ok().and_then(|d| *d)
+}
+
+/// Look up price by model ID string (case-insensitive prefix match), as of the
+/// pricing date if one is set.
 pub fn lookup_price(model_id: &str) -> Option<ModelPrice> {
+    lookup_price_at(model_id, pricing_date())
+}
+
+/// Look up a model's price as of `date`: a historical snapshot covering that
+/// month if it priced the model, otherwise the current catalog.
+pub fn lookup_price_at(model_id: &str, date: Option<PricingDate>) -> Option<ModelPrice> {
+    lookup_priced_at(model_id, date).map(|(price, _)| price)
+}
+
+/// How specifically a model ID matched the catalog. Ordered from most to least
+/// certain, so the weakest of several is their `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingConfidence {
+    /// A catalog entry for this model (any dated variant)
+    Exact,
+    /// A catch-all for the model's family, e.g. every `gpt-4*` not listed separately
+    FamilyDefault,
+    /// Only the vendor was recognized; priced as a typical model of that vendor
+    /// (unknown Claude models as Sonnet)
+    GenericFallback,
+}
+
+impl PricingConfidence {
+    /// Short description for reports.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            PricingConfidence::Exact => "exact",
+            PricingConfidence::FamilyDefault => "family default (estimated)",
+            PricingConfidence::GenericFallback => "generic fallback (guessed)",
+        }
+    }
+}
+
+/// [`lookup_price`] along with how much to trust the match, as of the pricing
+/// date if one is set.
+pub fn lookup_price_with_confidence(model_id: &str) -> Option<(ModelPrice, PricingConfidence)> {
+    lookup_priced_at(model_id, pricing_date())
+}
+
+fn lookup_priced_at(
+    model_id: &str,
+    date: Option<PricingDate>,
+) -> Option<(ModelPrice, PricingConfidence)> {
     let m = model_id.to_lowercase();
+    date.and_then(|d| {
+        SNAPSHOTS
+            .iter()
+            .filter(|s| s.from <= d && d < s.until)
+            .find_map(|s| (s.lookup)(&m))
+    })
+    .map(|price| (price, PricingConfidence::Exact))
+    .or_else(|| current_price(&m))
+}
+
+/// Price from the current catalog and how specifically it matched; `m` is the
+/// lowercased model ID.
+fn current_price(m: &str) -> Option<(ModelPrice, PricingConfidence)> {
+    use PricingConfidence::*;
+
     // Claude models
     if m.contains("claude-opus-4") || m.contains("claude-4-opus") {
-        return Some(ModelPrice::new(15.0, 75.0, 1.50, 3.75));
+        return Some((ModelPrice::new(15.0, 75.0, 1.50, 3.75), Exact));
+    }
+    // Haiku before Sonnet so family-first ids (`claude-4-5-haiku`) aren't
+    // caught by a looser Sonnet pattern
+    if m.contains("claude-haiku-4")
+        || m.contains("claude-4-haiku")
+        || m.contains("claude-4-5-haiku")
+        || m.contains("haiku-4-5")
+    {
+        return Some((ModelPrice::new(0.80, 4.0, 0.08, 1.0), Exact));
     }
     if m.contains("claude-sonnet-4")
         || m.contains("claude-4-sonnet")
-        || m.contains("claude-4-5")
-        || m.contains("claude-sonnet-4-5")
+        || m.contains("claude-4-5-sonnet")
     {
-        return Some(ModelPrice::new(3.0, 15.0, 0.30, 3.75));
-    }
-    if m.contains("claude-haiku-4") || m.contains("claude-4-haiku") || m.contains("haiku-4-5") {
-        return Some(ModelPrice::new(0.80, 4.0, 0.08, 1.0));
+        return Some((ModelPrice::new(3.0, 15.0, 0.30, 3.75), Exact));
     }
     if m.contains("claude-3-5-sonnet") || m.contains("claude-3.5-sonnet") {
-        return Some(ModelPrice::new(3.0, 15.0, 0.30, 3.75));
+        return Some((ModelPrice::new(3.0, 15.0, 0.30, 3.75), Exact));
     }
     if m.contains("claude-3-5-haiku") || m.contains("claude-3.5-haiku") {
-        return Some(ModelPrice::new(0.80, 4.0, 0.08, 1.0));
+        return Some((ModelPrice::new(0.80, 4.0, 0.08, 1.0), Exact));
     }
     if m.contains("claude-3-opus") {
-        return Some(ModelPrice::new(15.0, 75.0, 1.50, 3.75));
+        return Some((ModelPrice::new(15.0, 75.0, 1.50, 3.75), Exact));
     }
     if m.contains("claude-3-sonnet") {
-        return Some(ModelPrice::new(3.0, 15.0, 0.30, 3.75));
+        return Some((ModelPrice::new(3.0, 15.0, 0.30, 3.75), Exact));
     }
     if m.contains("claude-3-haiku") {
-        return Some(ModelPrice::new(0.25, 1.25, 0.03, 0.31));
+        return Some((ModelPrice::new(0.25, 1.25, 0.03, 0.31), Exact));
     }
     if m.contains("claude") {
         // Unknown Claude — use Sonnet pricing as safe default
-        return Some(ModelPrice::new(3.0, 15.0, 0.30, 3.75));
+        return Some((ModelPrice::new(3.0, 15.0, 0.30, 3.75), GenericFallback));
     }
     // OpenAI models
     if m.contains("gpt-5") {
-        return Some(ModelPrice::new(10.0, 40.0, 2.50, 10.0));
+        return Some((ModelPrice::new(10.0, 40.0, 2.50, 10.0), FamilyDefault));
     }
-    if m.contains("o3-mini") || m.contains("o4-mini") {
-        return Some(ModelPrice::new(1.10, 4.40, 0.275, 1.10));
+    // o-series ids are short enough to appear inside unrelated names, so they
+    // must stand alone as a word (`o3`, `openai/o4-mini`, not `proto3`)
+    if has_word(m, "o3-mini") || has_word(m, "o4-mini") {
+        return Some((ModelPrice::new(1.10, 4.40, 0.275, 1.10), Exact));
     }
-    if m.contains("o3") || m.contains("o4") {
-        return Some(ModelPrice::new(10.0, 40.0, 2.50, 10.0));
+    if has_word(m, "o3") || has_word(m, "o4") {
+        return Some((ModelPrice::new(10.0, 40.0, 2.50, 10.0), Exact));
     }
     if m.contains("gpt-4o-mini") {
-        return Some(ModelPrice::new(0.15, 0.60, 0.075, 0.15));
+        return Some((ModelPrice::new(0.15, 0.60, 0.075, 0.15), Exact));
     }
     if m.contains("gpt-4o") {
-        return Some(ModelPrice::new(2.50, 10.0, 1.25, 2.50));
+        return Some((ModelPrice::new(2.50, 10.0, 1.25, 2.50), Exact));
     }
     if m.contains("gpt-4") {
-        return Some(ModelPrice::new(30.0, 60.0, 7.50, 30.0));
+        return Some((ModelPrice::new(30.0, 60.0, 7.50, 30.0), FamilyDefault));
     }
     if m.contains("gpt-3.5") {
-        return Some(ModelPrice::new(0.50, 1.50, 0.50, 0.50));
+        return Some((ModelPrice::new(0.50, 1.50, 0.50, 0.50), Exact));
     }
     // Moonshot / Kimi
     if m.contains("kimi") || m.contains("moonshot") {
-        return Some(ModelPrice::new(0.15, 2.50, 0.04, 0.15));
+        return Some((ModelPrice::new(0.15, 2.50, 0.04, 0.15), FamilyDefault));
     }
     // Google
     if m.contains("gemini-2.0-flash") {
-        return Some(ModelPrice::new(0.10, 0.40, 0.025, 0.10));
+        return Some((ModelPrice::new(0.10, 0.40, 0.025, 0.10), Exact));
     }
     if m.contains("gemini-2") {
-        return Some(ModelPrice::new(1.25, 5.0, 0.31, 1.25));
+        return Some((ModelPrice::new(1.25, 5.0, 0.31, 1.25), FamilyDefault));
     }
     if m.contains("gemini-1.5-pro") {
-        return Some(ModelPrice::new(1.25, 5.0, 0.31, 1.25));
+        return Some((ModelPrice::new(1.25, 5.0, 0.31, 1.25), Exact));
     }
     if m.contains("gemini-1.5-flash") {
-        return Some(ModelPrice::new(0.075, 0.30, 0.02, 0.075));
+        return Some((ModelPrice::new(0.075, 0.30, 0.02, 0.075), Exact));
     }
     None
 }
 
+/// Model family (tier) for grouping costs across version strings, e.g. every
+/// `claude-*-sonnet-*` id is "sonnet". `None` for models the catalog doesn't know.
+pub fn classify_model(model_id: &str) -> Option<&'static str> {
+    let m = model_id.to_lowercase();
+    let m = m.as_str();
+    // Same precedence as the price catalog: Haiku before Sonnet, minis before
+    // their full-size model
+    if m.contains("opus") && m.contains("claude") {
+        return Some("opus");
+    }
+    if m.contains("haiku") {
+        return Some("haiku");
+    }
+    if m.contains("sonnet") {
+        return Some("sonnet");
+    }
+    if m.contains("gpt-5") {
+        return Some("gpt-5");
+    }
+    if has_word(m, "o3-mini") || has_word(m, "o4-mini") {
+        return Some("o-series-mini");
+    }
+    if has_word(m, "o3") || has_word(m, "o4") {
+        return Some("o-series");
+    }
+    if m.contains("gpt-4o-mini") {
+        return Some("gpt-4o-mini");
+    }
+    if m.contains("gpt-4o") {
+        return Some("gpt-4o");
+    }
+    if m.contains("gpt-4") {
+        return Some("gpt-4");
+    }
+    if m.contains("gpt-3.5") {
+        return Some("gpt-3.5");
+    }
+    if m.contains("kimi") || m.contains("moonshot") {
+        return Some("kimi");
+    }
+    if m.contains("gemini") && m.contains("flash") {
+        return Some("gemini-flash");
+    }
+    if m.contains("gemini") {
+        return Some("gemini-pro");
+    }
+    None
+}
+
+/// True if `needle` occurs in `haystack` with no alphanumeric character
+/// directly before or after it.
+fn has_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + needle.len()..].chars().next();
+        !before.is_some_and(|c| c.is_alphanumeric()) && !after.is_some_and(|c| c.is_alphanumeric())
+    })
+}
+
 pub fn estimate_cost(
     model_id: &str,
     input_tokens: u64,
@@ -122,3 +345,302 @@ pub fn estimate_cost(
         cache_write_tokens,
     ))
 }
+
+/// Cost split into input-side (fresh input + cache reads/writes) and
+/// output-side (generated tokens) components.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostBreakdown {
+    pub input_usd: f64,
+    pub output_usd: f64,
+}
+
+impl CostBreakdown {
+    pub fn total(&self) -> f64 {
+        self.input_usd + self.output_usd
+    }
+}
+
+impl std::ops::AddAssign for CostBreakdown {
+    fn add_assign(&mut self, other: Self) {
+        self.input_usd += other.input_usd;
+        self.output_usd += other.output_usd;
+    }
+}
+
+/// Price each token category of `usage` separately. When the source recorded an
+/// observed cost, the split is scaled so the components sum to that cost.
+pub fn cost_breakdown(usage: &CanonicalUsage, price: &ModelPrice) -> CostBreakdown {
+    let m = 1_000_000.0_f64;
+    let mut b = CostBreakdown {
+        input_usd: (usage.input_tokens as f64 / m) * price.input_per_mtok
+            + (usage.cache_read_tokens as f64 / m) * price.cache_read_per_mtok
+            + (usage.cache_write_tokens as f64 / m) * price.cache_write_per_mtok
+            + (usage.cache_write_1h_tokens as f64 / m)
+                * (price.cache_write_1h_per_mtok() - price.cache_write_per_mtok),
+        output_usd: (usage.output_tokens as f64 / m) * price.output_per_mtok,
+    };
+    if let Some(observed) = usage.cost_observed_usd {
+        let est = b.total();
+        if est > 0.0 {
+            b.input_usd *= observed / est;
+            b.output_usd *= observed / est;
+        }
+    }
+    b
+}
+
+/// How far tracekit's estimate for one model strays from the cost the source
+/// itself recorded (OpenCode reports a cost per turn), to catch catalog drift.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PricingCheck {
+    pub model: String,
+    /// Turns that carried both an observed cost and a priceable model
+    pub turns: usize,
+    pub observed_usd: f64,
+    pub estimated_usd: f64,
+    /// Mean of |estimated - observed| / observed over those turns
+    pub mean_relative_error: f64,
+    #[serde(skip)]
+    relative_error_sum: f64,
+}
+
+impl PricingCheck {
+    /// True when the mean relative error exceeds `threshold_pct` percent.
+    pub fn diverges(&self, threshold_pct: f64) -> bool {
+        self.mean_relative_error * 100.0 > threshold_pct
+    }
+}
+
+/// Accumulates [`PricingCheck`]s per model across any number of sessions.
+#[derive(Debug, Default)]
+pub struct PricingValidation {
+    by_model: std::collections::BTreeMap<String, PricingCheck>,
+}
+
+impl PricingValidation {
+    /// Record one turn. Turns without an observed cost, with guessed token
+    /// counts, or whose model has no price are ignored.
+    pub fn add(&mut self, model_id: &str, usage: &CanonicalUsage) {
+        let observed = match usage.cost_observed_usd {
+            Some(c) if c > 0.0 && !usage.heuristic => c,
+            _ => return,
+        };
+        let Some(price) = lookup_price(model_id) else {
+            return;
+        };
+        // Price the tokens alone; cost_breakdown would rescale to the observed cost
+        let unscaled = CanonicalUsage {
+            cost_observed_usd: None,
+            ..usage.clone()
+        };
+        let estimated = cost_breakdown(&unscaled, &price).total();
+        let check = self
+            .by_model
+            .entry(model_id.to_string())
+            .or_insert_with(|| PricingCheck {
+                model: model_id.to_string(),
+                ..Default::default()
+            });
+        check.turns += 1;
+        check.observed_usd += observed;
+        check.estimated_usd += estimated;
+        check.relative_error_sum += (estimated - observed).abs() / observed;
+        check.mean_relative_error = check.relative_error_sum / check.turns as f64;
+    }
+
+    /// Checked models, largest mean relative error first.
+    pub fn checks(&self) -> Vec<PricingCheck> {
+        let mut checks: Vec<PricingCheck> = self.by_model.values().cloned().collect();
+        checks.sort_by(|a, b| {
+            b.mean_relative_error
+                .partial_cmp(&a.mean_relative_error)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        checks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(model_id: &str) -> (f64, f64, f64, f64) {
+        let p = lookup_price(model_id).unwrap_or_else(|| panic!("no price for {}", model_id));
+        (
+            p.input_per_mtok,
+            p.output_per_mtok,
+            p.cache_read_per_mtok,
+            p.cache_write_per_mtok,
+        )
+    }
+
+    const OPUS: (f64, f64, f64, f64) = (15.0, 75.0, 1.50, 3.75);
+    const SONNET: (f64, f64, f64, f64) = (3.0, 15.0, 0.30, 3.75);
+    const HAIKU_4: (f64, f64, f64, f64) = (0.80, 4.0, 0.08, 1.0);
+
+    #[test]
+    fn model_families_ignore_version_strings() {
+        assert_eq!(classify_model("claude-opus-4-20250514"), Some("opus"));
+        assert_eq!(classify_model("claude-3-opus-20240229"), Some("opus"));
+        assert_eq!(classify_model("claude-sonnet-4-5-20250514"), Some("sonnet"));
+        assert_eq!(classify_model("claude-3-5-sonnet-20241022"), Some("sonnet"));
+        assert_eq!(classify_model("claude-4-5-haiku"), Some("haiku"));
+        assert_eq!(classify_model("gpt-4o-2024-08-06"), Some("gpt-4o"));
+        assert_eq!(classify_model("gpt-4o-mini"), Some("gpt-4o-mini"));
+        assert_eq!(classify_model("openai/o4-mini"), Some("o-series-mini"));
+        assert_eq!(classify_model("proto3"), None);
+        assert_eq!(classify_model("llama-3-70b"), None);
+    }
+
+    #[test]
+    fn claude_4_family() {
+        assert_eq!(price("claude-opus-4-20250514"), OPUS);
+        assert_eq!(price("claude-4-opus"), OPUS);
+        assert_eq!(price("openrouter/anthropic/claude-opus-4"), OPUS);
+        assert_eq!(price("claude-sonnet-4-5-20250514"), SONNET);
+        assert_eq!(price("claude-sonnet-4-20250514"), SONNET);
+        assert_eq!(price("claude-4-sonnet"), SONNET);
+        assert_eq!(price("claude-4-5-sonnet"), SONNET);
+        assert_eq!(price("claude-haiku-4-5-20251001"), HAIKU_4);
+        assert_eq!(price("claude-4-haiku"), HAIKU_4);
+        assert_eq!(price("claude-4-5-haiku"), HAIKU_4);
+        assert_eq!(price("anthropic/haiku-4-5"), HAIKU_4);
+    }
+
+    #[test]
+    fn claude_3_family() {
+        assert_eq!(price("claude-3-5-sonnet-20241022"), SONNET);
+        assert_eq!(price("claude-3.5-sonnet"), SONNET);
+        assert_eq!(price("claude-3-5-haiku-20241022"), HAIKU_4);
+        assert_eq!(price("claude-3.5-haiku"), HAIKU_4);
+        assert_eq!(price("claude-3-opus-20240229"), OPUS);
+        assert_eq!(price("claude-3-sonnet-20240229"), SONNET);
+        assert_eq!(price("claude-3-haiku-20240307"), (0.25, 1.25, 0.03, 0.31));
+    }
+
+    #[test]
+    fn unknown_claude_defaults_to_sonnet() {
+        assert_eq!(price("claude-next"), SONNET);
+        assert_eq!(price("CLAUDE-SONNET-4-5"), SONNET);
+    }
+
+    #[test]
+    fn openai_models() {
+        assert_eq!(price("gpt-5-codex"), (10.0, 40.0, 2.50, 10.0));
+        assert_eq!(price("o3-mini"), (1.10, 4.40, 0.275, 1.10));
+        assert_eq!(price("o4-mini"), (1.10, 4.40, 0.275, 1.10));
+        assert_eq!(price("o3"), (10.0, 40.0, 2.50, 10.0));
+        assert_eq!(price("o4"), (10.0, 40.0, 2.50, 10.0));
+        assert_eq!(price("gpt-4o-mini"), (0.15, 0.60, 0.075, 0.15));
+        assert_eq!(price("gpt-4o-2024-08-06"), (2.50, 10.0, 1.25, 2.50));
+        assert_eq!(price("gpt-4-turbo"), (30.0, 60.0, 7.50, 30.0));
+        assert_eq!(price("gpt-3.5-turbo"), (0.50, 1.50, 0.50, 0.50));
+    }
+
+    #[test]
+    fn o_series_requires_word_boundary() {
+        assert_eq!(price("openai/o3"), (10.0, 40.0, 2.50, 10.0));
+        assert_eq!(price("o3-2025-04-16"), (10.0, 40.0, 2.50, 10.0));
+        assert_eq!(price("azure/o4-mini"), (1.10, 4.40, 0.275, 1.10));
+        assert_eq!(price("o4-mini-2025-04-16"), (1.10, 4.40, 0.275, 1.10));
+        assert_eq!(price("gpt-4o"), (2.50, 10.0, 1.25, 2.50));
+        assert!(lookup_price("proto3-model").is_none());
+        assert!(lookup_price("mistral-neo4").is_none());
+        assert!(lookup_price("o3x").is_none());
+    }
+
+    #[test]
+    fn other_providers() {
+        assert_eq!(price("kimi-k2"), (0.15, 2.50, 0.04, 0.15));
+        assert_eq!(price("moonshot-v1-8k"), (0.15, 2.50, 0.04, 0.15));
+        assert_eq!(price("gemini-2.0-flash"), (0.10, 0.40, 0.025, 0.10));
+        assert_eq!(price("gemini-2.5-pro"), (1.25, 5.0, 0.31, 1.25));
+        assert_eq!(price("gemini-1.5-pro"), (1.25, 5.0, 0.31, 1.25));
+        assert_eq!(price("gemini-1.5-flash"), (0.075, 0.30, 0.02, 0.075));
+    }
+
+    #[test]
+    fn unknown_model_has_no_price() {
+        assert!(lookup_price("llama-3-70b").is_none());
+        assert!(estimate_cost("llama-3-70b", 1000, 1000, 0, 0).is_none());
+    }
+
+    #[test]
+    fn pricing_date_selects_historical_snapshot() {
+        let launch: PricingDate = "2024-06".parse().unwrap();
+        let current = lookup_price_at("gpt-4o-2024-05-13", None).unwrap();
+        let then = lookup_price_at("gpt-4o-2024-05-13", Some(launch)).unwrap();
+        let tokens = (1_000_000, 1_000_000, 0, 0);
+        assert!(
+            (current.estimate_cost(tokens.0, tokens.1, tokens.2, tokens.3) - 12.5).abs() < 1e-9
+        );
+        assert!((then.estimate_cost(tokens.0, tokens.1, tokens.2, tokens.3) - 20.0).abs() < 1e-9);
+        // Models the snapshot doesn't cover fall back to the current catalog
+        assert_eq!(
+            lookup_price_at("gpt-4o-mini", Some(launch))
+                .unwrap()
+                .input_per_mtok,
+            0.15
+        );
+        assert!("2024-13".parse::<PricingDate>().is_err());
+        assert!("24-06".parse::<PricingDate>().is_err());
+    }
+
+    #[test]
+    fn estimate_cost_sums_all_categories() {
+        let cost = estimate_cost(
+            "claude-sonnet-4-5",
+            1_000_000,
+            1_000_000,
+            1_000_000,
+            1_000_000,
+        )
+        .unwrap();
+        assert!((cost - (3.0 + 15.0 + 0.30 + 3.75)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pricing_validation_flags_diverging_model() {
+        let usage = |input: u64, observed: f64| CanonicalUsage {
+            input_tokens: input,
+            output_tokens: 0,
+            reasoning_tokens: 0,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            cache_write_1h_tokens: 0,
+            cost_observed_usd: Some(observed),
+            cost_estimated_usd: None,
+            latency_ms: None,
+            heuristic: false,
+        };
+        let mut v = PricingValidation::default();
+        // Sonnet input is $3/Mtok: one turn matches, one was billed at twice that
+        v.add("claude-sonnet-4-5", &usage(1_000_000, 3.0));
+        v.add("claude-sonnet-4-5", &usage(1_000_000, 6.0));
+        v.add("gpt-4o", &usage(1_000_000, 2.5));
+        v.add("llama-3-70b", &usage(1_000_000, 1.0));
+
+        let checks = v.checks();
+        assert_eq!(checks.len(), 2);
+        assert_eq!(checks[0].model, "claude-sonnet-4-5");
+        assert_eq!(checks[0].turns, 2);
+        assert!((checks[0].mean_relative_error - 0.25).abs() < 1e-9);
+        assert!(checks[0].diverges(10.0));
+        assert!(!checks[1].diverges(10.0));
+    }
+
+    #[test]
+    fn pricing_confidence_reflects_how_a_model_matched() {
+        let conf = |m: &str| lookup_price_with_confidence(m).map(|(_, c)| c);
+        assert_eq!(
+            conf("claude-sonnet-4-5-20260201"),
+            Some(PricingConfidence::Exact)
+        );
+        assert_eq!(conf("gpt-4-turbo"), Some(PricingConfidence::FamilyDefault));
+        assert_eq!(
+            conf("claude-sonnet-5"),
+            Some(PricingConfidence::GenericFallback)
+        );
+        assert_eq!(conf("llama-3-70b"), None);
+    }
+}
@@ -1,6 +1,10 @@
+use crate::pricing::{
+    cost_breakdown, lookup_price_with_confidence, CostBreakdown, PricingConfidence,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -52,6 +56,113 @@ pub struct CanonicalSession {
     pub total_cost_usd: Option<f64>,
     pub total_input_tokens: u64,
     pub total_output_tokens: u64,
+    /// Cost of auxiliary model calls (embeddings, summarization) that aren't tied
+    /// to a turn. Already included in `total_cost_usd`.
+    #[serde(default)]
+    pub auxiliary_cost_usd: Option<f64>,
+    /// Messages whose usage was estimated from text length (`--estimate-tokens`)
+    #[serde(default)]
+    pub heuristic_turns: usize,
+    /// Input-side vs output-side cost, where model prices are known. Covers
+    /// priced turns only: `auxiliary_cost_usd` and turns whose model isn't in the
+    /// catalog are left out, so its total can fall short of `total_cost_usd`.
+    #[serde(default)]
+    pub cost_breakdown: Option<CostBreakdown>,
+    /// Parsed messages by role; system also covers injected meta records
+    #[serde(default)]
+    pub user_count: usize,
+    #[serde(default)]
+    pub assistant_count: usize,
+    #[serde(default)]
+    pub system_count: usize,
+    /// Cache portions of `total_input_tokens`; the rest is fresh input
+    #[serde(default)]
+    pub total_cache_read_tokens: u64,
+    #[serde(default)]
+    pub total_cache_write_tokens: u64,
+    /// Reasoning/thinking tokens, where the source reports them separately
+    #[serde(default)]
+    pub total_reasoning_tokens: u64,
+    /// `key:value` labels for filtering and grouping, e.g. `repo:tracekit`,
+    /// `branch:main`
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Malformed trace lines skipped while reading the session
+    #[serde(default)]
+    pub parse_errors: usize,
+    /// Size of the opening context, when requested (`--include-system-prompt-size`)
+    #[serde(default)]
+    pub system_prompt: Option<SystemPromptSize>,
+    /// The trace stops mid-exchange: interrupted by the user, aborted, or cut off
+    /// before the agent finished its turn
+    #[serde(default)]
+    pub ended_abnormally: bool,
+    /// Tool calls made, and how many of them failed
+    #[serde(default)]
+    pub tool_calls: usize,
+    #[serde(default)]
+    pub tool_errors: usize,
+    /// 0–100 [`efficiency_score`](crate::efficiency_score), once the session has
+    /// been analyzed and scored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub efficiency_score: Option<u8>,
+    /// Weakest catalog match among the models whose cost was estimated rather
+    /// than observed; anything but `exact` means the total rests on a guessed price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pricing_confidence: Option<PricingConfidence>,
+    /// Portion of `total_cost_usd` the agent reported itself; the rest was
+    /// estimated from the price catalog
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub observed_cost_usd: Option<f64>,
+}
+
+/// Tokens in the context sent with a session's first request: the system
+/// prompt, tool definitions and injected instructions, plus the opening prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemPromptSize {
+    pub tokens: u64,
+    /// Estimated from text length because the first reply recorded no usage
+    pub estimated: bool,
+}
+
+/// Repository tag for a working directory: its last path component.
+pub fn repo_tag(cwd: &str) -> Option<String> {
+    let name = cwd
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()?;
+    (!name.is_empty()).then(|| format!("repo:{}", name))
+}
+
+/// Shorten a path under the home directory to `~/...` for display.
+pub fn collapse_home(path: &str) -> String {
+    match dirs::home_dir() {
+        Some(home) => collapse_home_in(path, &home.to_string_lossy()),
+        None => path.to_string(),
+    }
+}
+
+/// [`collapse_home`] against an explicit home directory. The prefix must end at a
+/// path component, so `/home/al` doesn't shorten `/home/alice`; on Windows the
+/// match ignores case and either separator may follow it.
+fn collapse_home_in(path: &str, home: &str) -> String {
+    let home = home.trim_end_matches(['/', '\\']);
+    let Some(head) = path.get(..home.len()).filter(|_| !home.is_empty()) else {
+        return path.to_string();
+    };
+    let same = if cfg!(windows) {
+        head.eq_ignore_ascii_case(home)
+    } else {
+        head == home
+    };
+    let rest = &path[home.len()..];
+    let at_boundary =
+        rest.is_empty() || rest.starts_with('/') || (cfg!(windows) && rest.starts_with('\\'));
+    if same && at_boundary {
+        format!("~{}", rest)
+    } else {
+        path.to_string()
+    }
 }
 
 impl CanonicalSession {
@@ -65,6 +176,24 @@ impl CanonicalSession {
     pub fn effective_cost(&self) -> Option<f64> {
         self.total_cost_usd
     }
+
+    /// True when no messages were found — the source file is empty or every
+    /// record failed to parse. Such sessions are not "clean", just unknown.
+    pub fn is_empty(&self) -> bool {
+        self.message_count == 0
+    }
+
+    /// Number of parsed messages with one of the given roles.
+    pub fn role_count(&self, roles: &[Role]) -> usize {
+        roles
+            .iter()
+            .map(|r| match r {
+                Role::User => self.user_count,
+                Role::Assistant => self.assistant_count,
+                Role::System => self.system_count,
+            })
+            .sum()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,9 +209,47 @@ pub struct CanonicalMessage {
     pub tool_calls: Vec<CanonicalTool>,
     pub is_sidechain: bool,
     pub finish_reason: Option<String>,
+    /// Characters of text/tool content, used for heuristic token estimation
+    #[serde(default)]
+    pub content_chars: usize,
+    /// Fingerprints of a long user prompt's lines (see [`prompt_lines`]), for
+    /// spotting content pasted again; empty for other messages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompt_lines: Vec<LineHash>,
 }
 
+/// One substantive line of prompt text: its whitespace-normalized hash and length.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineHash {
+    pub hash: u64,
+    pub chars: u32,
+}
+
+/// Prompts shorter than this many characters aren't fingerprinted.
+pub const PROMPT_LINES_MIN_CHARS: usize = 1000;
+
+/// Shorter lines (braces, blank-ish separators) are too common to mean anything.
+const PROMPT_LINE_MIN_CHARS: usize = 8;
+
+/// Fingerprint the lines of a long prompt, so text pasted into several prompts
+/// can be matched without keeping it. Whitespace within each line is collapsed
+/// first, so re-indented or re-wrapped copies still match. Empty for prompts under
+/// [`PROMPT_LINES_MIN_CHARS`].
+pub fn prompt_lines(text: &str) -> Vec<LineHash> {
+    if text.chars().count() < PROMPT_LINES_MIN_CHARS {
+        return Vec::new();
+    }
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| line.chars().count() >= PROMPT_LINE_MIN_CHARS)
+        .map(|line| LineHash {
+            hash: content_hash(&line),
+            chars: line.chars().count() as u32,
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum Role {
     User,
@@ -90,6 +257,18 @@ pub enum Role {
     System,
 }
 
+impl std::str::FromStr for Role {
+    type Err = anyhow::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "user" => Ok(Role::User),
+            "assistant" => Ok(Role::Assistant),
+            "system" => Ok(Role::System),
+            _ => Err(anyhow::anyhow!("Unknown role: {}", s)),
+        }
+    }
+}
+
 impl std::fmt::Display for Role {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -107,11 +286,45 @@ pub struct CanonicalUsage {
     pub reasoning_tokens: u64,
     pub cache_read_tokens: u64,
     pub cache_write_tokens: u64,
+    /// Portion of `cache_write_tokens` written with a 1-hour TTL, billed above the
+    /// default 5-minute tier (Claude)
+    #[serde(default)]
+    pub cache_write_1h_tokens: u64,
     /// Directly observed cost from source (e.g. OpenCode provides this)
     pub cost_observed_usd: Option<f64>,
     /// Estimated cost from token counts × model pricing
     pub cost_estimated_usd: Option<f64>,
     pub latency_ms: Option<u64>,
+    /// Token counts were guessed from content length, not recorded by the source
+    #[serde(default)]
+    pub heuristic: bool,
+}
+
+impl CanonicalTool {
+    /// The failure's [`ErrorClass`], or `None` if the call didn't fail. Read from
+    /// `error_class` when that names a class, otherwise from the error message,
+    /// so traces parsed before the taxonomy existed classify too.
+    pub fn error_kind(&self) -> Option<ErrorClass> {
+        if self.status != ToolStatus::Error {
+            return None;
+        }
+        let recorded = self.error_class.as_deref().and_then(|c| c.parse().ok());
+        Some(
+            recorded
+                .or_else(|| {
+                    self.error_message
+                        .as_deref()
+                        .and_then(|m| ErrorClass::for_tool(self, m))
+                })
+                .unwrap_or(ErrorClass::Other),
+        )
+    }
+
+    /// Whether the call failed transiently (timeout, network, rate limit), so
+    /// retrying it unchanged is reasonable.
+    pub fn is_retryable_error(&self) -> bool {
+        self.error_kind().is_some_and(|c| c.is_retryable())
+    }
 }
 
 impl CanonicalUsage {
@@ -129,15 +342,34 @@ impl CanonicalUsage {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CanonicalTool {
     pub tool_name: String,
+    /// Agent-independent category derived from `tool_name`
+    #[serde(default)]
+    pub category: ToolCategory,
     pub call_id: String,
     pub status: ToolStatus,
     pub error_class: Option<String>,
     pub error_message: Option<String>,
     pub args_summary: Option<String>,
     pub output_summary: Option<String>,
+    /// Approximate size of the tool's output, including non-text payloads
+    /// (images, JSON) that have no summary
+    #[serde(default)]
+    pub output_bytes: Option<u64>,
+    /// [`content_hash`] of the tool's full output, so identical outputs can be
+    /// recognized without keeping them
+    #[serde(default)]
+    pub output_hash: Option<u64>,
     pub duration_ms: Option<u64>,
 }
 
+/// Stable 64-bit FNV-1a hash of some content. Unlike `DefaultHasher` it is the
+/// same across builds, so hashes stay comparable in saved analyses.
+pub fn content_hash(content: &str) -> u64 {
+    content.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ToolStatus {
@@ -146,6 +378,213 @@ pub enum ToolStatus {
     Unknown,
 }
 
+/// Logical tool category, so the same operation aggregates across agents
+/// (`Read` / `read` / `read_file` / `cat` are all `Read`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolCategory {
+    Read,
+    Write,
+    Edit,
+    Search,
+    Exec,
+    #[default]
+    Other,
+}
+
+impl std::fmt::Display for ToolCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolCategory::Read => write!(f, "read"),
+            ToolCategory::Write => write!(f, "write"),
+            ToolCategory::Edit => write!(f, "edit"),
+            ToolCategory::Search => write!(f, "search"),
+            ToolCategory::Exec => write!(f, "exec"),
+            ToolCategory::Other => write!(f, "other"),
+        }
+    }
+}
+
+/// Map an agent-specific tool name to its [`ToolCategory`].
+pub fn canonical_tool_category(tool_name: &str) -> ToolCategory {
+    let name = tool_name.to_lowercase();
+    let has = |needles: &[&str]| needles.iter().any(|n| name.contains(n));
+
+    if has(&["todo"]) {
+        ToolCategory::Other
+    } else if has(&[
+        "edit",
+        "str_replace",
+        "apply_patch",
+        "patch",
+        "replace_in_file",
+    ]) {
+        ToolCategory::Edit
+    } else if has(&["write", "create_file", "delete_file"]) {
+        ToolCategory::Write
+    } else if has(&["grep", "glob", "search", "find"]) || name == "ls" || name == "list" {
+        ToolCategory::Search
+    } else if has(&["read", "cat", "view", "open"]) {
+        ToolCategory::Read
+    } else if has(&["bash", "shell", "exec", "terminal", "command"]) {
+        ToolCategory::Exec
+    } else {
+        ToolCategory::Other
+    }
+}
+
+/// True for finish reasons meaning the output hit the token limit
+/// (`max_tokens` on Anthropic, `length` on OpenAI-style providers).
+pub fn is_truncation_reason(reason: &str) -> bool {
+    matches!(reason, "max_tokens" | "max_output_tokens" | "length")
+}
+
+/// `error_class` for tool errors caused by a missing file or directory.
+pub const ERROR_CLASS_NOT_FOUND: &str = "not_found";
+
+/// What kind of failure a tool error was, as far as its message tells. Only
+/// transient kinds are worth retrying unchanged; the rest fail the same way
+/// until the call itself changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorClass {
+    NotFound,
+    Permission,
+    Timeout,
+    Network,
+    RateLimit,
+    Other,
+}
+
+impl ErrorClass {
+    /// Messages meaning a path doesn't exist. Bare "not found" is left out: it
+    /// also covers missing commands and unmatched edit strings.
+    const NOT_FOUND_PATTERNS: &[&str] = &["no such file", "enoent", "does not exist"];
+
+    const PATTERNS: &[(ErrorClass, &[&str])] = &[
+        (
+            ErrorClass::Permission,
+            &[
+                "permission denied",
+                "eacces",
+                "operation not permitted",
+                "access denied",
+            ],
+        ),
+        (
+            ErrorClass::Timeout,
+            &["timed out", "timeout", "deadline exceeded", "etimedout"],
+        ),
+        (
+            ErrorClass::Network,
+            &[
+                "connection refused",
+                "connection reset",
+                "econnrefused",
+                "econnreset",
+                "network is unreachable",
+                "could not resolve host",
+                "temporary failure in name resolution",
+                "socket hang up",
+                "bad gateway",
+                "service unavailable",
+            ],
+        ),
+        (
+            ErrorClass::RateLimit,
+            &[
+                "rate limit",
+                "rate-limit",
+                "too many requests",
+                "overloaded",
+            ],
+        ),
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorClass::NotFound => ERROR_CLASS_NOT_FOUND,
+            ErrorClass::Permission => "permission",
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Network => "network",
+            ErrorClass::RateLimit => "rate_limit",
+            ErrorClass::Other => "other",
+        }
+    }
+
+    /// The class whose patterns an error message matches, if any. Never
+    /// [`ErrorClass::NotFound`], which depends on the call; see [`Self::for_tool`].
+    pub fn from_message(message: &str) -> Option<Self> {
+        let m = message.to_lowercase();
+        Self::PATTERNS
+            .iter()
+            .find(|(_, patterns)| patterns.iter().any(|p| m.contains(p)))
+            .map(|(class, _)| *class)
+    }
+
+    /// The class of a failed call's error message. A missing-path message only
+    /// counts as [`ErrorClass::NotFound`] for a read, edit or search called with
+    /// a path; anywhere else the path in the message isn't the call's target.
+    pub fn for_tool(tool: &CanonicalTool, message: &str) -> Option<Self> {
+        let takes_path = matches!(
+            tool.category,
+            ToolCategory::Read | ToolCategory::Edit | ToolCategory::Search
+        ) && tool.args_summary.as_deref().is_some_and(looks_like_path);
+        let m = message.to_lowercase();
+        if takes_path && Self::NOT_FOUND_PATTERNS.iter().any(|p| m.contains(p)) {
+            return Some(ErrorClass::NotFound);
+        }
+        Self::from_message(message)
+    }
+
+    /// Transient failures that may succeed on an identical retry.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            ErrorClass::Timeout | ErrorClass::Network | ErrorClass::RateLimit
+        )
+    }
+}
+
+impl std::str::FromStr for ErrorClass {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            ErrorClass::NotFound,
+            ErrorClass::Permission,
+            ErrorClass::Timeout,
+            ErrorClass::Network,
+            ErrorClass::RateLimit,
+            ErrorClass::Other,
+        ]
+        .into_iter()
+        .find(|c| c.as_str() == s)
+        .ok_or_else(|| format!("unknown error class '{}'", s))
+    }
+}
+
+/// Whether a tool argument names a file or directory rather than, say, a
+/// search term.
+fn looks_like_path(arg: &str) -> bool {
+    arg.contains(['/', '\\']) || Path::new(arg).extension().is_some()
+}
+
+/// Classify a failed call from its error message, using `fallback` unless the
+/// message matches a known [`ErrorClass`] (see [`ErrorClass::for_tool`]).
+pub fn classify_tool_error(tool: &CanonicalTool, message: &str, fallback: &str) -> String {
+    ErrorClass::for_tool(tool, message)
+        .map(|c| c.as_str().to_string())
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// What makes two messages the same record for [`ParsedSession::merge`].
+type MergeKey = (Option<DateTime<Utc>>, Role, String, Vec<String>);
+
+fn merge_key(msg: &CanonicalMessage) -> MergeKey {
+    let calls = msg.tool_calls.iter().map(|t| t.call_id.clone()).collect();
+    (msg.ts, msg.role, msg.message_id.clone(), calls)
+}
+
 /// A fully parsed session with all messages
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedSession {
@@ -154,6 +593,140 @@ pub struct ParsedSession {
 }
 
 impl ParsedSession {
+    /// Keep only messages whose sequence falls in `from..=to` (either bound optional)
+    /// and recompute totals for the slice, so detectors see just that range.
+    pub fn slice_turns(&mut self, from: Option<usize>, to: Option<usize>) {
+        if from.is_none() && to.is_none() {
+            return;
+        }
+        self.messages
+            .retain(|m| from.is_none_or(|f| m.sequence >= f) && to.is_none_or(|t| m.sequence <= t));
+        self.session.total_cost_usd = None;
+        self.compute_totals();
+    }
+
+    /// Combine two fragments of one conversation (a resumed or forked session, or
+    /// pieces imported from several files) into a single session.
+    ///
+    /// Messages are concatenated, messages already seen are dropped (`self` wins),
+    /// and the rest are ordered by timestamp and re-sequenced from 0. A message is
+    /// a duplicate when its timestamp, role, id and tool call ids all match; the id
+    /// alone isn't enough, since Codex numbers messages per file (`user-3`).
+    /// A message without a timestamp keeps its place after the preceding message of
+    /// its fragment. Metadata comes from `self`, with gaps filled from `other`; the
+    /// time range spans both fragments and totals are recomputed.
+    pub fn merge(self, other: ParsedSession) -> ParsedSession {
+        let mut session = self.session;
+        let theirs = other.session;
+
+        let mut seen: HashSet<MergeKey> = HashSet::new();
+        let mut keyed: Vec<(Option<DateTime<Utc>>, CanonicalMessage)> = Vec::new();
+        for fragment in [self.messages, other.messages] {
+            let mut last_ts = None;
+            for msg in fragment {
+                last_ts = msg.ts.or(last_ts);
+                if seen.insert(merge_key(&msg)) {
+                    keyed.push((last_ts, msg));
+                }
+            }
+        }
+        // Stable, so equal or missing timestamps keep fragment order
+        keyed.sort_by_key(|(ts, _)| *ts);
+        let messages = keyed
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, mut msg))| {
+                msg.sequence = i;
+                msg
+            })
+            .collect();
+
+        session.started_at = match (session.started_at, theirs.started_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        session.ended_at = session.ended_at.max(theirs.ended_at);
+        session.cwd = session.cwd.or(theirs.cwd);
+        session.title = session.title.or(theirs.title);
+        session.model = session.model.or(theirs.model);
+        session.auxiliary_cost_usd = match (session.auxiliary_cost_usd, theirs.auxiliary_cost_usd) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+        session.parse_errors += theirs.parse_errors;
+        for tag in theirs.tags {
+            if !session.tags.contains(&tag) {
+                session.tags.push(tag);
+            }
+        }
+        session.total_cost_usd = None;
+
+        let mut merged = ParsedSession { session, messages };
+        merged.compute_totals();
+        merged
+    }
+
+    /// Size of the opening context: the billed input of the first main-thread
+    /// reply when it recorded usage, otherwise `chars_per_token` applied to the
+    /// user and system text sent before it. `None` if there's nothing to measure.
+    pub fn system_prompt_size(&self, chars_per_token: f64) -> Option<SystemPromptSize> {
+        let main: Vec<&CanonicalMessage> =
+            self.messages.iter().filter(|m| !m.is_sidechain).collect();
+        let first_reply = main.iter().position(|m| m.role == Role::Assistant);
+
+        if let Some(usage) = first_reply
+            .and_then(|i| main[i].usage.as_ref())
+            .filter(|u| !u.heuristic && u.total_billed_input() > 0)
+        {
+            return Some(SystemPromptSize {
+                tokens: usage.total_billed_input(),
+                estimated: false,
+            });
+        }
+
+        let chars: usize = main[..first_reply.unwrap_or(main.len())]
+            .iter()
+            .map(|m| m.content_chars)
+            .sum();
+        (chars > 0).then(|| SystemPromptSize {
+            tokens: (chars as f64 / chars_per_token.max(0.1)).ceil() as u64,
+            estimated: true,
+        })
+    }
+
+    /// Fill in heuristic usage for messages that have content but no recorded usage,
+    /// assuming `chars_per_token` characters per token. User content counts as input,
+    /// assistant content as output. Estimated usage is flagged `heuristic`.
+    pub fn estimate_missing_usage(&mut self, chars_per_token: f64) {
+        let chars_per_token = chars_per_token.max(0.1);
+        let fallback_model = self.session.model.clone();
+        for msg in &mut self.messages {
+            if msg.usage.is_some() || msg.content_chars == 0 {
+                continue;
+            }
+            let tokens = (msg.content_chars as f64 / chars_per_token).ceil() as u64;
+            let (input, output) = match msg.role {
+                Role::Assistant => (0, tokens),
+                _ => (tokens, 0),
+            };
+            let model = msg.model.as_deref().or(fallback_model.as_deref());
+            msg.usage = Some(CanonicalUsage {
+                input_tokens: input,
+                output_tokens: output,
+                reasoning_tokens: 0,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+                cache_write_1h_tokens: 0,
+                cost_observed_usd: None,
+                cost_estimated_usd: model
+                    .and_then(|m| crate::pricing::estimate_cost(m, input, output, 0, 0)),
+                latency_ms: None,
+                heuristic: true,
+            });
+        }
+        self.compute_totals();
+    }
+
     /// Compute aggregate cost across all messages
     pub fn compute_totals(&mut self) {
         let mut total_cost = 0.0_f64;
@@ -172,19 +745,57 @@ impl ParsedSession {
             }
         }
 
+        if let Some(aux) = self.session.auxiliary_cost_usd {
+            total_cost += aux;
+            has_cost = true;
+        }
+
+        let mut breakdown: Option<CostBreakdown> = None;
+        let mut confidence: Option<PricingConfidence> = None;
+        let mut observed: Option<f64> = None;
+        for msg in &self.messages {
+            let Some(ref u) = msg.usage else { continue };
+            if let Some(c) = u.cost_observed_usd {
+                *observed.get_or_insert(0.0) += c;
+            }
+            let model = msg.model.as_deref().or(self.session.model.as_deref());
+            if let Some((price, conf)) = model.and_then(lookup_price_with_confidence) {
+                *breakdown.get_or_insert_default() += cost_breakdown(u, &price);
+                // Observed costs don't depend on the catalog, so only estimates count
+                if u.cost_observed_usd.is_none() {
+                    confidence = confidence.max(Some(conf));
+                }
+            }
+        }
+        self.session.cost_breakdown = breakdown;
+        self.session.pricing_confidence = confidence;
+        self.session.observed_cost_usd = observed;
+
         // Include cache tokens in the input total for display (cache write + read)
-        let total_cache: u64 = self
-            .messages
-            .iter()
-            .filter_map(|m| m.usage.as_ref())
-            .map(|u| u.cache_read_tokens + u.cache_write_tokens)
-            .sum();
-        self.session.total_input_tokens = total_input + total_cache;
+        let usages = || self.messages.iter().filter_map(|m| m.usage.as_ref());
+        let cache_read: u64 = usages().map(|u| u.cache_read_tokens).sum();
+        let cache_write: u64 = usages().map(|u| u.cache_write_tokens).sum();
+        self.session.total_cache_read_tokens = cache_read;
+        self.session.total_cache_write_tokens = cache_write;
+        self.session.total_reasoning_tokens = usages().map(|u| u.reasoning_tokens).sum();
+        self.session.total_input_tokens = total_input + cache_read + cache_write;
         self.session.total_output_tokens = total_output;
         if has_cost {
             self.session.total_cost_usd = Some(total_cost);
         }
         self.session.message_count = self.messages.len();
+        let count = |role| self.messages.iter().filter(|m| m.role == role).count();
+        self.session.user_count = count(Role::User);
+        self.session.assistant_count = count(Role::Assistant);
+        self.session.system_count = count(Role::System);
+        let tools = || self.messages.iter().flat_map(|m| &m.tool_calls);
+        self.session.tool_calls = tools().count();
+        self.session.tool_errors = tools().filter(|t| t.status == ToolStatus::Error).count();
+        self.session.heuristic_turns = self
+            .messages
+            .iter()
+            .filter(|m| m.usage.as_ref().is_some_and(|u| u.heuristic))
+            .count();
 
         // Infer timestamps from messages
         let timestamps: Vec<DateTime<Utc>> = self.messages.iter().filter_map(|m| m.ts).collect();
@@ -238,13 +849,35 @@ pub struct Finding {
     pub kind: FindingKind,
     pub description: String,
     pub evidence: Vec<String>,
+    /// The turns `evidence` cites, for consumers that link back to messages
+    /// rather than parse "turn 12" out of the display strings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence_refs: Vec<EvidenceRef>,
     pub wasted_tokens: Option<u64>,
     pub wasted_cost_usd: Option<f64>,
     pub confidence: f64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
+/// A message cited by a finding, and the tool it concerns when there is one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvidenceRef {
+    pub sequence: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name: Option<String>,
+    pub message_id: String,
+}
+
+impl EvidenceRef {
+    pub fn new(msg: &CanonicalMessage, tool_name: Option<&str>) -> Self {
+        Self {
+            sequence: msg.sequence,
+            tool_name: tool_name.map(str::to_string),
+            message_id: msg.message_id.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum FindingKind {
     RetryLoop,
     EditCascade,
@@ -253,6 +886,117 @@ pub enum FindingKind {
     ContextBloat,
     ErrorRepromptChurn,
     SubagentOverhead,
+    MissingFileLoop,
+    OutputTruncation,
+    UnreusedCacheWrite,
+    CostConcentration,
+    ExplorationOverhead,
+    SlowTool,
+    RunawayOutput,
+    LowYieldTurn,
+    AbortedSession,
+    RedundantOutput,
+    RepeatedPaste,
+    ToolHeavyTurn,
+    CostlyModelSwitch,
+    /// A category produced by a custom detector (snake_case by convention)
+    Other(String),
+}
+
+impl FindingKind {
+    /// The snake_case identifier used in serialized output.
+    pub fn as_str(&self) -> &str {
+        match self {
+            FindingKind::RetryLoop => "retry_loop",
+            FindingKind::EditCascade => "edit_cascade",
+            FindingKind::ToolFanout => "tool_fanout",
+            FindingKind::RedundantReread => "redundant_reread",
+            FindingKind::ContextBloat => "context_bloat",
+            FindingKind::ErrorRepromptChurn => "error_reprompt_churn",
+            FindingKind::SubagentOverhead => "subagent_overhead",
+            FindingKind::MissingFileLoop => "missing_file_loop",
+            FindingKind::OutputTruncation => "output_truncation",
+            FindingKind::UnreusedCacheWrite => "unreused_cache_write",
+            FindingKind::CostConcentration => "cost_concentration",
+            FindingKind::ExplorationOverhead => "exploration_overhead",
+            FindingKind::SlowTool => "slow_tool",
+            FindingKind::RunawayOutput => "runaway_output",
+            FindingKind::LowYieldTurn => "low_yield_turn",
+            FindingKind::AbortedSession => "aborted_session",
+            FindingKind::RedundantOutput => "redundant_output",
+            FindingKind::RepeatedPaste => "repeated_paste",
+            FindingKind::ToolHeavyTurn => "tool_heavy_turn",
+            FindingKind::CostlyModelSwitch => "costly_model_switch",
+            FindingKind::Other(s) => s,
+        }
+    }
+
+    /// A short, prescriptive remedy for this kind of inefficiency.
+    pub fn suggestion(&self) -> &'static str {
+        match self {
+            FindingKind::RetryLoop => {
+                "Add a guard or try a different approach after the first failure instead of retrying identically"
+            }
+            FindingKind::EditCascade => {
+                "Re-read the file before editing again; a stale view is the usual cause of repeated edit failures"
+            }
+            FindingKind::ToolFanout => {
+                "Batch these calls into one (glob, multi-file read, or a single shell command)"
+            }
+            FindingKind::RedundantReread => {
+                "Keep the file contents in context or read only the needed range instead of re-reading"
+            }
+            FindingKind::ContextBloat => {
+                "Trim injected context (large files, logs, tool output) or summarize before passing it in"
+            }
+            FindingKind::ErrorRepromptChurn => {
+                "Stop and supply new information (error details, docs, a different strategy) before the next attempt"
+            }
+            FindingKind::SubagentOverhead => {
+                "Inline small tasks instead of delegating; reserve subagents for broad, parallel work"
+            }
+            FindingKind::MissingFileLoop => {
+                "Locate the file first (glob or search) instead of retrying a path that does not exist"
+            }
+            FindingKind::OutputTruncation => {
+                "Ask for shorter outputs or split the work across turns; raise the output limit if truncation was unexpected"
+            }
+            FindingKind::UnreusedCacheWrite => {
+                "Keep the cached prefix stable (system prompt, tools, early context) so later turns can read it"
+            }
+            FindingKind::CostConcentration => {
+                "Inspect that one turn first (a huge paste, a runaway read or tool output); fixing it fixes most of the bill"
+            }
+            FindingKind::ExplorationOverhead => {
+                "Point the agent at the relevant files up front (paths in the prompt, a CLAUDE.md/AGENTS.md map) instead of letting it survey the repo"
+            }
+            FindingKind::SlowTool => {
+                "Narrow slow commands (one test, an incremental build), run long jobs in the background, or set a timeout"
+            }
+            FindingKind::RunawayOutput => {
+                "Ask for a diff or the changed section instead of whole files, and write large content with a tool rather than in the reply"
+            }
+            FindingKind::LowYieldTurn => {
+                "Batch acknowledgements and status updates into the next real step; compact or restart a long context before small follow-ups"
+            }
+            FindingKind::AbortedSession => {
+                "Check whether the task was finished elsewhere; a cheap aborted session isn't an efficient one"
+            }
+            FindingKind::RedundantOutput => {
+                "Refer back to the earlier output instead of re-running the command or re-reading the file"
+            }
+            FindingKind::RepeatedPaste => {
+                "Point the agent at the file or earlier message instead of pasting the same content again"
+            }
+            FindingKind::ToolHeavyTurn => {
+                "Give the agent a clearer target (file, symbol, command) so it acts instead of probing with many tools"
+            }
+            FindingKind::CostlyModelSwitch => {
+                "Switch back to the cheaper model once the hard step is done; the pricier one didn't cut errors"
+            }
+            FindingKind::Other(_) => "See the custom detector's documentation for this rule",
+        }
+    }
 }
 
 impl std::fmt::Display for FindingKind {
@@ -265,6 +1009,97 @@ impl std::fmt::Display for FindingKind {
             FindingKind::ContextBloat => write!(f, "CONTEXT_BLOAT"),
             FindingKind::ErrorRepromptChurn => write!(f, "ERROR_REPROMPT_CHURN"),
             FindingKind::SubagentOverhead => write!(f, "SUBAGENT_OVERHEAD"),
+            FindingKind::MissingFileLoop => write!(f, "MISSING_FILE_LOOP"),
+            FindingKind::OutputTruncation => write!(f, "OUTPUT_TRUNCATION"),
+            FindingKind::UnreusedCacheWrite => write!(f, "UNREUSED_CACHE_WRITE"),
+            FindingKind::CostConcentration => write!(f, "COST_CONCENTRATION"),
+            FindingKind::ExplorationOverhead => write!(f, "EXPLORATION_OVERHEAD"),
+            FindingKind::SlowTool => write!(f, "SLOW_TOOL"),
+            FindingKind::RunawayOutput => write!(f, "RUNAWAY_OUTPUT"),
+            FindingKind::LowYieldTurn => write!(f, "LOW_YIELD_TURN"),
+            FindingKind::AbortedSession => write!(f, "ABORTED_SESSION"),
+            FindingKind::RedundantOutput => write!(f, "REDUNDANT_OUTPUT"),
+            FindingKind::RepeatedPaste => write!(f, "REPEATED_PASTE"),
+            FindingKind::ToolHeavyTurn => write!(f, "TOOL_HEAVY_TURN"),
+            FindingKind::CostlyModelSwitch => write!(f, "COSTLY_MODEL_SWITCH"),
+            FindingKind::Other(s) => write!(f, "{}", s.to_uppercase()),
+        }
+    }
+}
+
+impl std::str::FromStr for FindingKind {
+    type Err = std::convert::Infallible;
+    /// Accepts either the display (`RETRY_LOOP`) or serialized (`retry_loop`) form;
+    /// anything unrecognized becomes `Other` with a lowercased name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "retry_loop" => FindingKind::RetryLoop,
+            "edit_cascade" => FindingKind::EditCascade,
+            "tool_fanout" => FindingKind::ToolFanout,
+            "redundant_reread" => FindingKind::RedundantReread,
+            "context_bloat" => FindingKind::ContextBloat,
+            "error_reprompt_churn" => FindingKind::ErrorRepromptChurn,
+            "subagent_overhead" => FindingKind::SubagentOverhead,
+            "missing_file_loop" => FindingKind::MissingFileLoop,
+            "output_truncation" => FindingKind::OutputTruncation,
+            "unreused_cache_write" => FindingKind::UnreusedCacheWrite,
+            "cost_concentration" => FindingKind::CostConcentration,
+            "exploration_overhead" => FindingKind::ExplorationOverhead,
+            "slow_tool" => FindingKind::SlowTool,
+            "runaway_output" => FindingKind::RunawayOutput,
+            "low_yield_turn" => FindingKind::LowYieldTurn,
+            "aborted_session" => FindingKind::AbortedSession,
+            "redundant_output" => FindingKind::RedundantOutput,
+            "repeated_paste" => FindingKind::RepeatedPaste,
+            "tool_heavy_turn" => FindingKind::ToolHeavyTurn,
+            "costly_model_switch" => FindingKind::CostlyModelSwitch,
+            other => FindingKind::Other(other.to_string()),
+        })
+    }
+}
+
+// Serialized as a plain snake_case string so custom kinds look like built-ins.
+impl Serialize for FindingKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for FindingKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let Ok(kind) = s.parse();
+        Ok(kind)
+    }
+}
+
+/// Whether a session used tools at all. Pure chat sessions get different
+/// advice: tool-centric detectors are skipped for them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionKind {
+    /// No tool calls: conversation only
+    Chat,
+    /// At least one tool call
+    #[default]
+    Agentic,
+}
+
+impl SessionKind {
+    pub fn of(parsed: &ParsedSession) -> Self {
+        if parsed.messages.iter().any(|m| !m.tool_calls.is_empty()) {
+            SessionKind::Agentic
+        } else {
+            SessionKind::Chat
+        }
+    }
+}
+
+impl std::fmt::Display for SessionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionKind::Chat => write!(f, "chat"),
+            SessionKind::Agentic => write!(f, "agentic"),
         }
     }
 }
@@ -273,6 +1108,9 @@ impl std::fmt::Display for FindingKind {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalysisResult {
     pub session: CanonicalSession,
+    /// Analyses exported before kinds existed read back as agentic
+    #[serde(default)]
+    pub session_kind: SessionKind,
     pub findings: Vec<Finding>,
     pub top_expensive_messages: Vec<ExpensiveMessage>,
 }
@@ -287,4 +1125,239 @@ pub struct ExpensiveMessage {
     pub input_tokens: u64,
     pub output_tokens: u64,
     pub tool_count: usize,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_finding_kinds_round_trip_like_built_ins() {
+        let kinds = [
+            FindingKind::RetryLoop,
+            FindingKind::CostlyModelSwitch,
+            FindingKind::Other("slow_ci".to_string()),
+        ];
+        for kind in kinds {
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{}\"", kind.as_str()));
+            assert_eq!(serde_json::from_str::<FindingKind>(&json).unwrap(), kind);
+            let Ok(parsed) = kind.to_string().parse::<FindingKind>();
+            assert_eq!(parsed, kind);
+        }
+        assert_eq!(
+            FindingKind::Other("slow_ci".to_string()).to_string(),
+            "SLOW_CI"
+        );
+    }
+
+    #[test]
+    fn cwd_yields_repo_tag_from_last_component() {
+        assert_eq!(
+            repo_tag("/home/dev/src/tracekit").as_deref(),
+            Some("repo:tracekit")
+        );
+        assert_eq!(
+            repo_tag("/home/dev/src/tracekit/").as_deref(),
+            Some("repo:tracekit")
+        );
+        assert_eq!(
+            repo_tag(r"C:\work\tracekit").as_deref(),
+            Some("repo:tracekit")
+        );
+        assert_eq!(repo_tag("/"), None);
+        assert_eq!(repo_tag(""), None);
+    }
+
+    fn message(id: &str, role: Role, secs: i64) -> CanonicalMessage {
+        CanonicalMessage {
+            message_id: id.to_string(),
+            session_id: "s".to_string(),
+            parent_id: None,
+            sequence: 0,
+            role,
+            model: None,
+            ts: DateTime::from_timestamp(1_700_000_000 + secs, 0),
+            usage: None,
+            tool_calls: Vec::new(),
+            is_sidechain: false,
+            finish_reason: None,
+            content_chars: 0,
+            prompt_lines: Vec::new(),
+        }
+    }
+
+    fn fragment(messages: Vec<CanonicalMessage>) -> ParsedSession {
+        let session = serde_json::from_value(serde_json::json!({
+            "session_id": "s",
+            "source_agent": "codex",
+            "source_path": "/tmp/s.jsonl",
+            "cwd": null,
+            "title": null,
+            "started_at": null,
+            "ended_at": null,
+            "model": null,
+            "message_count": messages.len(),
+            "total_cost_usd": null,
+            "total_input_tokens": 0,
+            "total_output_tokens": 0,
+        }))
+        .unwrap();
+        ParsedSession { session, messages }
+    }
+
+    fn ids(parsed: &ParsedSession) -> Vec<(usize, &str)> {
+        parsed
+            .messages
+            .iter()
+            .map(|m| (m.sequence, m.message_id.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn merge_drops_overlapping_records_but_keeps_reused_ids() {
+        // The resumed file repeats the last two records of the first, then
+        // restarts Codex's per-file numbering for new messages
+        let first = fragment(vec![
+            message("user-0", Role::User, 0),
+            message("asst-1", Role::Assistant, 10),
+            message("user-2", Role::User, 20),
+        ]);
+        let second = fragment(vec![
+            message("asst-1", Role::Assistant, 10),
+            message("user-2", Role::User, 20),
+            message("user-0", Role::User, 30),
+            message("asst-1", Role::Assistant, 40),
+        ]);
+
+        let merged = first.merge(second);
+        assert_eq!(
+            ids(&merged),
+            [
+                (0, "user-0"),
+                (1, "asst-1"),
+                (2, "user-2"),
+                (3, "user-0"),
+                (4, "asst-1"),
+            ]
+        );
+        assert_eq!(merged.session.message_count, 5);
+    }
+
+    #[test]
+    fn merge_orders_by_timestamp_and_resequences_from_zero() {
+        let mut late = message("c", Role::User, 50);
+        late.sequence = 7;
+        let mut untimed = message("d", Role::Assistant, 0);
+        untimed.ts = None;
+        let first = fragment(vec![message("a", Role::User, 20), late, untimed]);
+        let second = fragment(vec![
+            message("x", Role::User, 10),
+            message("y", Role::Assistant, 30),
+        ]);
+
+        let merged = first.merge(second);
+        // The untimed message stays right after the one it followed
+        assert_eq!(
+            ids(&merged),
+            [(0, "x"), (1, "a"), (2, "y"), (3, "c"), (4, "d")]
+        );
+        assert_eq!(merged.session.started_at, merged.messages[0].ts);
+    }
+
+    #[test]
+    fn tool_names_normalize_across_agents() {
+        let cases = [
+            (ToolCategory::Read, ["Read", "read", "view_file"]),
+            (ToolCategory::Edit, ["Edit", "edit", "apply_patch"]),
+            (ToolCategory::Write, ["Write", "write", "create_file"]),
+            (ToolCategory::Search, ["Grep", "glob", "ls"]),
+            (ToolCategory::Exec, ["Bash", "bash", "shell"]),
+            (ToolCategory::Other, ["TodoWrite", "todowrite", "WebFetch"]),
+        ];
+        for (category, names) in cases {
+            for name in names {
+                assert_eq!(canonical_tool_category(name), category, "{}", name);
+            }
+        }
+    }
+
+    #[test]
+    fn not_found_means_a_missing_path_argument() {
+        let call = |name: &str, args: &str| CanonicalTool {
+            tool_name: name.to_string(),
+            category: canonical_tool_category(name),
+            call_id: "c".to_string(),
+            status: ToolStatus::Error,
+            error_class: None,
+            error_message: None,
+            args_summary: Some(args.to_string()),
+            output_summary: None,
+            output_bytes: None,
+            output_hash: None,
+            duration_ms: None,
+        };
+        let class =
+            |name, args, message| classify_tool_error(&call(name, args), message, "tool_error");
+
+        let missing = "File does not exist.";
+        assert_eq!(class("Read", "/repo/src/gone.rs", missing), "not_found");
+        assert_eq!(
+            class("Glob", "src/**/*.rs", "ENOENT: no such file"),
+            "not_found"
+        );
+        assert_eq!(
+            class(
+                "Edit",
+                "/repo/src/lib.rs",
+                "String to replace not found in file."
+            ),
+            "tool_error"
+        );
+        assert_eq!(
+            class(
+                "Bash",
+                "cargo nextest",
+                "bash: cargo-nextest: command not found"
+            ),
+            "tool_error"
+        );
+        // A missing path mentioned by a shell command isn't the call's target
+        assert_eq!(
+            class("Bash", "ls /tmp/x", "ls: /tmp/x: No such file or directory"),
+            "tool_error"
+        );
+        assert_eq!(class("Grep", "TODO", missing), "tool_error");
+        assert_eq!(
+            class("Bash", "curl example.com", "Operation timed out"),
+            "timeout"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn home_collapses_only_at_a_path_component() {
+        assert_eq!(collapse_home_in("/home/al/src", "/home/al"), "~/src");
+        assert_eq!(collapse_home_in("/home/al", "/home/al/"), "~");
+        assert_eq!(
+            collapse_home_in("/home/alice/src", "/home/al"),
+            "/home/alice/src"
+        );
+        assert_eq!(collapse_home_in("/HOME/al/src", "/home/al"), "/HOME/al/src");
+        assert_eq!(collapse_home_in("/tmp/x", ""), "/tmp/x");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn home_collapses_case_insensitively_on_windows() {
+        let home = r"C:\Users\Al";
+        assert_eq!(collapse_home_in(r"c:\users\al\src", home), r"~\src");
+        assert_eq!(collapse_home_in("C:/Users/Al/src", home), "~/src");
+        assert_eq!(
+            collapse_home_in(r"C:\Users\Alice\src", home),
+            r"C:\Users\Alice\src"
+        );
+    }
 }
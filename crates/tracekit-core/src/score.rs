@@ -0,0 +1,151 @@
+/// A single 0–100 efficiency score per analyzed session, to sort sessions by
+/// and track over time. It folds four signals into one number:
+///
+/// - **waste**: identified waste as a share of the session's cost
+/// - **findings**: total finding confidence, saturating at five confident findings
+/// - **cache**: share of input tokens not served from the prompt cache
+/// - **tool errors**: share of tool calls that failed
+///
+/// Each signal is a 0–1 penalty; the score is 100 minus their weighted average.
+/// A signal that doesn't apply (no cost, no caching reported, no tool calls) is
+/// left out of the average rather than counted as perfect or as failed.
+use crate::schema::AnalysisResult;
+
+/// Relative weight of each score component; they needn't sum to anything.
+#[derive(Debug, Clone, Copy)]
+pub struct ScoreWeights {
+    pub waste: f64,
+    pub findings: f64,
+    pub cache: f64,
+    pub tool_errors: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            waste: 40.0,
+            findings: 20.0,
+            cache: 20.0,
+            tool_errors: 20.0,
+        }
+    }
+}
+
+/// Total finding confidence at which the findings penalty is full.
+const FINDINGS_SATURATION: f64 = 5.0;
+
+/// Efficiency score with the default weights.
+pub fn efficiency_score(result: &AnalysisResult) -> u8 {
+    efficiency_score_with(result, &ScoreWeights::default())
+}
+
+/// The score stored on the session, or computed now if none was stored. Commands
+/// that rescale cost or waste for display store the score first, so a view
+/// doesn't change it.
+pub fn session_score(result: &AnalysisResult) -> u8 {
+    result
+        .session
+        .efficiency_score
+        .unwrap_or_else(|| efficiency_score(result))
+}
+
+pub fn efficiency_score_with(result: &AnalysisResult, weights: &ScoreWeights) -> u8 {
+    let s = &result.session;
+
+    let waste: f64 = result
+        .findings
+        .iter()
+        .filter_map(|f| f.wasted_cost_usd)
+        .sum();
+    let waste_ratio = s
+        .total_cost_usd
+        .filter(|&c| c > 0.0)
+        .map(|c| (waste / c).min(1.0));
+    let confidence: f64 = result.findings.iter().map(|f| f.confidence).sum();
+    let findings = Some((confidence / FINDINGS_SATURATION).min(1.0));
+    let cached = s.total_cache_read_tokens + s.total_cache_write_tokens;
+    let cache_miss = (cached > 0 && s.total_input_tokens > 0)
+        .then(|| 1.0 - s.total_cache_read_tokens as f64 / s.total_input_tokens as f64);
+    let tool_errors = (s.tool_calls > 0).then(|| s.tool_errors as f64 / s.tool_calls as f64);
+
+    let components = [
+        (weights.waste, waste_ratio),
+        (weights.findings, findings),
+        (weights.cache, cache_miss),
+        (weights.tool_errors, tool_errors),
+    ];
+    let (weighted, total) = components
+        .iter()
+        .filter_map(|&(w, penalty)| Some((w * penalty?, w)))
+        .fold((0.0, 0.0), |(p, t), (wp, w)| (p + wp, t + w));
+    if total <= 0.0 {
+        return 100;
+    }
+    ((1.0 - weighted / total) * 100.0).round().clamp(0.0, 100.0) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// $10 session with 10 tool calls (2 failed), no caching, and one fully
+    /// confident finding wasting `waste` dollars.
+    fn result(waste: f64) -> AnalysisResult {
+        serde_json::from_value(serde_json::json!({
+            "session": {
+                "session_id": "s",
+                "source_agent": "claude",
+                "source_path": "/tmp/s.jsonl",
+                "cwd": null,
+                "title": null,
+                "started_at": null,
+                "ended_at": null,
+                "model": null,
+                "message_count": 4,
+                "total_cost_usd": 10.0,
+                "total_input_tokens": 1000,
+                "total_output_tokens": 100,
+                "tool_calls": 10,
+                "tool_errors": 2,
+            },
+            "findings": [{
+                "kind": "retry_loop",
+                "description": "retried",
+                "evidence": [],
+                "wasted_tokens": null,
+                "wasted_cost_usd": waste,
+                "confidence": 1.0,
+            }],
+            "top_expensive_messages": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn score_is_a_weighted_average_of_the_applicable_penalties() {
+        // Waste 0.5, findings 1/5, tool errors 0.2; cache is left out (nothing cached):
+        // (40 * 0.5 + 20 * 0.2 + 20 * 0.2) / 80 = 0.35
+        assert_eq!(efficiency_score(&result(5.0)), 65);
+        assert_eq!(efficiency_score(&result(0.0)), 90);
+
+        let waste_only = ScoreWeights {
+            waste: 1.0,
+            findings: 0.0,
+            cache: 0.0,
+            tool_errors: 0.0,
+        };
+        assert_eq!(efficiency_score_with(&result(5.0), &waste_only), 50);
+        // Waste beyond the session cost can't push the penalty past full
+        assert_eq!(efficiency_score_with(&result(50.0), &waste_only), 0);
+    }
+
+    #[test]
+    fn a_stored_score_wins_over_recomputing() {
+        let mut r = result(5.0);
+        r.session.efficiency_score = Some(65);
+        r.findings[0].wasted_cost_usd = Some(1.0);
+        assert_eq!(session_score(&r), 65);
+        r.session.efficiency_score = None;
+        assert_eq!(session_score(&r), efficiency_score(&r));
+    }
+}
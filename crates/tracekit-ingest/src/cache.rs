@@ -0,0 +1,147 @@
+//! On-disk cache of fully parsed sessions, so large histories are parsed once
+//! (`tracekit capture import`) and later analysis skips the agent-specific parsers.
+//!
+//! Entries live under `~/.cache/tracekit/parsed/` (the platform cache dir), one
+//! JSON file per session, and are keyed on the source file's size and mtime plus
+//! the probed message count (for OpenCode, also the newest message or part file's
+//! mtime). A session whose trace changed since it was cached is treated as a miss
+//! and parsed again, as is one cached under a different `--pricing-date` or price
+//! catalog (parse-time costs depend on both).
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracekit_core::{Agent, CanonicalSession, ParsedSession};
+
+/// Bump when `ParsedSession` changes shape, or adapters parse the same trace
+/// differently, so stale entries are ignored.
+const CACHE_VERSION: u32 = 7;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Fingerprint {
+    source_len: u64,
+    source_modified_ns: u64,
+    message_count: usize,
+    #[serde(default)]
+    pricing_date: Option<String>,
+    #[serde(default)]
+    pricing_catalog: u32,
+    /// OpenCode only: newest message/part file, since the session file itself
+    /// rarely changes as the conversation grows
+    #[serde(default)]
+    content_modified_ns: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry<P> {
+    version: u32,
+    fingerprint: Fingerprint,
+    parsed: P,
+}
+
+/// Directory holding cached parses, if the platform has a cache dir.
+pub fn cache_dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("tracekit").join("parsed"))
+}
+
+fn entry_path(session: &CanonicalSession) -> Option<PathBuf> {
+    let id: String = session
+        .session_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    cache_dir().map(|d| d.join(format!("{}-{}.json", session.source_agent, id)))
+}
+
+fn fingerprint(session: &CanonicalSession) -> Option<Fingerprint> {
+    let meta = std::fs::metadata(&session.source_path).ok()?;
+    let content_modified = match session.source_agent {
+        Agent::Opencode => crate::opencode::content_modified(session),
+        _ => None,
+    };
+    Some(Fingerprint {
+        source_len: meta.len(),
+        source_modified_ns: unix_nanos(meta.modified().ok()?)?,
+        message_count: session.message_count,
+        pricing_date: tracekit_core::pricing_date().map(|d| d.to_string()),
+        pricing_catalog: tracekit_core::CATALOG_VERSION,
+        content_modified_ns: content_modified.and_then(unix_nanos),
+    })
+}
+
+fn unix_nanos(t: SystemTime) -> Option<u64> {
+    Some(t.duration_since(UNIX_EPOCH).ok()?.as_nanos() as u64)
+}
+
+/// Whether an up-to-date parse of `session` is cached.
+pub fn is_cached(session: &CanonicalSession) -> bool {
+    read_entry(session).is_some()
+}
+
+fn read_entry(session: &CanonicalSession) -> Option<CacheEntry<ParsedSession>> {
+    let path = entry_path(session)?;
+    let data = std::fs::read(path).ok()?;
+    let entry: CacheEntry<ParsedSession> = serde_json::from_slice(&data).ok()?;
+    (entry.version == CACHE_VERSION && Some(&entry.fingerprint) == fingerprint(session).as_ref())
+        .then_some(entry)
+}
+
+/// Load a cached parse of `session`, or `None` if missing or stale.
+///
+/// Session metadata (inferred CWD, tags) comes from the freshly discovered
+/// `session`; only the messages, parse-time costs and skipped-line counts are
+/// taken from the cache.
+pub fn load(session: &CanonicalSession) -> Option<ParsedSession> {
+    let cached = read_entry(session)?.parsed;
+    let mut parsed = ParsedSession {
+        session: CanonicalSession {
+            auxiliary_cost_usd: cached.session.auxiliary_cost_usd,
+            parse_errors: cached.session.parse_errors,
+            ..session.clone()
+        },
+        messages: cached.messages,
+    };
+    parsed.compute_totals();
+    Some(parsed)
+}
+
+/// Write the parse of the discovered `session` to the cache. The entry is written
+/// to a temporary file and renamed into place, so an interrupted import never
+/// leaves a partial entry.
+pub fn store(session: &CanonicalSession, parsed: &ParsedSession) -> Result<()> {
+    let (Some(path), Some(fingerprint)) = (entry_path(session), fingerprint(session)) else {
+        anyhow::bail!(
+            "no cache directory or unreadable source for {}",
+            session.session_id
+        );
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache dir {}", dir.display()))?;
+    }
+    let entry = CacheEntry {
+        version: CACHE_VERSION,
+        fingerprint,
+        parsed,
+    };
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_vec(&entry)?)
+        .with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(())
+}
+
+/// Parse `session`, using the cache when it holds an up-to-date entry.
+pub fn parse_session_cached(session: &CanonicalSession) -> Result<ParsedSession> {
+    match load(session) {
+        Some(parsed) => Ok(parsed),
+        None => crate::parse_session(session),
+    }
+}
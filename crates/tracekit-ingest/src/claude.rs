@@ -4,26 +4,13 @@
 /// Subagent files live in <session-uuid>/subagents/agent-<id>.jsonl.
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use tracekit_core::*;
 use walkdir::WalkDir;
 
-use super::default_root;
-
-// ── raw record types ──────────────────────────────────────────────────────────
-
-#[derive(Debug, Deserialize)]
-struct RawRecord {
-    #[serde(rename = "type")]
-    kind: String,
-    #[serde(default)]
-    timestamp: Option<String>,
-    #[serde(flatten)]
-    rest: Value,
-}
+use super::{default_root, note_skipped, walk_entry};
 
 pub fn discover_sessions() -> Result<Vec<CanonicalSession>> {
     let root = match default_root(Agent::Claude) {
@@ -32,48 +19,183 @@ pub fn discover_sessions() -> Result<Vec<CanonicalSession>> {
     };
 
     if !root.exists() {
+        note_skipped(Agent::Claude, &root, "directory does not exist");
         return Ok(Vec::new());
     }
 
-    // Group JSONL files by session_id (the UUID filename, excluding subagent paths)
     // Session files: <project>/<uuid>.jsonl
     // Subagent files: <project>/<uuid>/subagents/agent-*.jsonl (handled during parse)
+    Ok(discover_in(&root, 2, 2))
+}
+
+/// Recursively discover sessions under an arbitrary directory.
+pub fn discover_sessions_under(dir: &Path) -> Vec<CanonicalSession> {
+    discover_in(dir, 1, usize::MAX)
+}
+
+/// Whether `path` looks like a Claude session file: `<uuid>.jsonl`, not a
+/// subagent's `agent-*.jsonl` (or a Codex `rollout-*.jsonl` in a mixed folder).
+pub fn is_session_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("jsonl")
+        && path
+            .file_stem()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| !n.starts_with("agent-") && !n.starts_with("rollout-"))
+}
+
+fn discover_in(root: &Path, min_depth: usize, max_depth: usize) -> Vec<CanonicalSession> {
+    // Group JSONL files by session_id (the UUID filename)
     let mut session_paths: HashMap<String, PathBuf> = HashMap::new();
 
-    for entry in WalkDir::new(&root)
-        .min_depth(2)
-        .max_depth(2)
+    for entry in WalkDir::new(root)
+        .min_depth(min_depth)
+        .max_depth(max_depth)
         .into_iter()
-        .filter_map(|e| e.ok())
+        .filter_map(|e| walk_entry(Agent::Claude, e))
     {
         let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+        if !is_session_file(path) {
+            if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
+                let subagent = path
+                    .file_stem()
+                    .and_then(|n| n.to_str())
+                    .is_some_and(|n| n.starts_with("agent-"));
+                let reason = if subagent {
+                    "subagent transcript (read with its parent session)"
+                } else {
+                    "named like a Codex rollout, not <session-id>.jsonl"
+                };
+                note_skipped(Agent::Claude, path, reason);
+            }
             continue;
         }
         if let Some(name) = path.file_stem().and_then(|n| n.to_str()) {
-            // Skip subagent files at this level (they're agent-* not uuid-*)
-            if name.starts_with("agent-") {
-                continue;
+            if let Some(previous) = session_paths.insert(name.to_string(), path.to_path_buf()) {
+                note_skipped(
+                    Agent::Claude,
+                    &previous,
+                    format!("duplicate session id; using {}", path.display()),
+                );
             }
-            session_paths.insert(name.to_string(), path.to_path_buf());
         }
     }
 
     let mut sessions = Vec::new();
     for (session_id, path) in session_paths {
+        // Skip unparseable sessions
         match probe_session(&session_id, &path) {
             Ok(s) => sessions.push(s),
-            Err(_) => {} // skip unparseable sessions
+            Err(e) => note_skipped(Agent::Claude, &path, format!("unreadable: {:#}", e)),
+        }
+    }
+
+    sessions
+}
+
+/// Recover the project directory Claude Code encodes in a session's folder name
+/// (`/Users/me/my-app` → `-Users-me-my-app`, with `.` also becoming `-`). Dashes
+/// are ambiguous, so each is treated as a separator only when the path built so
+/// far is an existing directory.
+pub(crate) fn project_dir_from_path(path: &Path) -> Option<PathBuf> {
+    let encoded = path.parent()?.file_name()?.to_str()?.replace("--", "-.");
+    let mut tokens = encoded.strip_prefix('-')?.split('-');
+    let mut dir = PathBuf::from("/");
+    let mut segment = tokens.next()?.to_string();
+    for tok in tokens {
+        if dir.join(&segment).is_dir() {
+            dir.push(&segment);
+            segment = tok.to_string();
+        } else {
+            segment = format!("{}-{}", segment, tok);
         }
     }
+    dir.push(segment);
+    Some(dir)
+}
 
-    Ok(sessions)
+/// The first prompt the user typed, skipping meta records, tool results and
+/// slash-command wrappers (`<command-name>…`).
+pub fn first_prompt(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|r| r.get("type").and_then(|v| v.as_str()) == Some("user"))
+        .filter(|r| r.get("isMeta").and_then(|v| v.as_bool()) != Some(true))
+        .find_map(|r| {
+            let text = match r.pointer("/message/content")? {
+                Value::String(s) => s.clone(),
+                Value::Array(blocks) => blocks
+                    .iter()
+                    .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                    .filter_map(|b| b.get("text")?.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+                _ => return None,
+            };
+            let text = text.trim();
+            (!text.is_empty() && !text.starts_with('<')).then(|| text.to_string())
+        })
+}
+
+/// Count lines that aren't valid JSON; parsing skips them, so a non-zero count
+/// means the trace is damaged or truncated.
+fn count_malformed_lines(content: &str) -> usize {
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter(|l| serde_json::from_str::<serde::de::IgnoredAny>(l).is_err())
+        .count()
+}
+
+/// Whether the trace stops mid-exchange: the user interrupted the last request
+/// (`[Request interrupted by user]`), the final assistant record called a tool
+/// that never got a result, or the last line was cut off mid-write.
+fn ended_abnormally(content: &str) -> bool {
+    let Some(last_line) = content.lines().rev().find(|l| !l.trim().is_empty()) else {
+        return false;
+    };
+    if serde_json::from_str::<serde::de::IgnoredAny>(last_line).is_err() {
+        return true;
+    }
+    let last = content
+        .lines()
+        .rev()
+        .filter_map(|l| serde_json::from_str::<Value>(l).ok())
+        .find(|r| {
+            matches!(
+                r.get("type").and_then(|v| v.as_str()),
+                Some("user" | "assistant")
+            )
+        });
+    let Some(last) = last else {
+        return false;
+    };
+    let content = last.pointer("/message/content");
+    let blocks = content
+        .and_then(|c| c.as_array())
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    match last.get("type").and_then(|v| v.as_str()) {
+        Some("assistant") => blocks
+            .iter()
+            .any(|b| b.get("type").and_then(|t| t.as_str()) == Some("tool_use")),
+        _ => {
+            let interrupted = |t: &str| t.trim_start().starts_with("[Request interrupted");
+            content.and_then(|c| c.as_str()).is_some_and(interrupted)
+                || blocks
+                    .iter()
+                    .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                    .any(interrupted)
+        }
+    }
 }
 
 /// Quick scan — read only first ~20 records to extract metadata.
 fn probe_session(session_id: &str, path: &Path) -> Result<CanonicalSession> {
     let content = std::fs::read_to_string(path)?;
     let mut cwd: Option<String> = None;
+    let mut branch: Option<String> = None;
     let mut started_at: Option<DateTime<Utc>> = None;
     let mut model: Option<String> = None;
     let mut message_count = 0usize;
@@ -98,6 +220,13 @@ fn probe_session(session_id: &str, path: &Path) -> Result<CanonicalSession> {
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string());
                 }
+                if branch.is_none() {
+                    branch = record
+                        .get("gitBranch")
+                        .and_then(|v| v.as_str())
+                        .filter(|b| !b.is_empty() && *b != "HEAD")
+                        .map(|s| s.to_string());
+                }
                 if started_at.is_none() {
                     if let Some(ts) = record.get("timestamp").and_then(|v| v.as_str()) {
                         started_at = ts.parse().ok();
@@ -130,6 +259,26 @@ fn probe_session(session_id: &str, path: &Path) -> Result<CanonicalSession> {
         total_cost_usd: None,
         total_input_tokens: 0,
         total_output_tokens: 0,
+        auxiliary_cost_usd: None,
+        heuristic_turns: 0,
+        cost_breakdown: None,
+        user_count: 0,
+        assistant_count: 0,
+        system_count: 0,
+        total_cache_read_tokens: 0,
+        total_cache_write_tokens: 0,
+        total_reasoning_tokens: 0,
+        tags: branch
+            .map(|b| vec![format!("branch:{}", b)])
+            .unwrap_or_default(),
+        parse_errors: count_malformed_lines(&content),
+        system_prompt: None,
+        ended_abnormally: ended_abnormally(&content),
+        tool_calls: 0,
+        tool_errors: 0,
+        efficiency_score: None,
+        pricing_confidence: None,
+        observed_cost_usd: None,
     })
 }
 
@@ -137,7 +286,7 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
     let mut messages = Vec::new();
     let mut seq = 0usize;
 
-    parse_jsonl_file(
+    let mut parse_errors = parse_jsonl_file(
         &session.source_path,
         session,
         &mut messages,
@@ -146,18 +295,9 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
     )?;
 
     // Also load subagent files
-    let subagent_dir = session.source_path.with_extension("").join("subagents");
-    if subagent_dir.exists() {
-        for entry in WalkDir::new(&subagent_dir)
-            .min_depth(1)
-            .max_depth(1)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("jsonl") {
-                let _ = parse_jsonl_file(path, session, &mut messages, &mut seq, true);
-            }
+    for path in subagent_files(session) {
+        if let Ok(skipped) = parse_jsonl_file(&path, session, &mut messages, &mut seq, true) {
+            parse_errors += skipped;
         }
     }
 
@@ -165,38 +305,169 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
     messages.sort_by_key(|m| m.sequence);
 
     Ok(ParsedSession {
-        session: session.clone(),
+        session: CanonicalSession {
+            parse_errors,
+            ..session.clone()
+        },
         messages,
     })
 }
 
+/// Append the messages in one JSONL file, returning how many malformed lines
+/// were skipped.
 fn parse_jsonl_file(
     path: &Path,
     session: &CanonicalSession,
     messages: &mut Vec<CanonicalMessage>,
     seq: &mut usize,
     is_sidechain: bool,
-) -> Result<()> {
+) -> Result<usize> {
     let content =
         std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    let mut state = FileState::default();
+    parse_lines(
+        &content,
+        path,
+        session,
+        messages,
+        seq,
+        is_sidechain,
+        &mut state,
+    );
+    Ok(state.skipped)
+}
 
-    // We need to pair tool_use calls with their tool_result responses.
-    // Tool uses appear in assistant messages, results in the following user message.
-    let mut pending_tools: HashMap<String, CanonicalTool> = HashMap::new();
+/// A session's subagent traces (`<session-uuid>/subagents/*.jsonl`), sorted by path.
+pub fn subagent_files(session: &CanonicalSession) -> Vec<PathBuf> {
+    let subagent_dir = session.source_path.with_extension("").join("subagents");
+    if !subagent_dir.exists() {
+        return Vec::new();
+    }
+    let mut files: Vec<PathBuf> = WalkDir::new(&subagent_dir)
+        .min_depth(1)
+        .max_depth(1)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .map(|e| e.into_path())
+        .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("jsonl"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Position and tool-pairing state for one JSONL file, kept between reads so a
+/// growing file can be parsed a piece at a time.
+#[derive(Debug, Default)]
+struct FileState {
+    /// Bytes consumed so far; always the end of a complete line
+    offset: u64,
+    line_no: usize,
+    /// We need to pair tool_use calls with their tool_result responses.
+    /// Tool uses appear in assistant messages, results in the following user message.
+    /// Keyed by the source tool_use id; each queued entry is the call_id we assigned,
+    /// so duplicate ids pair with their results in order.
+    pending_tools: HashMap<String, VecDeque<String>>,
+    skipped: usize,
+}
+
+/// Incremental parse state for [`parse_tail`]: where each of the session's files
+/// (main trace and subagents) was read up to, tool calls still awaiting their
+/// results, and the running message sequence.
+#[derive(Debug, Default)]
+pub struct TailState {
+    files: HashMap<PathBuf, FileState>,
+    seq: usize,
+}
+
+impl TailState {
+    /// Malformed lines skipped so far, across all files.
+    pub fn parse_errors(&self) -> usize {
+        self.files.values().map(|f| f.skipped).sum()
+    }
+}
+
+/// Parse only what was appended to the session's files since the last call,
+/// adding new messages to `messages` and filling in tool results for calls made
+/// in earlier reads. A partly written last line is left for the next call.
+/// Returns the number of messages added. If the main trace shrank (rewritten or
+/// truncated), parsing starts over.
+pub fn parse_tail(
+    session: &CanonicalSession,
+    state: &mut TailState,
+    messages: &mut Vec<CanonicalMessage>,
+) -> Result<usize> {
+    let main_len = std::fs::metadata(&session.source_path)
+        .with_context(|| format!("reading {}", session.source_path.display()))?
+        .len();
+    if state
+        .files
+        .get(&session.source_path)
+        .is_some_and(|f| main_len < f.offset)
+    {
+        *state = TailState::default();
+        messages.clear();
+    }
+
+    let before = messages.len();
+    let mut paths = vec![(session.source_path.clone(), false)];
+    paths.extend(subagent_files(session).into_iter().map(|p| (p, true)));
+
+    for (path, is_sidechain) in paths {
+        let file = state.files.entry(path.clone()).or_default();
+        let Some(appended) = read_appended(&path, file)? else {
+            continue;
+        };
+        parse_lines(
+            &appended,
+            &path,
+            session,
+            messages,
+            &mut state.seq,
+            is_sidechain,
+            file,
+        );
+    }
+    Ok(messages.len() - before)
+}
+
+/// The complete lines appended to `path` since `file.offset`, advancing the
+/// offset past them; `None` when nothing new has been fully written.
+fn read_appended(path: &Path, file: &mut FileState) -> Result<Option<String>> {
+    use std::io::{Read, Seek, SeekFrom};
+    let mut f = std::fs::File::open(path).with_context(|| format!("reading {}", path.display()))?;
+    f.seek(SeekFrom::Start(file.offset))?;
+    let mut buf = Vec::new();
+    f.read_to_end(&mut buf)?;
+    let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+        return Ok(None);
+    };
+    buf.truncate(end + 1);
+    file.offset += buf.len() as u64;
+    Ok(Some(String::from_utf8_lossy(&buf).into_owned()))
+}
 
-    for (line_no, line) in content.lines().enumerate() {
+/// Append the messages in `content`, a run of complete JSONL lines from `path`,
+/// counting malformed lines in `state`.
+fn parse_lines(
+    content: &str,
+    path: &Path,
+    session: &CanonicalSession,
+    messages: &mut Vec<CanonicalMessage>,
+    seq: &mut usize,
+    is_sidechain: bool,
+    state: &mut FileState,
+) {
+    for line in content.lines() {
+        state.line_no += 1;
+        let line_no = state.line_no;
         if line.trim().is_empty() {
             continue;
         }
         let record: Value = match serde_json::from_str(line) {
             Ok(v) => v,
             Err(e) => {
-                eprintln!(
-                    "warn: {}:{}: parse error: {}",
-                    path.display(),
-                    line_no + 1,
-                    e
-                );
+                eprintln!("warn: {}:{}: parse error: {}", path.display(), line_no, e);
+                state.skipped += 1;
                 continue;
             }
         };
@@ -233,11 +504,14 @@ fn parse_jsonl_file(
 
                 // Tool calls from content blocks
                 let mut tool_calls: Vec<CanonicalTool> = Vec::new();
+                let mut ids_seen: HashMap<String, usize> = HashMap::new();
+                let mut content_chars = 0usize;
                 if let Some(content_arr) = record
                     .pointer("/message/content")
                     .and_then(|v| v.as_array())
                 {
                     for block in content_arr {
+                        content_chars += block_chars(block);
                         if block.get("type").and_then(|v| v.as_str()) == Some("tool_use") {
                             let tool_id = block
                                 .get("id")
@@ -251,17 +525,40 @@ fn parse_jsonl_file(
                                 .to_string();
                             let args_summary = extract_args_key(block.get("input"));
 
+                            // Malformed logs can repeat a tool_use id within one turn;
+                            // suffix repeats so both calls stay distinct
+                            let seen = ids_seen.entry(tool_id.clone()).or_default();
+                            *seen += 1;
+                            let call_id = if *seen > 1 {
+                                eprintln!(
+                                    "warn: {}:{}: duplicate tool_use id {}",
+                                    path.display(),
+                                    line_no,
+                                    tool_id
+                                );
+                                format!("{}#{}", tool_id, seen)
+                            } else {
+                                tool_id.clone()
+                            };
+
                             let tool = CanonicalTool {
                                 tool_name: tool_name.clone(),
-                                call_id: tool_id.clone(),
+                                category: canonical_tool_category(&tool_name),
+                                call_id: call_id.clone(),
                                 status: ToolStatus::Unknown,
                                 error_class: None,
                                 error_message: None,
                                 args_summary,
                                 output_summary: None,
+                                output_bytes: None,
+                                output_hash: None,
                                 duration_ms: None,
                             };
-                            pending_tools.insert(tool_id, tool.clone());
+                            state
+                                .pending_tools
+                                .entry(tool_id)
+                                .or_default()
+                                .push_back(call_id);
                             tool_calls.push(tool);
                         }
                     }
@@ -287,6 +584,8 @@ fn parse_jsonl_file(
                         .pointer("/message/stop_reason")
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string()),
+                    content_chars,
+                    prompt_lines: Vec::new(),
                 });
             }
 
@@ -308,12 +607,21 @@ fn parse_jsonl_file(
                                 .and_then(|v| v.as_bool())
                                 .unwrap_or(false);
 
-                            if let Some(pending) = pending_tools.get(&tool_use_id) {
+                            let call_id = state
+                                .pending_tools
+                                .get_mut(&tool_use_id)
+                                .and_then(|queue| queue.pop_front());
+                            if let Some(call_id) = call_id {
                                 let status = if is_error {
                                     ToolStatus::Error
                                 } else {
                                     ToolStatus::Success
                                 };
+                                let output_bytes = content_bytes(block.get("content"));
+                                let output_hash = block.get("content").map(|c| match c {
+                                    Value::String(s) => content_hash(s),
+                                    other => content_hash(&other.to_string()),
+                                });
                                 let err_msg = if is_error {
                                     extract_content_text(block.get("content"))
                                         .map(|s| s.chars().take(200).collect())
@@ -325,11 +633,17 @@ fn parse_jsonl_file(
                                 for msg in messages.iter_mut().rev() {
                                     let mut updated = false;
                                     for tool in msg.tool_calls.iter_mut() {
-                                        if tool.call_id == tool_use_id {
+                                        if tool.call_id == call_id {
                                             tool.status = status;
                                             tool.error_message = err_msg.clone();
+                                            tool.output_bytes = output_bytes;
+                                            tool.output_hash = output_hash;
                                             if is_error {
-                                                tool.error_class = Some("tool_error".to_string());
+                                                tool.error_class = Some(classify_tool_error(
+                                                    tool,
+                                                    err_msg.as_deref().unwrap_or_default(),
+                                                    "tool_error",
+                                                ));
                                             }
                                             updated = true;
                                             break;
@@ -339,8 +653,13 @@ fn parse_jsonl_file(
                                         break;
                                     }
                                 }
-                                let _ = pending; // suppress warning
-                                pending_tools.remove(&tool_use_id);
+                                if state
+                                    .pending_tools
+                                    .get(&tool_use_id)
+                                    .is_some_and(|queue| queue.is_empty())
+                                {
+                                    state.pending_tools.remove(&tool_use_id);
+                                }
                             }
                         }
                     }
@@ -351,6 +670,22 @@ fn parse_jsonl_file(
                     .get("timestamp")
                     .and_then(|v| v.as_str())
                     .and_then(|s| s.parse::<DateTime<Utc>>().ok());
+                let content_chars = match record.pointer("/message/content") {
+                    Some(Value::String(s)) => s.chars().count(),
+                    Some(Value::Array(arr)) => arr.iter().map(block_chars).sum(),
+                    _ => 0,
+                };
+                // Typed/pasted text only; tool results come back in user records too
+                let prompt_text = match record.pointer("/message/content") {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Array(arr)) => arr
+                        .iter()
+                        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                        .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    _ => String::new(),
+                };
 
                 messages.push(CanonicalMessage {
                     message_id: record
@@ -364,21 +699,26 @@ fn parse_jsonl_file(
                         .and_then(|v| v.as_str())
                         .map(|s| s.to_string()),
                     sequence: *seq,
-                    role: Role::User,
+                    // Injected meta records (command output, reminders) aren't user turns
+                    role: if record.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+                        Role::System
+                    } else {
+                        Role::User
+                    },
                     model: None,
                     ts,
                     usage: None,
                     tool_calls: Vec::new(),
                     is_sidechain,
                     finish_reason: None,
+                    content_chars,
+                    prompt_lines: prompt_lines(&prompt_text),
                 });
             }
 
             _ => {}
         }
     }
-
-    Ok(())
 }
 
 fn extract_claude_usage(record: &Value, model: Option<&str>) -> Option<CanonicalUsage> {
@@ -396,25 +736,45 @@ fn extract_claude_usage(record: &Value, model: Option<&str>) -> Option<Canonical
         .get("cache_read_input_tokens")
         .and_then(|v| v.as_u64())
         .unwrap_or(0);
-    let cache_write = usage
+    let flat_cache_write = usage
         .get("cache_creation_input_tokens")
         .and_then(|v| v.as_u64())
         .unwrap_or(0);
 
-    let cost_estimated = model.and_then(|m| {
-        tracekit_core::estimate_cost(m, input_tokens, output_tokens, cache_read, cache_write)
-    });
+    // Newer usage objects split cache creation by TTL; the tiers are priced
+    // differently, so prefer them over the flat total when present
+    let tier = |key: &str| {
+        usage
+            .pointer(&format!("/cache_creation/{}", key))
+            .and_then(|v| v.as_u64())
+    };
+    let (cache_write, cache_write_1h) = match (
+        tier("ephemeral_5m_input_tokens"),
+        tier("ephemeral_1h_input_tokens"),
+    ) {
+        (None, None) => (flat_cache_write, 0),
+        (five_min, one_hour) => {
+            let one_hour = one_hour.unwrap_or(0);
+            (five_min.unwrap_or(0) + one_hour, one_hour)
+        }
+    };
 
-    Some(CanonicalUsage {
+    let mut canonical = CanonicalUsage {
         input_tokens,
         output_tokens,
         reasoning_tokens: 0,
         cache_read_tokens: cache_read,
         cache_write_tokens: cache_write,
+        cache_write_1h_tokens: cache_write_1h,
         cost_observed_usd: None,
-        cost_estimated_usd: cost_estimated,
+        cost_estimated_usd: None,
         latency_ms: None,
-    })
+        heuristic: false,
+    };
+    canonical.cost_estimated_usd = model
+        .and_then(tracekit_core::lookup_price)
+        .map(|p| tracekit_core::cost_breakdown(&canonical, &p).total());
+    Some(canonical)
 }
 
 fn extract_args_key(input: Option<&Value>) -> Option<String> {
@@ -422,6 +782,7 @@ fn extract_args_key(input: Option<&Value>) -> Option<String> {
     // Try common path/file keys
     for key in &[
         "file_path",
+        "filePath",
         "path",
         "pattern",
         "command",
@@ -445,19 +806,414 @@ fn extract_args_key(input: Option<&Value>) -> Option<String> {
     None
 }
 
+/// Character count of a content block's text-like payload.
+fn block_chars(block: &Value) -> usize {
+    match block.get("type").and_then(|v| v.as_str()) {
+        Some("text") => block.get("text"),
+        Some("thinking") => block.get("thinking"),
+        Some("tool_use") => {
+            return block
+                .get("input")
+                .map(|v| v.to_string().chars().count())
+                .unwrap_or(0)
+        }
+        Some("tool_result") => {
+            return extract_content_text(block.get("content"))
+                .map(|s| s.chars().count())
+                .unwrap_or(0)
+        }
+        _ => None,
+    }
+    .and_then(|v| v.as_str())
+    .map(|s| s.chars().count())
+    .unwrap_or(0)
+}
+
+/// Text of a tool_result's content. Non-text blocks (images, JSON payloads) are
+/// summarized as `[image/png, ~48.0 KB]` so they still surface in error messages.
 fn extract_content_text(content: Option<&Value>) -> Option<String> {
     let v = content?;
     if let Some(s) = v.as_str() {
         return Some(s.to_string());
     }
     if let Some(arr) = v.as_array() {
-        for item in arr {
-            if item.get("type").and_then(|t| t.as_str()) == Some("text") {
-                if let Some(s) = item.get("text").and_then(|t| t.as_str()) {
-                    return Some(s.to_string());
-                }
+        let parts: Vec<String> = arr
+            .iter()
+            .map(|item| match item.get("type").and_then(|t| t.as_str()) {
+                Some("text") => item
+                    .get("text")
+                    .and_then(|t| t.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                Some("image") => format!(
+                    "[{}, ~{}]",
+                    item.pointer("/source/media_type")
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("image"),
+                    fmt_bytes(block_bytes(item))
+                ),
+                other => format!(
+                    "[{} block, ~{}]",
+                    other.unwrap_or("json"),
+                    fmt_bytes(block_bytes(item))
+                ),
+            })
+            .filter(|s| !s.is_empty())
+            .collect();
+        return (!parts.is_empty()).then(|| parts.join("\n"));
+    }
+    if v.is_null() {
+        return None;
+    }
+    Some(format!(
+        "[json, ~{}]",
+        fmt_bytes(v.to_string().len() as u64)
+    ))
+}
+
+/// Approximate size in bytes of a tool_result's content, across all block types.
+fn content_bytes(content: Option<&Value>) -> Option<u64> {
+    let v = content?;
+    match v {
+        Value::Null => None,
+        Value::String(s) => Some(s.len() as u64),
+        Value::Array(arr) => Some(arr.iter().map(block_bytes).sum()),
+        other => Some(other.to_string().len() as u64),
+    }
+}
+
+/// Size of one content block: text length, decoded size of base64 image data,
+/// or serialized length for anything else.
+fn block_bytes(block: &Value) -> u64 {
+    match block.get("type").and_then(|t| t.as_str()) {
+        Some("text") => block
+            .get("text")
+            .and_then(|t| t.as_str())
+            .map(|s| s.len() as u64)
+            .unwrap_or(0),
+        Some("image") => match block.pointer("/source/data").and_then(|d| d.as_str()) {
+            Some(data) => data.len() as u64 * 3 / 4,
+            None => block.to_string().len() as u64,
+        },
+        _ => block.to_string().len() as u64,
+    }
+}
+
+fn fmt_bytes(n: u64) -> String {
+    if n >= 1_048_576 {
+        format!("{:.1} MB", n as f64 / 1_048_576.0)
+    } else if n >= 1024 {
+        format!("{:.1} KB", n as f64 / 1024.0)
+    } else {
+        format!("{} B", n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A throwaway session trace, `<dir>/session.jsonl`.
+    struct Trace {
+        dir: PathBuf,
+    }
+
+    impl Trace {
+        fn new(records: &[Value]) -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let dir = std::env::temp_dir().join(format!(
+                "tracekit-claude-{}-{}",
+                std::process::id(),
+                NEXT.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::create_dir_all(&dir).unwrap();
+            let trace = Self { dir };
+            trace.append(records);
+            trace
+        }
+
+        fn path(&self) -> PathBuf {
+            self.dir.join("session.jsonl")
+        }
+
+        fn append(&self, records: &[Value]) {
+            use std::io::Write;
+            let mut f = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.path())
+                .unwrap();
+            for r in records {
+                writeln!(f, "{}", r).unwrap();
             }
         }
+
+        fn session(&self) -> CanonicalSession {
+            probe_session("session", &self.path()).unwrap()
+        }
+
+        fn parse(&self) -> ParsedSession {
+            let mut parsed = parse_session(&self.session()).unwrap();
+            parsed.compute_totals();
+            parsed
+        }
+    }
+
+    impl Drop for Trace {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn user(text: &str) -> Value {
+        json!({"type": "user", "timestamp": "2026-01-01T00:00:00Z", "message": {"role": "user", "content": text}})
+    }
+
+    fn assistant(id: &str, content: Value) -> Value {
+        json!({
+            "type": "assistant",
+            "timestamp": "2026-01-01T00:00:01Z",
+            "message": {"id": id, "model": "claude-sonnet-4", "content": content,
+                        "usage": {"input_tokens": 10, "output_tokens": 5}}
+        })
+    }
+
+    #[test]
+    fn meta_records_parse_as_system_messages() {
+        let mut meta = user("<command-output>ok</command-output>");
+        meta["isMeta"] = json!(true);
+        let trace = Trace::new(&[
+            user("fix the build"),
+            meta,
+            assistant("msg_1", json!([{"type": "text", "text": "done"}])),
+        ]);
+
+        let parsed = trace.parse();
+        let roles: Vec<Role> = parsed.messages.iter().map(|m| m.role).collect();
+        assert_eq!(roles, [Role::User, Role::System, Role::Assistant]);
+        assert_eq!(parsed.session.user_count, 1);
+        assert_eq!(parsed.session.system_count, 1);
+        assert_eq!(parsed.session.role_count(&[Role::User, Role::Assistant]), 2);
+    }
+
+    fn tool_use(id: &str, command: &str) -> Value {
+        json!({"type": "tool_use", "id": id, "name": "Bash", "input": {"command": command}})
+    }
+
+    fn tool_result(id: &str, is_error: bool, content: Value) -> Value {
+        json!({
+            "type": "user",
+            "message": {"role": "user", "content": [
+                {"type": "tool_result", "tool_use_id": id, "is_error": is_error, "content": content}
+            ]}
+        })
+    }
+
+    #[test]
+    fn repeated_tool_use_ids_stay_distinct_and_pair_in_order() {
+        let trace = Trace::new(&[
+            user("run the tests"),
+            assistant(
+                "msg_1",
+                json!([
+                    tool_use("toolu_1", "cargo test"),
+                    tool_use("toolu_1", "cargo build")
+                ]),
+            ),
+            tool_result("toolu_1", true, json!("tests failed")),
+            tool_result("toolu_1", false, json!("built")),
+        ]);
+
+        let parsed = trace.parse();
+        let calls = &parsed.messages[1].tool_calls;
+        let ids: Vec<&str> = calls.iter().map(|t| t.call_id.as_str()).collect();
+        assert_eq!(ids, ["toolu_1", "toolu_1#2"]);
+        assert_eq!(calls[0].status, ToolStatus::Error);
+        assert_eq!(calls[0].error_message.as_deref(), Some("tests failed"));
+        assert_eq!(calls[1].status, ToolStatus::Success);
+        assert_eq!(calls[1].error_message, None);
+    }
+
+    #[test]
+    fn structured_tool_results_are_sized_and_summarized() {
+        let image = json!({
+            "type": "image",
+            "source": {"type": "base64", "media_type": "image/png", "data": "A".repeat(4000)}
+        });
+        let payload = json!({"type": "json", "rows": [1, 2, 3]});
+        let trace = Trace::new(&[
+            user("take a screenshot"),
+            assistant("msg_1", json!([tool_use("toolu_1", "screenshot")])),
+            tool_result("toolu_1", true, json!([image, payload])),
+        ]);
+
+        let parsed = trace.parse();
+        let tool = &parsed.messages[1].tool_calls[0];
+        let payload_bytes = payload.to_string().len() as u64;
+        assert_eq!(tool.output_bytes, Some(3000 + payload_bytes));
+        assert_eq!(
+            tool.error_message.as_deref(),
+            Some(format!("[image/png, ~2.9 KB]\n[json block, ~{} B]", payload_bytes).as_str())
+        );
+    }
+
+    #[test]
+    fn tiered_cache_creation_prices_the_hour_tier_higher() {
+        let mut tiered = assistant("msg_1", json!([{"type": "text", "text": "a"}]));
+        tiered["message"]["usage"]["cache_creation_input_tokens"] = json!(300_000);
+        tiered["message"]["usage"]["cache_creation"] = json!({
+            "ephemeral_5m_input_tokens": 100_000,
+            "ephemeral_1h_input_tokens": 200_000,
+        });
+        let mut flat = assistant("msg_2", json!([{"type": "text", "text": "b"}]));
+        flat["message"]["usage"]["cache_creation_input_tokens"] = json!(300_000);
+        let trace = Trace::new(&[user("go"), tiered, flat]);
+
+        let parsed = trace.parse();
+        let usage = |i: usize| parsed.messages[i].usage.clone().unwrap();
+        let (tiered, flat) = (usage(1), usage(2));
+        assert_eq!(tiered.cache_write_tokens, 300_000);
+        assert_eq!(tiered.cache_write_1h_tokens, 200_000);
+        assert_eq!(flat.cache_write_tokens, 300_000);
+        assert_eq!(flat.cache_write_1h_tokens, 0);
+
+        // Sonnet: 5m writes at $3.75/Mtok, 1h writes at $6/Mtok, plus 10 in / 5 out
+        let base = 10.0 * 3.0 / 1e6 + 5.0 * 15.0 / 1e6;
+        let cost = |u: &CanonicalUsage| u.cost_estimated_usd.unwrap() - base;
+        assert!((cost(&tiered) - (0.375 + 1.2)).abs() < 1e-9);
+        assert!((cost(&flat) - 1.125).abs() < 1e-9);
+    }
+
+    #[test]
+    fn large_system_block_shows_in_system_prompt_size() {
+        let mut instructions = user(&"Always run the linters before committing.\n".repeat(1000));
+        instructions["isMeta"] = json!(true);
+        let mut unbilled = assistant("msg_1", json!([{"type": "text", "text": "ok"}]));
+        unbilled["message"].as_object_mut().unwrap().remove("usage");
+        let trace = Trace::new(&[instructions.clone(), user("fix the build"), unbilled]);
+
+        // No usage on the first reply: estimated from the text sent before it
+        let size = trace.parse().system_prompt_size(4.0).unwrap();
+        assert!(size.estimated);
+        assert!(size.tokens > 10_000, "{:?}", size);
+
+        let mut billed = assistant("msg_1", json!([{"type": "text", "text": "ok"}]));
+        billed["message"]["usage"]["cache_creation_input_tokens"] = json!(18_000);
+        let trace = Trace::new(&[instructions, user("fix the build"), billed]);
+        let size = trace.parse().system_prompt_size(4.0).unwrap();
+        assert_eq!(
+            size,
+            SystemPromptSize {
+                tokens: 18_010,
+                estimated: false
+            }
+        );
+    }
+
+    #[test]
+    fn identical_large_outputs_are_flagged_as_redundant() {
+        let listing = "src/lib.rs: 1200 lines\n".repeat(200);
+        let other = "tests/cli.rs: 300 lines\n".repeat(200);
+        let trace = Trace::new(&[
+            user("what's in the tree?"),
+            assistant("msg_1", json!([tool_use("toolu_1", "wc -l src/*")])),
+            tool_result("toolu_1", false, json!(listing)),
+            assistant("msg_2", json!([tool_use("toolu_2", "wc -l tests/*")])),
+            tool_result("toolu_2", false, json!(other)),
+            assistant("msg_3", json!([tool_use("toolu_3", "wc -l src/*")])),
+            tool_result("toolu_3", false, json!(listing)),
+        ]);
+
+        let parsed = trace.parse();
+        let findings = RedundantOutputDetector.detect(&parsed);
+        assert_eq!(findings.len(), 1);
+        let f = &findings[0];
+        assert_eq!(f.kind, FindingKind::RedundantOutput);
+        let seq = |id: &str| {
+            parsed
+                .messages
+                .iter()
+                .find(|m| m.message_id == id)
+                .unwrap()
+                .sequence
+        };
+        let turns: Vec<usize> = f.evidence_refs.iter().map(|r| r.sequence).collect();
+        assert_eq!(turns, [seq("msg_1"), seq("msg_3")]);
+        // Only the repeat counts: its bytes at ~4 per token
+        assert_eq!(f.wasted_tokens, Some(listing.len() as u64 / 4));
+    }
+
+    #[test]
+    fn a_large_paste_repeated_in_a_later_prompt_is_flagged() {
+        let code: String = (0..60)
+            .map(|i| format!("    let value_{} = compute(input, {});\n", i, i))
+            .collect();
+        // The second paste is re-indented; line fingerprints ignore whitespace
+        let reindented = code.replace("    ", "\t");
+        let trace = Trace::new(&[
+            user(&format!("Why does this panic?\n{}", code)),
+            assistant(
+                "msg_1",
+                json!([{"type": "text", "text": "Check the bounds."}]),
+            ),
+            user(&format!("Still panics:\n{}", reindented)),
+            assistant("msg_2", json!([{"type": "text", "text": "Try this."}])),
+            user("thanks"),
+        ]);
+
+        let parsed = trace.parse();
+        let findings = RepeatedPasteDetector.detect(&parsed);
+        assert_eq!(findings.len(), 1);
+        let f = &findings[0];
+        assert_eq!(f.kind, FindingKind::RepeatedPaste);
+        assert_eq!(f.evidence_refs.len(), 1);
+        assert_eq!(f.evidence_refs[0].sequence, parsed.messages[2].sequence);
+        assert!(f.wasted_tokens.unwrap() > 300, "{:?}", f.wasted_tokens);
+        assert!(f.wasted_cost_usd.is_some());
+    }
+
+    #[test]
+    fn tail_parsing_in_chunks_matches_a_full_parse() {
+        let trace = Trace::new(&[
+            user("run the tests, café"),
+            assistant(
+                "msg_1",
+                json!([
+                    tool_use("toolu_1", "cargo test"),
+                    tool_use("toolu_2", "cat missing.rs")
+                ]),
+            ),
+            tool_result(
+                "toolu_2",
+                true,
+                json!("cat: missing.rs: No such file or directory"),
+            ),
+            tool_result("toolu_1", false, json!("test result: ok. 3 passed")),
+            assistant("msg_2", json!([{"type": "text", "text": "All green."}])),
+        ]);
+        let content = std::fs::read(trace.path()).unwrap();
+        let session = trace.session();
+        let as_json = |msgs: &[CanonicalMessage]| serde_json::to_value(msgs).unwrap();
+        let full = parse_session(&session).unwrap().messages;
+
+        for step in [1, 7, 64, 333, content.len()] {
+            std::fs::write(trace.path(), b"").unwrap();
+            let mut state = TailState::default();
+            let mut messages = Vec::new();
+            let mut cut = 0;
+            while cut < content.len() {
+                cut = (cut + step).min(content.len());
+                std::fs::write(trace.path(), &content[..cut]).unwrap();
+                parse_tail(&session, &mut state, &mut messages).unwrap();
+            }
+            assert_eq!(as_json(&messages), as_json(&full), "step {}", step);
+        }
+
+        // Results read after their calls still pair by id
+        let calls = &full[1].tool_calls;
+        assert_eq!(calls[0].status, ToolStatus::Success);
+        assert_eq!(calls[1].status, ToolStatus::Error);
     }
-    None
 }
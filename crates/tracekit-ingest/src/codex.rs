@@ -9,7 +9,7 @@ use std::path::Path;
 use tracekit_core::*;
 use walkdir::WalkDir;
 
-use super::default_root;
+use super::{default_root, note_skipped, walk_entry};
 
 pub fn discover_sessions() -> Result<Vec<CanonicalSession>> {
     let root = match default_root(Agent::Codex) {
@@ -18,42 +18,98 @@ pub fn discover_sessions() -> Result<Vec<CanonicalSession>> {
     };
 
     if !root.exists() {
+        note_skipped(Agent::Codex, &root, "directory does not exist");
         return Ok(Vec::new());
     }
 
-    let mut sessions = Vec::new();
+    // Rollouts normally live at YYYY/MM/DD/rollout-*.jsonl, but archived or
+    // reorganized trees nest differently; accept any shallow depth and rely on
+    // the filename prefix plus a content probe instead
+    Ok(discover_in(&root, 6))
+}
 
-    for entry in WalkDir::new(&root)
-        .min_depth(4) // YYYY/MM/DD/rollout-*.jsonl
-        .max_depth(4)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
-            continue;
-        }
-        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-        if !name.starts_with("rollout-") {
-            continue;
-        }
+/// Recursively discover sessions under an arbitrary directory.
+pub fn discover_sessions_under(dir: &Path) -> Vec<CanonicalSession> {
+    discover_in(dir, usize::MAX)
+}
 
-        match probe_session(path) {
-            Ok(s) => sessions.push(s),
-            Err(_) => {}
-        }
-    }
+/// Whether `path` looks like a Codex rollout file (`rollout-*.jsonl`).
+pub fn is_session_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("jsonl")
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with("rollout-"))
+}
+
+fn discover_in(root: &Path, max_depth: usize) -> Vec<CanonicalSession> {
+    WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_map(|e| walk_entry(Agent::Codex, e))
+        .filter(|e| {
+            let matched = is_session_file(e.path());
+            if !matched && e.path().extension().and_then(|x| x.to_str()) == Some("jsonl") {
+                note_skipped(Agent::Codex, e.path(), "not named rollout-*.jsonl");
+            }
+            matched
+        })
+        .filter_map(|e| match probe_session(e.path()) {
+            Ok(s) => Some(s),
+            Err(err) => {
+                note_skipped(Agent::Codex, e.path(), format!("unreadable: {:#}", err));
+                None
+            }
+        })
+        .collect()
+}
 
-    Ok(sessions)
+/// The first prompt the user typed, skipping injected context such as
+/// `<environment_context>` and `<user_instructions>`.
+pub fn first_prompt(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|r| r.get("type").and_then(|v| v.as_str()) == Some("response_item"))
+        .find_map(|r| {
+            let payload = r.get("payload")?;
+            let text = match payload.get("type").and_then(|v| v.as_str())? {
+                "user_message" => payload
+                    .get("content")
+                    .or_else(|| payload.get("text"))?
+                    .as_str()?
+                    .to_string(),
+                "message" if payload.get("role").and_then(|v| v.as_str()) == Some("user") => {
+                    payload
+                        .get("content")?
+                        .as_array()?
+                        .iter()
+                        .filter_map(|c| c.get("text")?.as_str())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }
+                _ => return None,
+            };
+            let text = text.trim();
+            (!text.is_empty() && !text.starts_with('<')).then(|| text.to_string())
+        })
 }
 
 fn probe_session(path: &Path) -> Result<CanonicalSession> {
     let content = std::fs::read_to_string(path)?;
     let mut session_id: Option<String> = None;
     let mut cwd: Option<String> = None;
+    let mut branch: Option<String> = None;
     let mut started_at: Option<DateTime<Utc>> = None;
     let mut model: Option<String> = None;
     let mut message_count = 0usize;
+    let mut looks_like_rollout = false;
+    let mut parse_errors = 0usize;
+    // Task lifecycle events: a task left open or aborted means the session was cut short
+    let mut task_open = false;
+    let mut ended_abnormally = false;
 
     for line in content.lines() {
         if line.trim().is_empty() {
@@ -61,11 +117,33 @@ fn probe_session(path: &Path) -> Result<CanonicalSession> {
         }
         let record: Value = match serde_json::from_str(line) {
             Ok(v) => v,
-            Err(_) => continue,
+            Err(_) => {
+                parse_errors += 1;
+                continue;
+            }
         };
+        // Modern records carry `type`; legacy rollouts open with an id/timestamp header
+        looks_like_rollout |= record.get("type").is_some()
+            || (record.get("id").is_some() && record.get("timestamp").is_some());
 
         let kind = record.get("type").and_then(|v| v.as_str()).unwrap_or("");
 
+        match record.pointer("/payload/type").and_then(|v| v.as_str()) {
+            Some("task_started") => {
+                task_open = true;
+                ended_abnormally = false;
+            }
+            Some("task_complete") => {
+                task_open = false;
+                ended_abnormally = false;
+            }
+            Some("turn_aborted") => {
+                task_open = false;
+                ended_abnormally = true;
+            }
+            _ => {}
+        }
+
         match kind {
             "session_meta" => {
                 let payload = record.get("payload").unwrap_or(&Value::Null);
@@ -83,6 +161,11 @@ fn probe_session(path: &Path) -> Result<CanonicalSession> {
                 if let Some(mp) = payload.get("model_provider").and_then(|v| v.as_str()) {
                     model = Some(mp.to_string());
                 }
+                branch = payload
+                    .pointer("/git/branch")
+                    .and_then(|v| v.as_str())
+                    .filter(|b| !b.is_empty())
+                    .map(|s| s.to_string());
             }
             "response_item" => {
                 let payload = record.get("payload").unwrap_or(&Value::Null);
@@ -106,11 +189,16 @@ fn probe_session(path: &Path) -> Result<CanonicalSession> {
         }
     }
 
+    if !looks_like_rollout {
+        anyhow::bail!("not a Codex rollout: {}", path.display());
+    }
+    let ended_abnormally = ended_abnormally || task_open;
+
     // Fallback: derive session_id from filename
     let session_id = session_id.unwrap_or_else(|| {
         path.file_stem()
             .and_then(|n| n.to_str())
-            .and_then(|n| n.split('-').last())
+            .and_then(|n| n.split('-').next_back())
             .unwrap_or("unknown")
             .to_string()
     });
@@ -128,6 +216,26 @@ fn probe_session(path: &Path) -> Result<CanonicalSession> {
         total_cost_usd: None,
         total_input_tokens: 0,
         total_output_tokens: 0,
+        auxiliary_cost_usd: None,
+        heuristic_turns: 0,
+        cost_breakdown: None,
+        user_count: 0,
+        assistant_count: 0,
+        system_count: 0,
+        total_cache_read_tokens: 0,
+        total_cache_write_tokens: 0,
+        total_reasoning_tokens: 0,
+        tags: branch
+            .map(|b| vec![format!("branch:{}", b)])
+            .unwrap_or_default(),
+        parse_errors,
+        system_prompt: None,
+        ended_abnormally,
+        tool_calls: 0,
+        tool_errors: 0,
+        efficiency_score: None,
+        pricing_confidence: None,
+        observed_cost_usd: None,
     })
 }
 
@@ -136,235 +244,327 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
         .with_context(|| format!("reading {}", session.source_path.display()))?;
 
     let mut messages = Vec::new();
-    let mut seq = 0usize;
+    let mut state = TailState::default();
+    for line in content.lines() {
+        parse_line(line, session, &mut messages, &mut state);
+    }
+    // Flush any remaining turn
+    if state.in_turn || !state.tool_calls.is_empty() {
+        flush_assistant_turn(
+            &mut messages,
+            &mut state.seq,
+            session,
+            &mut state.tool_calls,
+            &mut state.chars,
+            state.ts,
+        );
+    }
 
-    // We build a virtual "assistant turn" by accumulating function_call and output blocks
-    // between user messages. Codex doesn't have clean turn boundaries, so we group by
-    // "agent_message" / task_complete events.
+    Ok(ParsedSession {
+        session: session.clone(),
+        messages,
+    })
+}
 
-    let mut current_tool_calls: Vec<CanonicalTool> = Vec::new();
-    let mut pending_calls: HashMap<String, String> = HashMap::new(); // call_id -> tool_name
-    let mut current_ts: Option<DateTime<Utc>> = None;
-    let mut in_turn = false;
+/// Parse state carried between lines, and between calls to [`parse_tail`].
+///
+/// We build a virtual "assistant turn" by accumulating function_call and output blocks
+/// between user messages. Codex doesn't have clean turn boundaries, so we group by
+/// "agent_message" / task_complete events.
+#[derive(Debug, Default)]
+pub struct TailState {
+    /// Bytes consumed so far; always the end of a complete line
+    offset: u64,
+    seq: usize,
+    tool_calls: Vec<CanonicalTool>,
+    chars: usize,
+    pending_calls: HashMap<String, String>, // call_id -> tool_name
+    ts: Option<DateTime<Utc>>,
+    in_turn: bool,
+}
 
-    for line in content.lines() {
-        if line.trim().is_empty() {
-            continue;
-        }
-        let record: Value = match serde_json::from_str(line) {
-            Ok(v) => v,
-            Err(_) => continue,
-        };
+impl TailState {
+    /// The assistant turn still being accumulated, if any, as a message. It is
+    /// only final once a later record closes it, so incremental callers should
+    /// show it without keeping it.
+    pub fn open_turn(&self, session: &CanonicalSession) -> Option<CanonicalMessage> {
+        (self.in_turn || !self.tool_calls.is_empty()).then(|| {
+            assistant_turn(
+                session,
+                self.seq + 1,
+                self.tool_calls.clone(),
+                self.chars,
+                self.ts,
+            )
+        })
+    }
+}
 
-        let kind = record.get("type").and_then(|v| v.as_str()).unwrap_or("");
-        let ts: Option<DateTime<Utc>> = record
-            .get("timestamp")
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse().ok());
+/// Parse only the complete lines appended to the rollout since the last call,
+/// adding the turns they close to `messages`. A partly written last line is left
+/// for the next call, and the turn in progress stays in `state` (see
+/// [`TailState::open_turn`]). Returns the number of messages added. If the file
+/// shrank (rewritten or truncated), parsing starts over.
+pub fn parse_tail(
+    session: &CanonicalSession,
+    state: &mut TailState,
+    messages: &mut Vec<CanonicalMessage>,
+) -> Result<usize> {
+    use std::io::{Read, Seek, SeekFrom};
+    let path = &session.source_path;
+    let mut f = std::fs::File::open(path).with_context(|| format!("reading {}", path.display()))?;
+    if f.metadata()?.len() < state.offset {
+        *state = TailState::default();
+        messages.clear();
+    }
+    f.seek(SeekFrom::Start(state.offset))?;
+    let mut buf = Vec::new();
+    f.read_to_end(&mut buf)?;
+    let Some(end) = buf.iter().rposition(|&b| b == b'\n') else {
+        return Ok(0);
+    };
+    buf.truncate(end + 1);
+    state.offset += buf.len() as u64;
 
-        match kind {
-            "session_meta" => {
-                // Beginning of session — synthesize a system message
-            }
+    let before = messages.len();
+    for line in String::from_utf8_lossy(&buf).lines() {
+        parse_line(line, session, messages, state);
+    }
+    Ok(messages.len() - before)
+}
 
-            "response_item" => {
-                let payload = record.get("payload").unwrap_or(&Value::Null);
-                let ptype = payload.get("type").and_then(|v| v.as_str()).unwrap_or("");
+fn parse_line(
+    line: &str,
+    session: &CanonicalSession,
+    messages: &mut Vec<CanonicalMessage>,
+    state: &mut TailState,
+) {
+    if line.trim().is_empty() {
+        return;
+    }
+    let record: Value = match serde_json::from_str(line) {
+        Ok(v) => v,
+        Err(_) => return,
+    };
 
-                match ptype {
-                    "user_message" => {
-                        // Flush current assistant turn if any
-                        if in_turn {
-                            flush_assistant_turn(
-                                &mut messages,
-                                &mut seq,
-                                session,
-                                &mut current_tool_calls,
-                                current_ts,
-                            );
-                            in_turn = false;
-                        }
-                        // Add user message
-                        seq += 1;
-                        messages.push(CanonicalMessage {
-                            message_id: format!("user-{}", seq),
-                            session_id: session.session_id.clone(),
-                            parent_id: None,
-                            sequence: seq,
-                            role: Role::User,
-                            model: None,
-                            ts,
-                            usage: None,
-                            tool_calls: Vec::new(),
-                            is_sidechain: false,
-                            finish_reason: None,
-                        });
-                        in_turn = true;
-                        current_ts = ts;
+    let kind = record.get("type").and_then(|v| v.as_str()).unwrap_or("");
+    let ts: Option<DateTime<Utc>> = record
+        .get("timestamp")
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse().ok());
+
+    match kind {
+        "session_meta" => {
+            // Beginning of session — synthesize a system message
+        }
+
+        "response_item" => {
+            let payload = record.get("payload").unwrap_or(&Value::Null);
+            let ptype = payload.get("type").and_then(|v| v.as_str()).unwrap_or("");
+
+            match ptype {
+                "user_message" => {
+                    // Flush current assistant turn if any
+                    if state.in_turn {
+                        flush_assistant_turn(
+                            messages,
+                            &mut state.seq,
+                            session,
+                            &mut state.tool_calls,
+                            &mut state.chars,
+                            state.ts,
+                        );
                     }
+                    // Add user message
+                    let text = payload
+                        .get("content")
+                        .and_then(|v| v.as_str())
+                        .or_else(|| payload.get("text").and_then(|v| v.as_str()))
+                        .unwrap_or("");
+                    state.seq += 1;
+                    messages.push(CanonicalMessage {
+                        message_id: format!("user-{}", state.seq),
+                        session_id: session.session_id.clone(),
+                        parent_id: None,
+                        sequence: state.seq,
+                        role: Role::User,
+                        model: None,
+                        ts,
+                        usage: None,
+                        tool_calls: Vec::new(),
+                        is_sidechain: false,
+                        finish_reason: None,
+                        content_chars: text.chars().count(),
+                        prompt_lines: prompt_lines(text),
+                    });
+                    state.in_turn = true;
+                    state.ts = ts;
+                }
 
-                    "function_call" => {
-                        in_turn = true;
-                        if current_ts.is_none() {
-                            current_ts = ts;
-                        }
-                        let call_id = payload
-                            .get("call_id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let name = payload
-                            .get("name")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let args = payload
-                            .get("arguments")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("{}");
-                        let args_summary = extract_codex_args(args, &name);
-
-                        pending_calls.insert(call_id.clone(), name.clone());
-                        current_tool_calls.push(CanonicalTool {
-                            tool_name: name,
-                            call_id,
-                            status: ToolStatus::Unknown,
-                            error_class: None,
-                            error_message: None,
-                            args_summary,
-                            output_summary: None,
-                            duration_ms: None,
-                        });
+                "function_call" => {
+                    state.in_turn = true;
+                    if state.ts.is_none() {
+                        state.ts = ts;
                     }
+                    let call_id = payload
+                        .get("call_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    let name = payload
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    let args = payload
+                        .get("arguments")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("{}");
+                    let args_summary = extract_codex_args(args, &name);
+                    state.chars += args.chars().count();
+
+                    state.pending_calls.insert(call_id.clone(), name.clone());
+                    state.tool_calls.push(CanonicalTool {
+                        category: canonical_tool_category(&name),
+                        tool_name: name,
+                        call_id,
+                        status: ToolStatus::Unknown,
+                        error_class: None,
+                        error_message: None,
+                        args_summary,
+                        output_summary: None,
+                        output_bytes: None,
+                        output_hash: None,
+                        duration_ms: None,
+                    });
+                }
 
-                    "function_call_output" => {
-                        let call_id = payload
-                            .get("call_id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
-                        let output = payload.get("output").and_then(|v| v.as_str()).unwrap_or("");
-
-                        let is_error = output_looks_like_error(output);
-                        let status = if is_error {
-                            ToolStatus::Error
-                        } else {
-                            ToolStatus::Success
-                        };
-
-                        // Update the pending tool call
-                        for tool in current_tool_calls.iter_mut() {
-                            if tool.call_id == call_id {
-                                tool.status = status;
-                                if is_error {
-                                    tool.error_class = Some("exec_error".to_string());
-                                    tool.error_message = Some(output.chars().take(200).collect());
-                                } else {
-                                    tool.output_summary = Some(output.chars().take(100).collect());
-                                }
-                                break;
+                "function_call_output" => {
+                    let call_id = payload
+                        .get("call_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    let output = payload.get("output").and_then(|v| v.as_str()).unwrap_or("");
+
+                    let is_error = output_looks_like_error(output);
+                    let status = if is_error {
+                        ToolStatus::Error
+                    } else {
+                        ToolStatus::Success
+                    };
+
+                    // Update the pending tool call
+                    for tool in state.tool_calls.iter_mut() {
+                        if tool.call_id == call_id {
+                            tool.status = status;
+                            tool.output_bytes = Some(output.len() as u64);
+                            tool.output_hash = Some(content_hash(output));
+                            if is_error {
+                                tool.error_class =
+                                    Some(classify_tool_error(tool, output, "exec_error"));
+                                tool.error_message = Some(output.chars().take(200).collect());
+                            } else {
+                                tool.output_summary = Some(output.chars().take(100).collect());
                             }
+                            break;
                         }
                     }
+                }
 
-                    "agent_message" | "task_complete" => {
-                        // End of this assistant turn
-                        if in_turn || !current_tool_calls.is_empty() {
-                            flush_assistant_turn(
-                                &mut messages,
-                                &mut seq,
-                                session,
-                                &mut current_tool_calls,
-                                current_ts,
-                            );
-                            in_turn = false;
-                            current_ts = None;
-                        }
-                    }
+                "agent_message" | "task_complete"
+                    if state.in_turn || !state.tool_calls.is_empty() =>
+                {
+                    // End of this assistant turn
+                    flush_assistant_turn(
+                        messages,
+                        &mut state.seq,
+                        session,
+                        &mut state.tool_calls,
+                        &mut state.chars,
+                        state.ts,
+                    );
+                    state.in_turn = false;
+                    state.ts = None;
+                }
 
-                    "custom_tool_call" => {
-                        // Similar to function_call
-                        in_turn = true;
-                        let call_id = payload
-                            .get("call_id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("")
-                            .to_string();
-                        let name = payload
-                            .get("name")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("custom_tool")
-                            .to_string();
-                        pending_calls.insert(call_id.clone(), name.clone());
-                        current_tool_calls.push(CanonicalTool {
-                            tool_name: name,
-                            call_id,
-                            status: ToolStatus::Unknown,
-                            error_class: None,
-                            error_message: None,
-                            args_summary: None,
-                            output_summary: None,
-                            duration_ms: None,
-                        });
-                    }
+                "custom_tool_call" => {
+                    // Similar to function_call
+                    state.in_turn = true;
+                    let call_id = payload
+                        .get("call_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("")
+                        .to_string();
+                    let name = payload
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("custom_tool")
+                        .to_string();
+                    state.chars += payload
+                        .get("input")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.chars().count())
+                        .unwrap_or(0);
+                    state.pending_calls.insert(call_id.clone(), name.clone());
+                    state.tool_calls.push(CanonicalTool {
+                        category: canonical_tool_category(&name),
+                        tool_name: name,
+                        call_id,
+                        status: ToolStatus::Unknown,
+                        error_class: None,
+                        error_message: None,
+                        args_summary: None,
+                        output_summary: None,
+                        output_bytes: None,
+                        output_hash: None,
+                        duration_ms: None,
+                    });
+                }
 
-                    "custom_tool_call_output" => {
-                        let call_id = payload
-                            .get("call_id")
-                            .and_then(|v| v.as_str())
-                            .unwrap_or("");
-                        let output = payload
-                            .get("output")
-                            .and_then(|v| {
-                                v.as_str()
-                                    .map(|s| s.to_string())
-                                    .or_else(|| serde_json::to_string(v).ok())
-                            })
-                            .unwrap_or_default();
-                        let is_error = output_looks_like_error(&output);
-
-                        for tool in current_tool_calls.iter_mut() {
-                            if tool.call_id == call_id {
-                                tool.status = if is_error {
-                                    ToolStatus::Error
-                                } else {
-                                    ToolStatus::Success
-                                };
-                                if is_error {
-                                    tool.error_class = Some("exec_error".to_string());
-                                    tool.error_message = Some(output.chars().take(200).collect());
-                                }
-                                break;
+                "custom_tool_call_output" => {
+                    let call_id = payload
+                        .get("call_id")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or("");
+                    let output = payload
+                        .get("output")
+                        .and_then(|v| {
+                            v.as_str()
+                                .map(|s| s.to_string())
+                                .or_else(|| serde_json::to_string(v).ok())
+                        })
+                        .unwrap_or_default();
+                    let is_error = output_looks_like_error(&output);
+
+                    for tool in state.tool_calls.iter_mut() {
+                        if tool.call_id == call_id {
+                            tool.status = if is_error {
+                                ToolStatus::Error
+                            } else {
+                                ToolStatus::Success
+                            };
+                            tool.output_bytes = Some(output.len() as u64);
+                            tool.output_hash = Some(content_hash(&output));
+                            if is_error {
+                                tool.error_class =
+                                    Some(classify_tool_error(tool, &output, "exec_error"));
+                                tool.error_message = Some(output.chars().take(200).collect());
                             }
+                            break;
                         }
                     }
-
-                    _ => {}
                 }
-            }
 
-            "event_msg" => {
-                // token_count, workspace-write, etc. — not much useful per-call data here
+                _ => {}
             }
+        }
 
-            _ => {}
+        "event_msg" => {
+            // token_count, workspace-write, etc. — not much useful per-call data here
         }
-    }
 
-    // Flush any remaining turn
-    if in_turn || !current_tool_calls.is_empty() {
-        flush_assistant_turn(
-            &mut messages,
-            &mut seq,
-            session,
-            &mut current_tool_calls,
-            current_ts,
-        );
+        _ => {}
     }
-
-    Ok(ParsedSession {
-        session: session.clone(),
-        messages,
-    })
 }
 
 fn flush_assistant_turn(
@@ -372,22 +572,41 @@ fn flush_assistant_turn(
     seq: &mut usize,
     session: &CanonicalSession,
     tool_calls: &mut Vec<CanonicalTool>,
+    content_chars: &mut usize,
     ts: Option<DateTime<Utc>>,
 ) {
     *seq += 1;
-    messages.push(CanonicalMessage {
-        message_id: format!("asst-{}", *seq),
+    messages.push(assistant_turn(
+        session,
+        *seq,
+        std::mem::take(tool_calls),
+        std::mem::take(content_chars),
+        ts,
+    ));
+}
+
+fn assistant_turn(
+    session: &CanonicalSession,
+    seq: usize,
+    tool_calls: Vec<CanonicalTool>,
+    content_chars: usize,
+    ts: Option<DateTime<Utc>>,
+) -> CanonicalMessage {
+    CanonicalMessage {
+        message_id: format!("asst-{}", seq),
         session_id: session.session_id.clone(),
         parent_id: None,
-        sequence: *seq,
+        sequence: seq,
         role: Role::Assistant,
         model: session.model.clone(),
         ts,
         usage: None, // Codex rollout files don't include per-call token counts
-        tool_calls: std::mem::take(tool_calls),
+        tool_calls,
         is_sidechain: false,
         finish_reason: None,
-    });
+        content_chars,
+        prompt_lines: Vec::new(),
+    }
 }
 
 fn extract_codex_args(args_json: &str, tool_name: &str) -> Option<String> {
@@ -431,3 +650,134 @@ fn output_looks_like_error(output: &str) -> bool {
         || lower.contains("no such file or directory")
         || (lower.contains("process exited with code") && !lower.contains("code 0"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A throwaway `sessions/` tree.
+    struct Sessions {
+        root: PathBuf,
+    }
+
+    impl Sessions {
+        fn new() -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let root = std::env::temp_dir()
+                .join(format!(
+                    "tracekit-codex-{}-{}",
+                    std::process::id(),
+                    NEXT.fetch_add(1, Ordering::Relaxed)
+                ))
+                .join("sessions");
+            Self { root }
+        }
+
+        fn write(&self, rel: &str, records: &[Value]) -> PathBuf {
+            let path = self.root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            let lines: Vec<String> = records.iter().map(|r| r.to_string()).collect();
+            std::fs::write(&path, lines.join("\n") + "\n").unwrap();
+            path
+        }
+    }
+
+    impl Drop for Sessions {
+        fn drop(&mut self) {
+            if let Some(dir) = self.root.parent() {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    fn meta(id: &str) -> Value {
+        json!({
+            "timestamp": "2026-01-01T00:00:00Z",
+            "type": "session_meta",
+            "payload": {"id": id, "cwd": "/work/proj", "timestamp": "2026-01-01T00:00:00Z"}
+        })
+    }
+
+    #[test]
+    fn rollouts_are_found_at_non_standard_depths() {
+        let sessions = Sessions::new();
+        sessions.write("2026/01/rollout-shallow.jsonl", &[meta("shallow")]);
+        sessions.write("archive/2026/01/01/rollout-deep.jsonl", &[meta("deep")]);
+        // Named like a rollout but not one, and a rollout without the prefix
+        sessions.write("notes/rollout-notes.jsonl", &[json!({"note": "todo"})]);
+        sessions.write("2026/01/01/session.jsonl", &[meta("unnamed")]);
+
+        let mut ids: Vec<String> = discover_in(&sessions.root, 6)
+            .into_iter()
+            .map(|s| s.session_id)
+            .collect();
+        ids.sort();
+        assert_eq!(ids, ["deep", "shallow"]);
+    }
+
+    fn item(secs: u32, payload: Value) -> Value {
+        json!({
+            "timestamp": format!("2026-01-01T00:00:{:02}Z", secs),
+            "type": "response_item",
+            "payload": payload
+        })
+    }
+
+    #[test]
+    fn tail_parsing_in_chunks_matches_a_full_parse() {
+        let call = |id: &str, cmd: &str| {
+            json!({"type": "function_call", "call_id": id, "name": "shell",
+                   "arguments": json!({"command": ["bash", "-lc", cmd]}).to_string()})
+        };
+        let output = |id: &str, out: &str| json!({"type": "function_call_output", "call_id": id, "output": out});
+        let records = [
+            meta("tail"),
+            item(
+                1,
+                json!({"type": "user_message", "content": "run the tests, café"}),
+            ),
+            item(2, call("call_1", "cargo test")),
+            item(3, call("call_2", "cat missing.rs")),
+            item(
+                4,
+                output("call_2", "cat: missing.rs: No such file or directory"),
+            ),
+            item(5, output("call_1", "test result: ok. 3 passed")),
+            item(6, json!({"type": "agent_message", "message": "All green."})),
+            item(7, json!({"type": "user_message", "content": "now lint"})),
+            item(8, call("call_3", "cargo clippy")),
+            item(9, output("call_3", "error: unused variable")),
+            // The file ends mid-turn, with no closing agent_message
+        ];
+        let sessions = Sessions::new();
+        let path = sessions.write("2026/01/01/rollout-tail.jsonl", &records);
+        let content = std::fs::read(&path).unwrap();
+        let session = probe_session(&path).unwrap();
+        let as_json = |msgs: &[CanonicalMessage]| serde_json::to_value(msgs).unwrap();
+        let full = parse_session(&session).unwrap().messages;
+        assert_eq!(full.len(), 4);
+
+        for step in [1, 7, 64, 333, content.len()] {
+            std::fs::write(&path, b"").unwrap();
+            let mut state = TailState::default();
+            let mut messages = Vec::new();
+            let mut cut = 0;
+            while cut < content.len() {
+                cut = (cut + step).min(content.len());
+                std::fs::write(&path, &content[..cut]).unwrap();
+                parse_tail(&session, &mut state, &mut messages).unwrap();
+            }
+            messages.extend(state.open_turn(&session));
+            assert_eq!(as_json(&messages), as_json(&full), "step {}", step);
+        }
+
+        // Tool results pair with their calls even when they arrive out of order
+        let first_turn = &full[1].tool_calls;
+        assert_eq!(first_turn[0].status, ToolStatus::Success);
+        assert_eq!(first_turn[1].status, ToolStatus::Error);
+        assert_eq!(full[3].tool_calls[0].status, ToolStatus::Error);
+    }
+}
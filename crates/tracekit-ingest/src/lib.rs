@@ -1,18 +1,44 @@
+pub mod cache;
 pub mod claude;
 pub mod codex;
 pub mod opencode;
+pub mod tags;
 
 use anyhow::Result;
-use std::path::PathBuf;
-use tracekit_core::{Agent, CanonicalSession, ParsedSession};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracekit_core::{
+    collapse_home, repo_tag, Agent, AnalysisResult, CanonicalSession, ParsedSession,
+};
+
+/// `--min-messages` default: single-message sessions are usually aborted starts.
+pub const DEFAULT_MIN_MESSAGES: usize = 2;
+
+/// Which discovered sessions to keep, and how many. The default keeps every
+/// session.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryFilter {
+    /// Keep sessions started at or after this time (or with no start time)
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    /// Keep sessions started at or before this time (or with no start time)
+    pub until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Keep sessions whose CWD contains this string
+    pub cwd: Option<String>,
+    /// Infer a CWD for sessions that don't record one before matching `cwd`
+    pub assume_cwd: Option<CwdSource>,
+    /// Skip sessions with fewer messages than this
+    pub min_messages: usize,
+    /// Keep at most this many sessions, newest first
+    pub limit: Option<usize>,
+}
 
 /// Discover all sessions for the given agent(s).
+///
+/// Results are ordered newest first, with ties (equal or missing start times)
+/// broken by session id and then source path, so `limit` is stable across runs.
 pub fn discover_sessions(
     agents: &[Agent],
-    since: Option<chrono::DateTime<chrono::Utc>>,
-    until: Option<chrono::DateTime<chrono::Utc>>,
-    cwd_filter: Option<&str>,
-    limit: Option<usize>,
+    filter: &DiscoveryFilter,
 ) -> Result<Vec<CanonicalSession>> {
     let mut sessions = Vec::new();
 
@@ -27,30 +53,187 @@ pub fn discover_sessions(
         sessions.extend(found);
     }
 
+    Ok(filter_sessions(sessions, filter))
+}
+
+/// A file or directory discovery passed over, and why (`--explain-discovery`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub agent: Agent,
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// Skips recorded so far; `None` unless `--explain-discovery` turned recording on.
+static SKIPPED: Mutex<Option<Vec<SkippedFile>>> = Mutex::new(None);
+
+/// Turn recording of skipped files on or off. Off by default, so normal runs
+/// keep nothing.
+pub fn set_explain_discovery(enabled: bool) {
+    *SKIPPED.lock().unwrap_or_else(|e| e.into_inner()) = enabled.then(Vec::new);
+}
+
+/// Record that discovery skipped `path`, when recording is on.
+pub(crate) fn note_skipped(agent: Agent, path: &Path, reason: impl std::fmt::Display) {
+    if let Some(skipped) = SKIPPED.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        let entry = SkippedFile {
+            agent,
+            path: path.to_path_buf(),
+            reason: reason.to_string(),
+        };
+        // Commands may discover more than once (e.g. to resolve an id, then to report)
+        if !skipped.contains(&entry) {
+            skipped.push(entry);
+        }
+    }
+}
+
+/// A directory-walk entry, recording ones that couldn't be read as skipped.
+pub(crate) fn walk_entry(
+    agent: Agent,
+    entry: walkdir::Result<walkdir::DirEntry>,
+) -> Option<walkdir::DirEntry> {
+    match entry {
+        Ok(e) => Some(e),
+        Err(err) => {
+            if let Some(path) = err.path() {
+                note_skipped(agent, path, format!("unreadable: {}", err));
+            }
+            None
+        }
+    }
+}
+
+/// Take the skips recorded so far, in the order they happened.
+pub fn take_skipped() -> Vec<SkippedFile> {
+    SKIPPED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .map(std::mem::take)
+        .unwrap_or_default()
+}
+
+/// Recursively discover sessions for the given agent(s) under an arbitrary
+/// directory, such as a shared folder of exported traces. Files are matched with
+/// each adapter's `is_session_file`; pass the result through [`filter_sessions`].
+pub fn discover_sessions_in(dir: &Path, agents: &[Agent]) -> Vec<CanonicalSession> {
+    let mut sessions = Vec::new();
+    for agent in agents {
+        let found = match agent {
+            Agent::Claude => claude::discover_sessions_under(dir),
+            Agent::Opencode => opencode::discover_sessions_under(dir),
+            Agent::Codex => codex::discover_sessions_under(dir),
+            Agent::Pi | Agent::Kodo => Vec::new(),
+        };
+        sessions.extend(found);
+    }
+    sessions
+}
+
+/// Tag, filter, order and truncate discovered sessions; see [`discover_sessions`].
+pub fn filter_sessions(
+    mut sessions: Vec<CanonicalSession>,
+    filter: &DiscoveryFilter,
+) -> Vec<CanonicalSession> {
+    if let Some(from) = filter.assume_cwd {
+        for s in sessions.iter_mut().filter(|s| s.cwd.is_none()) {
+            s.cwd = infer_cwd(s, from);
+        }
+    }
+    for s in &mut sessions {
+        if let Some(tag) = s.cwd.as_deref().and_then(repo_tag) {
+            s.tags.insert(0, tag);
+        }
+    }
+    match tags::TagStore::load() {
+        Ok(store) => store.apply(&mut sessions),
+        Err(e) => eprintln!("warn: ignoring session tags: {:#}", e),
+    }
+
     // Apply filters
-    if let Some(since) = since {
-        sessions.retain(|s| s.started_at.map(|t| t >= since).unwrap_or(true));
+    let mut retain = |keep: &dyn Fn(&CanonicalSession) -> bool, reason: &str| {
+        sessions.retain(|s| {
+            let kept = keep(s);
+            if !kept {
+                note_skipped(s.source_agent, &s.source_path, reason);
+            }
+            kept
+        })
+    };
+    if let Some(since) = filter.since {
+        retain(
+            &|s| s.started_at.map(|t| t >= since).unwrap_or(true),
+            "filtered: started before --since",
+        );
     }
-    if let Some(until) = until {
-        sessions.retain(|s| s.started_at.map(|t| t <= until).unwrap_or(true));
+    if let Some(until) = filter.until {
+        retain(
+            &|s| s.started_at.map(|t| t <= until).unwrap_or(true),
+            "filtered: started after --until",
+        );
     }
-    if let Some(cwd) = cwd_filter {
-        sessions.retain(|s| s.cwd.as_deref().map(|c| c.contains(cwd)).unwrap_or(false));
+    if let Some(cwd) = filter.cwd.as_deref() {
+        retain(
+            &|s| s.cwd.as_deref().map(|c| c.contains(cwd)).unwrap_or(false),
+            "filtered: CWD doesn't match --cwd",
+        );
     }
+    let too_short = format!(
+        "filtered: fewer than {} messages (--min-messages)",
+        filter.min_messages
+    );
+    retain(&|s| s.message_count >= filter.min_messages, &too_short);
 
-    // Sort newest first
-    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    // Sort newest first; break ties (including missing timestamps) by id and path so
+    // the order doesn't depend on directory walk or HashMap iteration order
+    sessions.sort_by(|a, b| {
+        b.started_at
+            .cmp(&a.started_at)
+            .then_with(|| a.session_id.cmp(&b.session_id))
+            .then_with(|| a.source_path.cmp(&b.source_path))
+    });
 
-    if let Some(n) = limit {
+    if let Some(n) = filter.limit {
         sessions.truncate(n);
     }
 
-    Ok(sessions)
+    sessions
+}
+
+/// Keep a random `n` of `sessions` (all of them if there are no more than `n`),
+/// in their original order. The choice depends only on `seed` and the input
+/// order, which [`filter_sessions`] makes stable, so a seed reproduces a sample.
+pub fn sample_sessions(sessions: &mut Vec<CanonicalSession>, n: usize, seed: u64) {
+    if sessions.len() <= n {
+        return;
+    }
+    // splitmix64: tiny, seedable and the same on every platform
+    let mut state = seed;
+    let mut next = || {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    // Partial Fisher-Yates: the first n slots end up a uniform sample
+    let mut picks: Vec<usize> = (0..sessions.len()).collect();
+    for i in 0..n {
+        let j = i + (next() % (picks.len() - i) as u64) as usize;
+        picks.swap(i, j);
+    }
+    let mut keep = vec![false; sessions.len()];
+    for &i in &picks[..n] {
+        keep[i] = true;
+    }
+    let mut kept = keep.into_iter();
+    sessions.retain(|_| kept.next().unwrap_or(false));
 }
 
 /// Find a specific session by ID across all agents.
 pub fn find_session(session_id: &str, agents: &[Agent]) -> Result<Option<CanonicalSession>> {
-    let sessions = discover_sessions(agents, None, None, None, None)?;
+    let sessions = discover_sessions(agents, &DiscoveryFilter::default())?;
     Ok(sessions
         .into_iter()
         .find(|s| s.session_id.starts_with(session_id)))
@@ -71,34 +254,466 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
     Ok(parsed)
 }
 
+/// Incremental parser for a session that is still being written. Each
+/// [`SessionTail::poll`] reads only what was appended since the last one, so
+/// refreshing a huge, growing trace stays cheap. Claude and Codex only; their
+/// traces are append-only JSONL.
+pub struct SessionTail {
+    session: CanonicalSession,
+    messages: Vec<tracekit_core::CanonicalMessage>,
+    state: TailState,
+}
+
+enum TailState {
+    Claude(claude::TailState),
+    Codex(codex::TailState),
+}
+
+impl SessionTail {
+    /// `None` for agents whose traces can't be tailed.
+    pub fn new(session: &CanonicalSession) -> Option<Self> {
+        let state = match session.source_agent {
+            Agent::Claude => TailState::Claude(Default::default()),
+            Agent::Codex => TailState::Codex(Default::default()),
+            _ => return None,
+        };
+        Some(Self {
+            session: session.clone(),
+            messages: Vec::new(),
+            state,
+        })
+    }
+
+    /// Read newly appended records; returns how many messages they added.
+    pub fn poll(&mut self) -> Result<usize> {
+        match &mut self.state {
+            TailState::Claude(state) => {
+                claude::parse_tail(&self.session, state, &mut self.messages)
+            }
+            TailState::Codex(state) => codex::parse_tail(&self.session, state, &mut self.messages),
+        }
+    }
+
+    /// The session as parsed so far, totals computed, as [`parse_session`]
+    /// would return it (Codex's unfinished turn included).
+    pub fn parsed(&self) -> ParsedSession {
+        let mut session = self.session.clone();
+        let mut messages = self.messages.clone();
+        match &self.state {
+            TailState::Claude(state) => session.parse_errors = state.parse_errors(),
+            TailState::Codex(state) => messages.extend(state.open_turn(&self.session)),
+        }
+        let mut parsed = ParsedSession { session, messages };
+        parsed.compute_totals();
+        parsed
+    }
+}
+
 /// Resolve the default root path for an agent.
 pub fn default_root(agent: Agent) -> Option<PathBuf> {
-    let home = dirs_next();
+    let home = dirs::home_dir();
     match agent {
-        Agent::Claude => home.map(|h| h.join(".claude").join("projects")),
-        Agent::Opencode => home.map(|h| {
-            h.join(".local")
-                .join("share")
-                .join("opencode")
-                .join("storage")
-        }),
+        Agent::Claude => claude_root(
+            std::env::var_os("CLAUDE_CONFIG_DIR")
+                .filter(|d| !d.is_empty())
+                .map(PathBuf::from),
+            home,
+        ),
+        Agent::Opencode => opencode_root(dirs::data_dir(), home),
         Agent::Codex => home.map(|h| h.join(".codex").join("sessions")),
         Agent::Pi => home.map(|h| h.join(".pi").join("agent").join("sessions")),
         Agent::Kodo => home.map(|h| h.join(".kodo").join("sessions")),
     }
 }
 
-fn dirs_next() -> Option<PathBuf> {
-    std::env::var("HOME").ok().map(PathBuf::from)
+/// Claude Code may keep its config in `$CLAUDE_CONFIG_DIR`, `~/.config/claude` or
+/// `~/.claude`; use the first projects dir that exists, defaulting to `~/.claude`.
+fn claude_root(config_dir: Option<PathBuf>, home: Option<PathBuf>) -> Option<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(dir) = config_dir {
+        candidates.push(dir.join("projects"));
+    }
+    if let Some(h) = &home {
+        candidates.push(h.join(".config").join("claude").join("projects"));
+    }
+    candidates
+        .into_iter()
+        .find(|p| p.exists())
+        .or_else(|| home.map(|h| h.join(".claude").join("projects")))
+}
+
+/// OpenCode keeps its storage under the platform data directory
+/// (`$XDG_DATA_HOME`, `%APPDATA%`); on macOS it still uses `~/.local/share`,
+/// so fall back to that when the data-dir copy doesn't exist.
+fn opencode_root(data_dir: Option<PathBuf>, home: Option<PathBuf>) -> Option<PathBuf> {
+    let storage = |d: PathBuf| d.join("opencode").join("storage");
+    let primary = data_dir.map(storage);
+    if primary.as_ref().is_some_and(|p| p.exists()) {
+        return primary;
+    }
+    home.map(|h| storage(h.join(".local").join("share")))
+        .filter(|p| p.exists())
+        .or(primary)
+}
+
+/// Longest title synthesized from a prompt, in characters.
+const PROMPT_TITLE_MAX: usize = 60;
+
+/// Give untitled sessions a title from the first line of their first user prompt
+/// (Claude, Codex). Sessions the agent already titled (OpenCode) are left alone.
+pub fn title_from_prompt(sessions: &mut [CanonicalSession]) {
+    for s in sessions.iter_mut().filter(|s| s.title.is_none()) {
+        let prompt = match s.source_agent {
+            Agent::Claude => claude::first_prompt(&s.source_path),
+            Agent::Codex => codex::first_prompt(&s.source_path),
+            _ => None,
+        };
+        s.title = prompt.and_then(|p| {
+            let line = p.lines().map(str::trim).find(|l| !l.is_empty())?;
+            Some(if line.chars().count() <= PROMPT_TITLE_MAX {
+                line.to_string()
+            } else {
+                let head: String = line.chars().take(PROMPT_TITLE_MAX - 1).collect();
+                format!("{}…", head.trim_end())
+            })
+        });
+    }
+}
+
+/// Where to infer a working directory for sessions that don't record one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CwdSource {
+    /// The project directory encoded in the session's storage path (Claude)
+    Path,
+    /// The nearest ancestor holding a `.git`, starting from the path-inferred
+    /// project directory, then from the session file itself
+    Git,
+}
+
+/// Infer a session's working directory; `None` when nothing can be inferred.
+pub fn infer_cwd(session: &CanonicalSession, from: CwdSource) -> Option<String> {
+    let project = match session.source_agent {
+        Agent::Claude => claude::project_dir_from_path(&session.source_path),
+        _ => None,
+    };
+    let dir = match from {
+        CwdSource::Path => project,
+        CwdSource::Git => project
+            .iter()
+            .map(PathBuf::as_path)
+            .chain(session.source_path.parent())
+            .find_map(git_root),
+    }?;
+    Some(dir.to_string_lossy().to_string())
+}
+
+fn git_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
 }
 
 /// Shorten a path for display purposes
 pub fn short_path(path: &std::path::Path) -> String {
-    let home = std::env::var("HOME").unwrap_or_default();
-    let s = path.to_string_lossy();
-    if !home.is_empty() && s.starts_with(&home) {
-        format!("~{}", &s[home.len()..])
-    } else {
-        s.to_string()
+    collapse_home(&path.to_string_lossy())
+}
+
+/// Home directory as a string (`$HOME` on Unix, the user profile on Windows)
+fn home_string() -> String {
+    dirs::home_dir()
+        .map(|h| h.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// How much of a filesystem path to keep in rendered output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathRedaction {
+    /// Collapse the home directory to `~`
+    Home,
+    /// Keep only the final path component
+    Full,
+}
+
+impl std::str::FromStr for PathRedaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "home" => Ok(Self::Home),
+            "full" => Ok(Self::Full),
+            other => Err(format!(
+                "invalid path redaction '{}' (expected home or full)",
+                other
+            )),
+        }
+    }
+}
+
+/// Redact a single path string.
+pub fn redact_path(path: &str, mode: PathRedaction) -> String {
+    match mode {
+        PathRedaction::Home => collapse_home(path),
+        PathRedaction::Full => {
+            let name = std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            if name.is_empty() {
+                "…".to_string()
+            } else {
+                format!("…/{}", name)
+            }
+        }
+    }
+}
+
+/// Redact every path field on a session (source path and cwd).
+pub fn redact_session_paths(session: &mut CanonicalSession, mode: PathRedaction) {
+    let source = redact_path(&session.source_path.to_string_lossy(), mode);
+    session.source_path = PathBuf::from(source);
+    if let Some(cwd) = session.cwd.take() {
+        session.cwd = Some(redact_path(&cwd, mode));
+    }
+}
+
+/// Redact paths on an analysis result, including free-text finding descriptions
+/// and evidence (which may embed file paths from tool arguments).
+pub fn redact_analysis_paths(result: &mut AnalysisResult, mode: PathRedaction) {
+    let home = home_string();
+    if let Some(cwd) = result.session.cwd.clone().filter(|c| !c.is_empty()) {
+        // Scrub the project dir before the home dir so `Full` hides the whole prefix
+        let replacement = redact_path(&cwd, mode);
+        for f in &mut result.findings {
+            f.description = f.description.replace(&cwd, &replacement);
+            for ev in &mut f.evidence {
+                *ev = ev.replace(&cwd, &replacement);
+            }
+        }
+    }
+    if !home.is_empty() {
+        for f in &mut result.findings {
+            f.description = f.description.replace(&home, "~");
+            for ev in &mut f.evidence {
+                *ev = ev.replace(&home, "~");
+            }
+        }
+    }
+    redact_session_paths(&mut result.session, mode);
+}
+
+/// Replace each session ID with a short alias (`S-001`, `S-002`, ... in result
+/// order) everywhere it appears in the results, including trace paths and
+/// finding text, so a shared report stays internally consistent without
+/// revealing real IDs. Returns the `(alias, real id)` mapping.
+pub fn pseudonymize_results(results: &mut [AnalysisResult]) -> Vec<(String, String)> {
+    let mut mapping: Vec<(String, String)> = Vec::new();
+    for r in results.iter() {
+        let id = &r.session.session_id;
+        if !id.is_empty() && !mapping.iter().any(|(_, real)| real == id) {
+            mapping.push((format!("S-{:03}", mapping.len() + 1), id.clone()));
+        }
+    }
+    // Longest IDs first, so an ID that prefixes another can't clobber part of it
+    let mut by_len: Vec<&(String, String)> = mapping.iter().collect();
+    by_len.sort_by_key(|(_, real)| std::cmp::Reverse(real.len()));
+    let scrub = |text: &str| {
+        by_len
+            .iter()
+            .fold(text.to_string(), |t, (alias, real)| t.replace(real, alias))
+    };
+
+    for r in results.iter_mut() {
+        r.session.session_id = scrub(&r.session.session_id);
+        r.session.source_path = PathBuf::from(scrub(&r.session.source_path.to_string_lossy()));
+        for f in &mut r.findings {
+            f.description = scrub(&f.description);
+            for ev in &mut f.evidence {
+                *ev = scrub(ev);
+            }
+        }
+        for m in &mut r.top_expensive_messages {
+            m.message_id = scrub(&m.message_id);
+        }
+    }
+    mapping
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracekit_core::{Finding, FindingKind};
+
+    fn session(id: usize) -> CanonicalSession {
+        CanonicalSession {
+            session_id: format!("s{}", id),
+            source_agent: Agent::Claude,
+            source_path: PathBuf::from(format!("/tmp/s{}.jsonl", id)),
+            cwd: None,
+            title: None,
+            started_at: None,
+            ended_at: None,
+            model: None,
+            message_count: 2,
+            total_cost_usd: None,
+            total_input_tokens: 0,
+            total_output_tokens: 0,
+            auxiliary_cost_usd: None,
+            heuristic_turns: 0,
+            cost_breakdown: None,
+            user_count: 0,
+            assistant_count: 0,
+            system_count: 0,
+            total_cache_read_tokens: 0,
+            total_cache_write_tokens: 0,
+            total_reasoning_tokens: 0,
+            tags: Vec::new(),
+            parse_errors: 0,
+            system_prompt: None,
+            ended_abnormally: false,
+            tool_calls: 0,
+            tool_errors: 0,
+            efficiency_score: None,
+            pricing_confidence: None,
+            observed_cost_usd: None,
+        }
+    }
+
+    fn sampled_ids(n: usize, seed: u64) -> Vec<String> {
+        let mut sessions: Vec<CanonicalSession> = (0..100).map(session).collect();
+        sample_sessions(&mut sessions, n, seed);
+        sessions.into_iter().map(|s| s.session_id).collect()
+    }
+
+    #[test]
+    fn sampling_with_a_seed_is_reproducible() {
+        let first = sampled_ids(10, 42);
+        assert_eq!(first.len(), 10);
+        assert_eq!(first, sampled_ids(10, 42));
+        assert_ne!(first, sampled_ids(10, 43));
+
+        // Picks keep their original relative order
+        let positions: Vec<usize> = first.iter().map(|id| id[1..].parse().unwrap()).collect();
+        assert!(positions.windows(2).all(|w| w[0] < w[1]));
+
+        assert_eq!(sampled_ids(500, 42).len(), 100);
+    }
+
+    #[test]
+    fn equal_start_times_order_by_id_then_path() {
+        let at = |id: usize, secs: Option<i64>| {
+            let mut s = session(id);
+            s.started_at = secs.and_then(|t| chrono::DateTime::from_timestamp(t, 0));
+            s
+        };
+        let mut twin = at(2, Some(100));
+        twin.source_path = PathBuf::from("/tmp/a/s2.jsonl");
+        let sessions = vec![
+            at(3, None),
+            at(2, Some(100)),
+            at(1, None),
+            twin,
+            at(4, Some(200)),
+            at(1, Some(100)),
+        ];
+
+        let order = |mut sessions: Vec<CanonicalSession>| -> Vec<String> {
+            sessions.reverse();
+            filter_sessions(sessions, &DiscoveryFilter::default())
+                .into_iter()
+                .map(|s| format!("{}:{}", s.session_id, s.source_path.display()))
+                .collect()
+        };
+        let expected = vec![
+            "s4:/tmp/s4.jsonl",
+            "s1:/tmp/s1.jsonl",
+            "s2:/tmp/a/s2.jsonl",
+            "s2:/tmp/s2.jsonl",
+            "s1:/tmp/s1.jsonl",
+            "s3:/tmp/s3.jsonl",
+        ];
+        assert_eq!(order(sessions.clone()), expected);
+        // Input order doesn't matter
+        let mut shuffled = sessions;
+        shuffled.rotate_left(2);
+        assert_eq!(order(shuffled), expected);
+    }
+
+    #[test]
+    fn single_message_sessions_need_a_lower_min_messages() {
+        let mut short = session(1);
+        short.message_count = 1;
+        let kept = |min_messages| {
+            let filter = DiscoveryFilter {
+                min_messages,
+                ..Default::default()
+            };
+            filter_sessions(vec![short.clone(), session(2)], &filter)
+                .into_iter()
+                .map(|s| s.session_id)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(kept(DEFAULT_MIN_MESSAGES), ["s2"]);
+        assert_eq!(kept(1), ["s1", "s2"]);
+    }
+
+    #[test]
+    fn claude_root_prefers_existing_config_dirs() {
+        let home = std::env::temp_dir().join(format!("tracekit-home-{}", std::process::id()));
+        let config = home.join("custom");
+        let xdg = home.join(".config/claude/projects");
+        let legacy = home.join(".claude/projects");
+
+        // Nothing exists yet: fall back to ~/.claude
+        let root = || claude_root(Some(config.clone()), Some(home.clone()));
+        assert_eq!(root(), Some(legacy.clone()));
+
+        std::fs::create_dir_all(&xdg).unwrap();
+        assert_eq!(root(), Some(xdg));
+
+        std::fs::create_dir_all(config.join("projects")).unwrap();
+        assert_eq!(root(), Some(config.join("projects")));
+
+        std::fs::remove_dir_all(&home).unwrap();
+    }
+
+    fn finding(description: String) -> Finding {
+        Finding {
+            kind: FindingKind::RedundantReread,
+            description,
+            evidence: Vec::new(),
+            evidence_refs: Vec::new(),
+            wasted_tokens: None,
+            wasted_cost_usd: None,
+            confidence: 1.0,
+        }
+    }
+
+    #[test]
+    fn redaction_collapses_home_paths_to_tilde() {
+        let home = home_string();
+        if home.is_empty() {
+            return;
+        }
+        let file = format!("{}/proj/src/main.rs", home);
+        assert_eq!(
+            redact_path(&file, PathRedaction::Home),
+            "~/proj/src/main.rs"
+        );
+        assert_eq!(redact_path(&file, PathRedaction::Full), "…/main.rs");
+
+        let mut session = session(1);
+        session.cwd = Some(String::new());
+        let mut result = AnalysisResult {
+            session,
+            session_kind: Default::default(),
+            findings: vec![finding(format!("read {} 3 times", file))],
+            top_expensive_messages: Vec::new(),
+        };
+        // An empty cwd must not be "replaced" between every character
+        redact_analysis_paths(&mut result, PathRedaction::Home);
+        assert_eq!(
+            result.findings[0].description,
+            "read ~/proj/src/main.rs 3 times"
+        );
     }
 }
@@ -3,15 +3,63 @@
 ///   ~/.local/share/opencode/storage/session/<project_hash>/<ses_*.json>
 ///   ~/.local/share/opencode/storage/message/<ses_id>/<msg_*.json>
 ///   ~/.local/share/opencode/storage/part/<msg_id>/<prt_*.json>
+///
+/// A session is spread over one file per message and one per part, far more
+/// files than the other agents' single JSONL, so every read here goes through
+/// `read_file`, which caps how many are open at once (`--max-open-files`).
+/// Callers parse many sessions on parallel threads, which would otherwise risk
+/// EMFILE on large histories.
 use anyhow::{Context, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use serde::Deserialize;
 use serde_json::Value;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, RwLock};
+use std::time::SystemTime;
 use tracekit_core::*;
 use walkdir::WalkDir;
 
-use super::default_root;
+use super::{default_root, note_skipped, walk_entry};
+
+/// Default cap on OpenCode files open at once; well under the common 256/1024
+/// per-process descriptor limits.
+pub const DEFAULT_MAX_OPEN_FILES: usize = 64;
+
+static MAX_OPEN_FILES: RwLock<usize> = RwLock::new(DEFAULT_MAX_OPEN_FILES);
+static OPEN_FILES: Mutex<usize> = Mutex::new(0);
+static FILE_CLOSED: Condvar = Condvar::new();
+
+/// Cap how many OpenCode files are read at once (minimum 1).
+pub fn set_max_open_files(n: usize) {
+    if let Ok(mut m) = MAX_OPEN_FILES.write() {
+        *m = n.max(1);
+    }
+}
+
+/// The cap set with [`set_max_open_files`].
+pub fn max_open_files() -> usize {
+    MAX_OPEN_FILES
+        .read()
+        .map(|m| *m)
+        .unwrap_or(DEFAULT_MAX_OPEN_FILES)
+}
+
+/// Read a file to a string, first waiting until fewer than `max_open_files()`
+/// reads are in flight.
+fn read_file(path: &Path) -> std::io::Result<String> {
+    let max = max_open_files();
+    {
+        let mut open = OPEN_FILES.lock().unwrap_or_else(|e| e.into_inner());
+        while *open >= max {
+            open = FILE_CLOSED.wait(open).unwrap_or_else(|e| e.into_inner());
+        }
+        *open += 1;
+    }
+    let result = std::fs::read_to_string(path);
+    *OPEN_FILES.lock().unwrap_or_else(|e| e.into_inner()) -= 1;
+    FILE_CLOSED.notify_one();
+    result
+}
 
 pub fn discover_sessions() -> Result<Vec<CanonicalSession>> {
     let root = match default_root(Agent::Opencode) {
@@ -21,31 +69,94 @@ pub fn discover_sessions() -> Result<Vec<CanonicalSession>> {
 
     let session_root = root.join("session");
     if !session_root.exists() {
+        note_skipped(Agent::Opencode, &session_root, "directory does not exist");
         return Ok(Vec::new());
     }
 
-    let mut sessions = Vec::new();
+    Ok(discover_in(&session_root, 2, 2))
+}
 
-    for entry in WalkDir::new(&session_root)
-        .min_depth(2)
-        .max_depth(2)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let path = entry.path();
-        if path.extension().and_then(|e| e.to_str()) != Some("json") {
-            continue;
-        }
-        match parse_session_file(path, &root) {
-            Ok(s) => sessions.push(s),
-            Err(_) => {}
+/// Recursively discover sessions under an arbitrary directory holding copies of
+/// OpenCode `storage/` trees.
+pub fn discover_sessions_under(dir: &Path) -> Vec<CanonicalSession> {
+    discover_in(dir, 1, usize::MAX)
+}
+
+/// Whether `path` looks like an OpenCode session file:
+/// `<storage>/session/<project_hash>/<ses_*.json>`.
+pub fn is_session_file(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("json")
+        && path
+            .parent()
+            .and_then(Path::parent)
+            .and_then(Path::file_name)
+            == Some("session".as_ref())
+}
+
+/// The `storage/` directory a session file belongs to, which also holds its
+/// `message/` and `part/` trees.
+pub fn storage_root(session_file: &Path) -> Option<&Path> {
+    session_file.ancestors().nth(3)
+}
+
+/// Newest modification time among a session's message and part files. These
+/// change as the conversation grows, while the session file often doesn't.
+pub fn content_modified(session: &CanonicalSession) -> Option<SystemTime> {
+    let root = storage_root(&session.source_path)?;
+    let part_root = root.join("part");
+    let mut newest = None;
+    let files = |dir: PathBuf| {
+        WalkDir::new(dir)
+            .min_depth(1)
+            .max_depth(1)
+            .into_iter()
+            .filter_map(|e| e.ok())
+    };
+    for msg in files(root.join("message").join(&session.session_id)) {
+        // Parts live under the message id, which is also the message file's name
+        let parts = msg.path().file_stem().map(|id| part_root.join(id));
+        for entry in std::iter::once(msg).chain(parts.into_iter().flat_map(files)) {
+            newest = newest.max(entry.metadata().ok().and_then(|m| m.modified().ok()));
         }
     }
+    newest
+}
 
-    Ok(sessions)
+fn discover_in(root: &Path, min_depth: usize, max_depth: usize) -> Vec<CanonicalSession> {
+    WalkDir::new(root)
+        .min_depth(min_depth)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_map(|e| walk_entry(Agent::Opencode, e))
+        .filter(|e| {
+            let matched = is_session_file(e.path());
+            if !matched && e.path().extension().and_then(|x| x.to_str()) == Some("json") {
+                note_skipped(
+                    Agent::Opencode,
+                    e.path(),
+                    "not in a session/<project>/ directory",
+                );
+            }
+            matched
+        })
+        .filter_map(|e| {
+            let Some(storage) = storage_root(e.path()) else {
+                note_skipped(Agent::Opencode, e.path(), "no storage/ directory above it");
+                return None;
+            };
+            match parse_session_file(e.path(), storage) {
+                Ok(s) => Some(s),
+                Err(err) => {
+                    note_skipped(Agent::Opencode, e.path(), format!("unreadable: {:#}", err));
+                    None
+                }
+            }
+        })
+        .collect()
 }
 
 #[derive(Debug, Deserialize)]
+#[allow(dead_code)]
 struct RawSession {
     id: String,
     #[serde(rename = "projectID")]
@@ -63,7 +174,22 @@ struct RawTime {
     completed: Option<u64>,
 }
 
-fn ms_to_utc(ms: u64) -> DateTime<Utc> {
+/// Epoch values below this are seconds rather than milliseconds: as
+/// milliseconds it is September 2001, as seconds it is tens of millennia away.
+const SECONDS_CUTOFF: u64 = 1_000_000_000_000;
+
+/// An OpenCode `time` value in milliseconds. Most versions store milliseconds,
+/// but some store seconds, which read as milliseconds land in January 1970.
+pub fn epoch_millis(t: u64) -> u64 {
+    if t < SECONDS_CUTOFF {
+        t.saturating_mul(1000)
+    } else {
+        t
+    }
+}
+
+fn ms_to_utc(t: u64) -> DateTime<Utc> {
+    let ms = epoch_millis(t);
     let secs = (ms / 1000) as i64;
     let nanos = ((ms % 1000) * 1_000_000) as u32;
     Utc.timestamp_opt(secs, nanos)
@@ -72,7 +198,7 @@ fn ms_to_utc(ms: u64) -> DateTime<Utc> {
 }
 
 fn parse_session_file(path: &std::path::Path, root: &std::path::Path) -> Result<CanonicalSession> {
-    let content = std::fs::read_to_string(path)?;
+    let content = read_file(path)?;
     let raw: RawSession = serde_json::from_str(&content)
         .with_context(|| format!("parsing session {}", path.display()))?;
 
@@ -86,9 +212,10 @@ fn parse_session_file(path: &std::path::Path, root: &std::path::Path) -> Result<
 
     // Quick scan messages to get message count and model
     let msg_root = root.join("message").join(&raw.id);
-    let (message_count, model) = if msg_root.exists() {
+    let (message_count, model, last_message) = if msg_root.exists() {
         let mut count = 0;
         let mut found_model: Option<String> = None;
+        let mut last: Option<PathBuf> = None;
         for e in WalkDir::new(&msg_root)
             .min_depth(1)
             .max_depth(1)
@@ -97,8 +224,12 @@ fn parse_session_file(path: &std::path::Path, root: &std::path::Path) -> Result<
         {
             if e.path().extension().and_then(|x| x.to_str()) == Some("json") {
                 count += 1;
+                // Message IDs sort chronologically, as in `parse_session`
+                if last.as_deref().is_none_or(|l| e.path() > l) {
+                    last = Some(e.path().to_path_buf());
+                }
                 if found_model.is_none() {
-                    if let Ok(data) = std::fs::read_to_string(e.path()) {
+                    if let Ok(data) = read_file(e.path()) {
                         if let Ok(v) = serde_json::from_str::<Value>(&data) {
                             if let Some(m) = v.get("modelID").and_then(|x| x.as_str()) {
                                 // Strip provider prefix e.g. "openrouter/moonshotai/kimi-k2.5" -> keep as-is
@@ -109,10 +240,11 @@ fn parse_session_file(path: &std::path::Path, root: &std::path::Path) -> Result<
                 }
             }
         }
-        (count, found_model)
+        (count, found_model, last)
     } else {
-        (0, None)
+        (0, None, None)
     };
+    let ended_abnormally = last_message.is_some_and(|p| message_aborted(&p));
 
     Ok(CanonicalSession {
         session_id: raw.id,
@@ -127,11 +259,48 @@ fn parse_session_file(path: &std::path::Path, root: &std::path::Path) -> Result<
         total_cost_usd: None,
         total_input_tokens: 0,
         total_output_tokens: 0,
+        auxiliary_cost_usd: None,
+        heuristic_turns: 0,
+        cost_breakdown: None,
+        user_count: 0,
+        assistant_count: 0,
+        system_count: 0,
+        total_cache_read_tokens: 0,
+        total_cache_write_tokens: 0,
+        total_reasoning_tokens: 0,
+        tags: Vec::new(),
+        parse_errors: 0,
+        system_prompt: None,
+        ended_abnormally,
+        tool_calls: 0,
+        tool_errors: 0,
+        efficiency_score: None,
+        pricing_confidence: None,
+        observed_cost_usd: None,
     })
 }
 
+/// Whether a message file records an aborted turn: OpenCode sets `error.name`
+/// to `MessageAbortedError` (older versions an `aborted` status) when the user
+/// cancels a response.
+fn message_aborted(path: &Path) -> bool {
+    let Some(v) = read_file(path)
+        .ok()
+        .and_then(|d| serde_json::from_str::<Value>(&d).ok())
+    else {
+        return false;
+    };
+    v.pointer("/error/name")
+        .and_then(|n| n.as_str())
+        .is_some_and(|n| n.contains("Abort"))
+        || v.get("status").and_then(|s| s.as_str()) == Some("aborted")
+}
+
 pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
-    let root = match default_root(Agent::Opencode) {
+    let root = match storage_root(&session.source_path)
+        .map(Path::to_path_buf)
+        .or_else(|| default_root(Agent::Opencode))
+    {
         Some(r) => r,
         None => {
             return Ok(ParsedSession {
@@ -153,6 +322,7 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
 
     let mut messages = Vec::new();
     let mut seq = 0usize;
+    let mut auxiliary_cost: Option<f64> = None;
 
     // Collect all message files
     let mut msg_files: Vec<PathBuf> = WalkDir::new(&msg_root)
@@ -168,7 +338,7 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
     msg_files.sort();
 
     for msg_path in &msg_files {
-        let data = match std::fs::read_to_string(msg_path) {
+        let data = match read_file(msg_path) {
             Ok(d) => d,
             Err(_) => continue,
         };
@@ -203,8 +373,12 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
             .map(ms_to_utc);
 
         let latency_ms = match (
-            v.pointer("/time/created").and_then(|x| x.as_u64()),
-            v.pointer("/time/completed").and_then(|x| x.as_u64()),
+            v.pointer("/time/created")
+                .and_then(|x| x.as_u64())
+                .map(epoch_millis),
+            v.pointer("/time/completed")
+                .and_then(|x| x.as_u64())
+                .map(epoch_millis),
         ) {
             (Some(s), Some(e)) if e >= s => Some(e - s),
             _ => None,
@@ -216,14 +390,24 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
 
         // Load parts for this message
         let msg_part_root = part_root.join(&msg_id);
-        let (tool_calls, step_usage) = if msg_part_root.exists() {
+        let parts = if msg_part_root.exists() {
             load_parts(&msg_part_root, model.as_deref())?
         } else {
-            (Vec::new(), None)
+            LoadedParts::default()
+        };
+        if let Some(c) = parts.auxiliary_cost {
+            *auxiliary_cost.get_or_insert(0.0) += c;
+        }
+        let tool_calls = parts.tool_calls;
+        let content_chars = parts.content_chars;
+        let prompt_lines = if role == Role::User {
+            tracekit_core::prompt_lines(&parts.text)
+        } else {
+            Vec::new()
         };
 
         // Prefer step-finish usage if available (it's per-step), otherwise use message-level
-        let usage = step_usage.or(direct_usage);
+        let usage = parts.step_usage.or(direct_usage);
 
         seq += 1;
         messages.push(CanonicalMessage {
@@ -241,13 +425,15 @@ pub fn parse_session(session: &CanonicalSession) -> Result<ParsedSession> {
                 .get("finish")
                 .and_then(|x| x.as_str())
                 .map(|s| s.to_string()),
+            content_chars,
+            prompt_lines,
         });
     }
 
-    Ok(ParsedSession {
-        session: session.clone(),
-        messages,
-    })
+    let mut session = session.clone();
+    session.auxiliary_cost_usd = auxiliary_cost;
+
+    Ok(ParsedSession { session, messages })
 }
 
 fn extract_opencode_usage(
@@ -284,18 +470,34 @@ fn extract_opencode_usage(
         reasoning_tokens: reasoning,
         cache_read_tokens: cache_read,
         cache_write_tokens: cache_write,
+        cache_write_1h_tokens: 0,
         cost_observed_usd: cost,
         cost_estimated_usd: cost_estimated,
         latency_ms,
+        heuristic: false,
     })
 }
 
-fn load_parts(
-    part_dir: &PathBuf,
-    model: Option<&str>,
-) -> Result<(Vec<CanonicalTool>, Option<CanonicalUsage>)> {
+#[derive(Default)]
+struct LoadedParts {
+    tool_calls: Vec<CanonicalTool>,
+    step_usage: Option<CanonicalUsage>,
+    /// Cost of embedding/summarization calls recorded as their own parts
+    auxiliary_cost: Option<f64>,
+    /// Characters of text/reasoning content across parts
+    content_chars: usize,
+    /// Text parts joined, for fingerprinting user prompts
+    text: String,
+}
+
+fn load_parts(part_dir: &PathBuf, model: Option<&str>) -> Result<LoadedParts> {
     let mut tool_calls = Vec::new();
     let mut step_usage: Option<CanonicalUsage> = None;
+    let mut auxiliary_cost: Option<f64> = None;
+    let mut content_chars = 0usize;
+    let mut text = String::new();
+    // (call id, path) for each file a patch part reports changed
+    let mut patched: Vec<(String, String)> = Vec::new();
 
     let mut part_files: Vec<PathBuf> = WalkDir::new(part_dir)
         .min_depth(1)
@@ -309,7 +511,7 @@ fn load_parts(
     part_files.sort();
 
     for part_path in &part_files {
-        let data = match std::fs::read_to_string(part_path) {
+        let data = match read_file(part_path) {
             Ok(d) => d,
             Err(_) => continue,
         };
@@ -321,6 +523,17 @@ fn load_parts(
         let part_type = v.get("type").and_then(|x| x.as_str()).unwrap_or("");
 
         match part_type {
+            "text" | "reasoning" => {
+                let part_text = v.get("text").and_then(|x| x.as_str()).unwrap_or("");
+                content_chars += part_text.chars().count();
+                if part_type == "text" {
+                    if !text.is_empty() {
+                        text.push('\n');
+                    }
+                    text.push_str(part_text);
+                }
+            }
+
             "step-finish" => {
                 let cost = v.get("cost").and_then(|x| x.as_f64());
                 if let Some(tokens) = v.get("tokens") {
@@ -364,14 +577,27 @@ fn load_parts(
                             reasoning_tokens: reasoning,
                             cache_read_tokens: cache_read,
                             cache_write_tokens: cache_write,
+                            cache_write_1h_tokens: 0,
                             cost_observed_usd: cost,
                             cost_estimated_usd: cost_estimated,
                             latency_ms: None,
+                            heuristic: false,
                         });
                     }
                 }
             }
 
+            "embedding" | "summary" => {
+                // Auxiliary model call with its own usage; may name a different model
+                let aux_model = v.get("modelID").and_then(|x| x.as_str()).or(model);
+                let cost = v.get("cost").and_then(|x| x.as_f64()).or_else(|| {
+                    extract_opencode_usage(&v, None, None, aux_model)?.effective_cost()
+                });
+                if let Some(c) = cost {
+                    *auxiliary_cost.get_or_insert(0.0) += c;
+                }
+            }
+
             "tool" => {
                 let call_id = v
                     .get("callID")
@@ -394,7 +620,7 @@ fn load_parts(
                     _ => ToolStatus::Unknown,
                 };
 
-                let args_summary = v.pointer("/state/input").map(|x| extract_opencode_args(x));
+                let args_summary = v.pointer("/state/input").map(extract_opencode_args);
 
                 let err_msg = if status == ToolStatus::Error {
                     v.pointer("/state/output")
@@ -405,39 +631,105 @@ fn load_parts(
                 };
 
                 let duration_ms = match (
-                    v.pointer("/state/time/start").and_then(|x| x.as_u64()),
-                    v.pointer("/state/time/end").and_then(|x| x.as_u64()),
+                    v.pointer("/state/time/start")
+                        .and_then(|x| x.as_u64())
+                        .map(epoch_millis),
+                    v.pointer("/state/time/end")
+                        .and_then(|x| x.as_u64())
+                        .map(epoch_millis),
                 ) {
                     (Some(s), Some(e)) if e >= s => Some(e - s),
                     _ => None,
                 };
 
-                tool_calls.push(CanonicalTool {
+                let mut tool = CanonicalTool {
+                    category: canonical_tool_category(&tool_name),
                     tool_name,
                     call_id,
                     status,
-                    error_class: if status == ToolStatus::Error {
-                        Some("tool_error".to_string())
-                    } else {
-                        None
-                    },
+                    error_class: None,
                     error_message: err_msg,
                     args_summary,
                     output_summary: None,
+                    output_bytes: v
+                        .pointer("/state/output")
+                        .and_then(|x| x.as_str())
+                        .map(|s| s.len() as u64),
+                    output_hash: v
+                        .pointer("/state/output")
+                        .and_then(|x| x.as_str())
+                        .map(content_hash),
                     duration_ms,
-                });
+                };
+                if status == ToolStatus::Error {
+                    let message = tool.error_message.as_deref().unwrap_or_default();
+                    tool.error_class = Some(classify_tool_error(&tool, message, "tool_error"));
+                }
+                tool_calls.push(tool);
             }
 
+            "patch" => {
+                // End-of-step record of files the step changed
+                let part_id = v.get("id").and_then(|x| x.as_str()).unwrap_or("patch");
+                let files = v.get("files").and_then(|x| x.as_array());
+                for (i, file) in files.into_iter().flatten().enumerate() {
+                    if let Some(path) = file.as_str() {
+                        patched.push((
+                            format!("{}#{}", part_id, i),
+                            path.chars().take(100).collect(),
+                        ));
+                    }
+                }
+            }
+
+            // Session bookkeeping (working-tree hashes, context compaction markers):
+            // neither content nor tool activity
+            "snapshot" | "compaction" => {}
+
             _ => {}
         }
     }
 
-    Ok((tool_calls, step_usage))
+    // A patched file usually has its own edit/write part already. Only files
+    // changed some other way (a shell command, say) get an edit here, so
+    // edit/reread detectors still see the write without double-counting it.
+    for (call_id, path) in patched {
+        let edited = tool_calls.iter().any(|t| {
+            matches!(t.category, ToolCategory::Edit | ToolCategory::Write)
+                && t.args_summary.as_deref() == Some(path.as_str())
+        });
+        if edited {
+            continue;
+        }
+        tool_calls.push(CanonicalTool {
+            tool_name: "patch".to_string(),
+            category: ToolCategory::Edit,
+            call_id,
+            status: ToolStatus::Success,
+            error_class: None,
+            error_message: None,
+            args_summary: Some(path),
+            output_summary: None,
+            output_bytes: None,
+            output_hash: None,
+            duration_ms: None,
+        });
+    }
+
+    Ok(LoadedParts {
+        tool_calls,
+        step_usage,
+        auxiliary_cost,
+        content_chars,
+        text,
+    })
 }
 
 fn extract_opencode_args(v: &Value) -> String {
     // Try common fields
-    for key in &["file", "path", "command", "query", "pattern", "name"] {
+    for key in &[
+        "filePath", "file", "path", "command", "query", "pattern", "name",
+    ] {
         if let Some(s) = v.get(key).and_then(|x| x.as_str()) {
             return s.chars().take(100).collect();
         }
@@ -449,3 +741,180 @@ fn extract_opencode_args(v: &Value) -> String {
         .take(100)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A throwaway `storage/` tree holding one session, `ses_test`.
+    struct Storage {
+        root: PathBuf,
+    }
+
+    impl Storage {
+        fn new() -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let root = std::env::temp_dir()
+                .join(format!(
+                    "tracekit-opencode-{}-{}",
+                    std::process::id(),
+                    NEXT.fetch_add(1, Ordering::Relaxed)
+                ))
+                .join("storage");
+            let storage = Self { root };
+            storage.write(
+                "session/proj/ses_test.json",
+                &json!({"id": "ses_test", "directory": "/work/proj", "time": {"created": 1_700_000_000_000u64}}),
+            );
+            storage
+        }
+
+        fn write(&self, rel: &str, v: &Value) {
+            let path = self.root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, v.to_string()).unwrap();
+        }
+
+        fn message(&self, id: &str, role: &str) {
+            self.write(
+                &format!("message/ses_test/{}.json", id),
+                &json!({"id": id, "role": role, "modelID": "claude-sonnet-4", "time": {"created": 1_700_000_000_000u64}}),
+            );
+        }
+
+        fn part(&self, msg: &str, id: &str, mut v: Value) {
+            v["id"] = json!(id);
+            self.write(&format!("part/{}/{}.json", msg, id), &v);
+        }
+
+        fn parse(&self) -> ParsedSession {
+            let session_file = self.root.join("session/proj/ses_test.json");
+            let session = parse_session_file(&session_file, &self.root).unwrap();
+            let mut parsed = parse_session(&session).unwrap();
+            parsed.compute_totals();
+            parsed
+        }
+    }
+
+    impl Drop for Storage {
+        fn drop(&mut self) {
+            if let Some(dir) = self.root.parent() {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    #[test]
+    fn auxiliary_parts_add_to_session_cost() {
+        let storage = Storage::new();
+        storage.message("msg_001", "assistant");
+        storage.part(
+            "msg_001",
+            "prt_001",
+            json!({"type": "step-finish", "cost": 0.5, "tokens": {"input": 100, "output": 10}}),
+        );
+        storage.part(
+            "msg_001",
+            "prt_002",
+            json!({"type": "summary", "cost": 0.25, "tokens": {"input": 1000, "output": 50}}),
+        );
+
+        let parsed = storage.parse();
+        assert_eq!(parsed.session.auxiliary_cost_usd, Some(0.25));
+        assert_eq!(parsed.session.total_cost_usd, Some(0.75));
+        // The summary call's tokens are not a turn's tokens
+        assert_eq!(parsed.session.total_input_tokens, 100);
+    }
+
+    #[test]
+    fn parallel_parses_share_the_open_file_cap() {
+        let storage = Storage::new();
+        storage.message("msg_001", "user");
+        storage.message("msg_002", "assistant");
+        storage.part(
+            "msg_002",
+            "prt_001",
+            json!({"type": "text", "text": "done"}),
+        );
+
+        set_max_open_files(0);
+        assert_eq!(max_open_files(), 1);
+        let counts: Vec<usize> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| storage.parse().messages.len()))
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        set_max_open_files(DEFAULT_MAX_OPEN_FILES);
+
+        // Every thread got through the one-file cap; a slot that was never
+        // released would have left the rest waiting forever
+        assert_eq!(counts, [2; 8]);
+    }
+
+    #[test]
+    fn patch_parts_only_add_edits_for_files_without_one() {
+        let storage = Storage::new();
+        storage.message("msg_001", "assistant");
+        storage.part(
+            "msg_001",
+            "prt_001",
+            json!({"type": "tool", "callID": "call_1", "tool": "edit",
+                   "state": {"status": "completed", "input": {"filePath": "/work/proj/src/a.rs"}, "output": ""}}),
+        );
+        storage.part(
+            "msg_001",
+            "prt_002",
+            json!({"type": "patch", "hash": "abc", "files": ["/work/proj/src/a.rs", "/work/proj/src/b.rs"]}),
+        );
+
+        let parsed = storage.parse();
+        let tools: Vec<(&str, Option<&str>)> = parsed.messages[0]
+            .tool_calls
+            .iter()
+            .map(|t| (t.tool_name.as_str(), t.args_summary.as_deref()))
+            .collect();
+        assert_eq!(
+            tools,
+            [
+                ("edit", Some("/work/proj/src/a.rs")),
+                ("patch", Some("/work/proj/src/b.rs")),
+            ]
+        );
+        assert_eq!(
+            parsed.messages[0].tool_calls[1].category,
+            ToolCategory::Edit
+        );
+        assert_eq!(parsed.messages[0].tool_calls[1].call_id, "prt_002#1");
+    }
+
+    #[test]
+    fn content_modified_tracks_the_newest_part_file() {
+        let storage = Storage::new();
+        storage.message("msg_001", "assistant");
+        storage.part("msg_001", "prt_001", json!({"type": "text", "text": "hi"}));
+        let session = storage.parse().session;
+
+        let later = SystemTime::now() + std::time::Duration::from_secs(3600);
+        std::fs::File::options()
+            .write(true)
+            .open(storage.root.join("part/msg_001/prt_001.json"))
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(content_modified(&session), Some(later));
+    }
+
+    #[test]
+    fn times_in_seconds_or_milliseconds_give_the_same_instant() {
+        let expected = Utc.with_ymd_and_hms(2025, 6, 1, 12, 0, 0).unwrap();
+        assert_eq!(ms_to_utc(1_748_779_200), expected);
+        assert_eq!(ms_to_utc(1_748_779_200_000), expected);
+        assert_eq!(
+            ms_to_utc(1_748_779_200_250),
+            expected + chrono::Duration::milliseconds(250)
+        );
+    }
+}
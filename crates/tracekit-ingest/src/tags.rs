@@ -0,0 +1,89 @@
+//! User-assigned session tags (`tracekit capture tag`), kept in a sidecar file
+//! so the agents' own trace files are never modified.
+//!
+//! The store lives at `~/.local/state/tracekit/tags.json` (the platform state
+//! dir where there is one) and maps session IDs to their tags. Discovery merges
+//! these into each session's `tags`, alongside the derived `repo:`/`branch:` ones.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tracekit_core::CanonicalSession;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct TagStore {
+    tags: BTreeMap<String, Vec<String>>,
+}
+
+/// Path of the tag store, if a home or state directory is known.
+pub fn store_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(|| dirs::home_dir().map(|h| h.join(".local").join("state")))
+        .map(|d| d.join("tracekit").join("tags.json"))
+}
+
+impl TagStore {
+    /// Read the store; a missing file is an empty store.
+    pub fn load() -> Result<Self> {
+        let Some(path) = store_path() else {
+            return Ok(Self::default());
+        };
+        match std::fs::read(&path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("Failed to parse {}", path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        }
+    }
+
+    /// Write the store, replacing the previous file atomically.
+    pub fn save(&self) -> Result<PathBuf> {
+        let Some(path) = store_path() else {
+            anyhow::bail!("no home directory to store tags in");
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Tags stored for `session_id`.
+    pub fn get(&self, session_id: &str) -> &[String] {
+        self.tags.get(session_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Add `tags` to `session_id`, skipping ones it already has. Returns how many
+    /// were new.
+    pub fn add(&mut self, session_id: &str, tags: &[String]) -> usize {
+        let entry = self.tags.entry(session_id.to_string()).or_default();
+        let before = entry.len();
+        for tag in tags {
+            if !entry.contains(tag) {
+                entry.push(tag.clone());
+            }
+        }
+        entry.len() - before
+    }
+
+    /// Append each session's stored tags to its `tags`.
+    pub fn apply(&self, sessions: &mut [CanonicalSession]) {
+        if self.tags.is_empty() {
+            return;
+        }
+        for s in sessions {
+            for tag in self.get(&s.session_id) {
+                if !s.tags.contains(tag) {
+                    s.tags.push(tag.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use anyhow::Result;
+use std::sync::RwLock;
+
+/// Currency used when rendering costs. Costs are always computed and stored in
+/// USD; this only converts and relabels them for display.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Currency {
+    /// ISO 4217 code, e.g. `EUR`
+    pub code: String,
+    /// Units of this currency per 1 USD
+    pub rate: f64,
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        Self {
+            code: "USD".to_string(),
+            rate: 1.0,
+        }
+    }
+}
+
+impl Currency {
+    pub fn new(code: &str, rate: f64) -> Result<Self> {
+        let code = code.trim().to_uppercase();
+        if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+            anyhow::bail!(
+                "Invalid currency code '{}' (expected e.g. USD, EUR, GBP)",
+                code
+            );
+        }
+        if !rate.is_finite() || rate <= 0.0 {
+            anyhow::bail!("Invalid FX rate {} (must be a positive number)", rate);
+        }
+        Ok(Self { code, rate })
+    }
+
+    pub fn symbol(&self) -> Option<&'static str> {
+        match self.code.as_str() {
+            "USD" => Some("$"),
+            "EUR" => Some("€"),
+            "GBP" => Some("£"),
+            "JPY" | "CNY" => Some("¥"),
+            "INR" => Some("₹"),
+            _ => None,
+        }
+    }
+
+    /// Convert a USD amount and format it, e.g. `€1.2345` or `1.2345 CHF`.
+    pub fn format(&self, usd: f64, decimals: usize) -> String {
+        let amount = usd * self.rate;
+        match self.symbol() {
+            Some(sym) => format!("{}{:.*}", sym, decimals, amount),
+            None => format!("{:.*} {}", decimals, amount, self.code),
+        }
+    }
+}
+
+static DISPLAY_CURRENCY: RwLock<Option<Currency>> = RwLock::new(None);
+
+/// Set the currency all renderers use for costs (default USD).
+pub fn set_display_currency(currency: Currency) {
+    if let Ok(mut c) = DISPLAY_CURRENCY.write() {
+        *c = Some(currency);
+    }
+}
+
+pub fn display_currency() -> Currency {
+    DISPLAY_CURRENCY
+        .read()
+        .ok()
+        .and_then(|c| c.clone())
+        .unwrap_or_default()
+}
+
+/// Format a USD amount in the display currency.
+pub fn fmt_money(usd: f64, decimals: usize) -> String {
+    display_currency().format(usd, decimals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn costs_convert_at_the_rate_and_take_the_currency_label() {
+        let eur = Currency::new("eur", 0.9).unwrap();
+        assert_eq!(eur.code, "EUR");
+        assert_eq!(eur.format(2.0, 2), "€1.80");
+
+        let chf = Currency::new("CHF", 0.8).unwrap();
+        assert_eq!(chf.format(2.0, 2), "1.60 CHF");
+
+        assert!(Currency::new("EUR", 0.0).is_err());
+        assert!(Currency::new("EURO", 0.9).is_err());
+    }
+}
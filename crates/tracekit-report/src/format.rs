@@ -0,0 +1,100 @@
+//! Number and time formatting shared by every renderer. Helpers for optional
+//! values take the placeholder to show when the value is missing (`-` in the
+//! terminal, `—` in HTML).
+
+use crate::currency::fmt_money;
+pub use tracekit_core::fmt_tokens;
+use tracekit_core::SystemPromptSize;
+
+pub fn fmt_cost(cost: Option<f64>, missing: &str) -> String {
+    match cost {
+        Some(c) => fmt_money(c, 4),
+        None => missing.to_string(),
+    }
+}
+
+/// Opening-context size, e.g. `18.2k tokens (first request)`; estimates get a `~`.
+pub fn fmt_system_prompt(size: &SystemPromptSize) -> String {
+    if size.estimated {
+        format!("~{} tokens (estimated from text)", fmt_tokens(size.tokens))
+    } else {
+        format!("{} tokens (first request)", fmt_tokens(size.tokens))
+    }
+}
+
+pub fn fmt_duration(secs: Option<i64>, missing: &str) -> String {
+    match secs {
+        None => missing.to_string(),
+        Some(s) if s < 60 => format!("{}s", s),
+        Some(s) if s < 3600 => format!("{}m{}s", s / 60, s % 60),
+        Some(s) => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+pub fn fmt_ts(ts: Option<chrono::DateTime<chrono::Utc>>, missing: &str) -> String {
+    match ts {
+        Some(t) => t.format("%Y-%m-%d %H:%M").to_string(),
+        None => missing.to_string(),
+    }
+}
+
+/// Shorten absolute paths embedded in finding text for display: paths under the
+/// session's `cwd` become relative, and the rest of the home directory becomes `~`.
+/// Only renderers call this; JSON output keeps the full paths.
+pub fn relative_paths(text: &str, cwd: Option<&str>) -> String {
+    let mut out = text.to_string();
+    if let Some(cwd) = cwd
+        .map(|c| c.trim_end_matches('/'))
+        .filter(|c| !c.is_empty())
+    {
+        out = out.replace(&format!("{}/", cwd), "");
+    }
+    if let Some(home) = dirs::home_dir() {
+        let home = home.to_string_lossy();
+        let home = home.trim_end_matches('/');
+        if !home.is_empty() {
+            out = out.replace(&format!("{}/", home), "~/");
+        }
+    }
+    out
+}
+
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let head: String = s.chars().take(max.saturating_sub(1)).collect();
+        format!("{}…", head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_counts_switch_units_at_the_boundaries() {
+        assert_eq!(fmt_tokens(0), "0");
+        assert_eq!(fmt_tokens(999), "999");
+        assert_eq!(fmt_tokens(1_000), "1.0k");
+        assert_eq!(fmt_tokens(999_949), "999.9k");
+        // Rounds up into the next unit instead of reading `1000.0k`
+        assert_eq!(fmt_tokens(999_999), "1.0M");
+        assert_eq!(fmt_tokens(1_000_000), "1.0M");
+        assert_eq!(fmt_tokens(12_345_678), "12.3M");
+    }
+
+    #[test]
+    fn costs_and_durations_show_the_placeholder_when_missing() {
+        assert_eq!(fmt_cost(Some(0.0123), "-"), "$0.0123");
+        assert_eq!(fmt_cost(None, "-"), "-");
+        assert_eq!(fmt_cost(None, "—"), "—");
+
+        assert_eq!(fmt_duration(None, "—"), "—");
+        assert_eq!(fmt_duration(Some(59), "-"), "59s");
+        assert_eq!(fmt_duration(Some(60), "-"), "1m0s");
+        assert_eq!(fmt_duration(Some(3_599), "-"), "59m59s");
+        assert_eq!(fmt_duration(Some(3_600), "-"), "1h0m");
+        assert_eq!(fmt_duration(Some(90_061), "-"), "25h1m");
+    }
+}
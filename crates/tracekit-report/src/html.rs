@@ -1,9 +1,18 @@
+use crate::currency::fmt_money;
+use crate::format::{self, fmt_tokens, truncate};
 use anyhow::Result;
+
+/// Placeholder for missing values in HTML cells
+const MISSING: &str = "—";
 use tracekit_core::*;
 
 pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
     let s = &result.session;
-    let findings_html = render_findings(&result.findings);
+    let findings_html = if s.is_empty() {
+        r#"<div style="padding:1.25rem;color:var(--warn);font-size:.875rem">No messages parsed — the session file is empty or unparseable.</div>"#.to_string()
+    } else {
+        render_findings(&result.findings, result.session.cwd.as_deref())
+    };
     let expensive_html = render_expensive_messages(&result.top_expensive_messages);
 
     // Total identified waste
@@ -13,7 +22,7 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
         .filter_map(|f| f.wasted_cost_usd)
         .sum();
     let waste_display = if total_waste > 0.0 {
-        format!("${:.2}", total_waste)
+        fmt_money(total_waste, 2)
     } else {
         "—".to_string()
     };
@@ -176,6 +185,61 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
     background: linear-gradient(135deg, rgba(248,113,113,0.06) 0%, var(--surface) 60%);
   }}
 
+  /* Waste by finding kind — horizontal bars */
+  .waste-chart {{ padding: 0.75rem 1.25rem; }}
+  .waste-row {{
+    display: grid;
+    grid-template-columns: 12rem 1fr 6rem;
+    align-items: center;
+    gap: 0.75rem;
+    padding: 0.25rem 0;
+    font-family: var(--font-mono);
+    font-size: 0.75rem;
+  }}
+  .waste-label {{ color: var(--text-2); overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }}
+  .waste-track {{
+    height: 10px;
+    border-radius: 999px;
+    background: var(--surface-2);
+    overflow: hidden;
+  }}
+  .waste-fill {{ height: 100%; background: var(--danger); }}
+  .waste-value {{ color: var(--danger); text-align: right; }}
+
+  /* Token mix — fresh vs cached input */
+  .token-mix {{
+    background: var(--surface);
+    border: 1px solid var(--border);
+    border-radius: var(--radius-lg);
+    padding: 0.875rem 1.25rem;
+    margin: -0.75rem 0 1.5rem;
+  }}
+  .token-bar {{
+    display: flex;
+    height: 10px;
+    border-radius: 999px;
+    overflow: hidden;
+    background: var(--surface-2);
+  }}
+  .seg-fresh {{ background: var(--accent); }}
+  .seg-read  {{ background: var(--success); }}
+  .seg-write {{ background: var(--warn); }}
+  .token-legend {{
+    display: flex;
+    gap: 1.25rem;
+    margin-top: 0.5rem;
+    font-family: var(--font-mono);
+    font-size: 0.72rem;
+    color: var(--text-2);
+  }}
+  .token-legend i {{
+    display: inline-block;
+    width: 8px;
+    height: 8px;
+    border-radius: 2px;
+    margin-right: 0.35rem;
+  }}
+
   /* ── Sections ────────────────────────────────────────── */
   .section {{
     background: var(--surface);
@@ -299,6 +363,11 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
     padding-left: 0.5rem;
   }}
   .finding-evidence::before {{ content: '· '; color: var(--border-2); }}
+  .finding-fix {{
+    font-size: 0.78rem;
+    color: var(--text-2);
+  }}
+  .finding-fix::before {{ content: 'fix: '; color: var(--success); font-weight: 600; }}
   .no-findings {{
     padding: 1.25rem;
     color: var(--success);
@@ -324,12 +393,13 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
   <span class="header-logo">tracekit</span>
   <span class="header-sep">/</span>
   <span class="badge">{agent}</span>
+  <span class="badge">{kind}</span>
   <span class="session-id">{session_id}</span>
 </div>
 <div class="container">
 
   <div class="kpi-grid">
-    <div class="kpi">
+    <div class="kpi"{pricing_title}>
       <div class="kpi-label">Total Cost</div>
       <div class="kpi-value success">{total_cost}</div>
     </div>
@@ -337,6 +407,14 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
       <div class="kpi-label">Identified Waste</div>
       <div class="kpi-value {waste_class}">{waste_display}</div>
     </div>
+    <div class="kpi">
+      <div class="kpi-label">Input-side Cost</div>
+      <div class="kpi-value">{input_side}</div>
+    </div>
+    <div class="kpi">
+      <div class="kpi-label">Output-side Cost</div>
+      <div class="kpi-value">{output_side}</div>
+    </div>
     <div class="kpi">
       <div class="kpi-label">Messages</div>
       <div class="kpi-value info">{message_count}</div>
@@ -357,7 +435,12 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
       <div class="kpi-label">Findings</div>
       <div class="kpi-value {findings_color}">{findings_count}</div>
     </div>
+    <div class="kpi">
+      <div class="kpi-label">Efficiency</div>
+      <div class="kpi-value {score_class}">{score}</div>
+    </div>
   </div>
+  {token_mix}
 
   <div class="section">
     <div class="section-header">Session</div>
@@ -366,6 +449,11 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
       <dt>Model</dt><dd>{model}</dd>
       <dt>CWD</dt><dd>{cwd}</dd>
       <dt>Started</dt><dd>{started_at}</dd>
+      <dt>Aux Cost</dt><dd>{aux_cost}</dd>
+      <dt>Cache Read</dt><dd>{cache_read}</dd>
+      <dt>Cache Write</dt><dd>{cache_write}</dd>
+      <dt>Reasoning</dt><dd>{reasoning}</dd>
+      <dt>Estimated</dt><dd>{heuristic}</dd>
       <dt>Source</dt><dd>{source_path}</dd>
     </dl>
   </div>
@@ -386,22 +474,49 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
 </html>"#,
         session_id = &s.session_id,
         agent = s.source_agent,
-        total_cost = fmt_cost_html(s.total_cost_usd),
+        kind = result.session_kind,
+        total_cost = format::fmt_cost(s.total_cost_usd, MISSING),
+        pricing_title = s
+            .pricing_confidence
+            .filter(|c| *c != PricingConfidence::Exact)
+            .map(|c| format!(
+                r#" title="Pricing: {} — a model isn't in the price catalog""#,
+                c.describe()
+            ))
+            .unwrap_or_default(),
         waste_display = waste_display,
         waste_class = waste_class,
+        input_side = format::fmt_cost(s.cost_breakdown.map(|b| b.input_usd), MISSING),
+        output_side = format::fmt_cost(s.cost_breakdown.map(|b| b.output_usd), MISSING),
         message_count = s.message_count,
+        token_mix = render_token_mix(s),
         input_tokens = fmt_tokens(s.total_input_tokens),
         output_tokens = fmt_tokens(s.total_output_tokens),
-        duration = fmt_duration(s.duration_secs()),
+        duration = format::fmt_duration(s.duration_secs(), MISSING),
         findings_count = result.findings.len(),
         findings_color = if result.findings.is_empty() {
             "success"
         } else {
             "danger"
         },
+        score = if s.is_empty() {
+            MISSING.to_string()
+        } else {
+            format!("{}/100", session_score(result))
+        },
+        score_class = score_class(session_score(result) as f64),
         model = html_escape(s.model.as_deref().unwrap_or("-")),
         cwd = html_escape(s.cwd.as_deref().unwrap_or("-")),
-        started_at = fmt_ts(s.started_at),
+        started_at = format::fmt_ts(s.started_at, MISSING),
+        aux_cost = format::fmt_cost(s.auxiliary_cost_usd, MISSING),
+        cache_read = fmt_tokens(s.total_cache_read_tokens),
+        cache_write = fmt_tokens(s.total_cache_write_tokens),
+        reasoning = fmt_tokens(s.total_reasoning_tokens),
+        heuristic = if s.heuristic_turns > 0 {
+            format!("{} turns (heuristic, from text length)", s.heuristic_turns)
+        } else {
+            "—".to_string()
+        },
         source_path = html_escape(&s.source_path.display().to_string()),
         findings_html = findings_html,
         expensive_html = expensive_html,
@@ -409,24 +524,102 @@ pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
     ))
 }
 
-pub fn render_aggregate(results: &[AnalysisResult]) -> Result<String> {
+/// Inline, dependency-free row filter for the aggregate sessions table
+/// (matches agent, session ID and CWD).
+const SESSION_FILTER_SCRIPT: &str = r#"<script>
+(function () {
+  var input = document.getElementById('session-filter');
+  var rows = document.querySelectorAll('#sessions tbody tr:not(.session-detail)');
+  input.addEventListener('input', function () {
+    var q = input.value.trim().toLowerCase();
+    rows.forEach(function (row) {
+      var c = row.cells;
+      var text = (c[0].textContent + ' ' + c[1].textContent + ' ' + c[4].textContent).toLowerCase();
+      var display = text.indexOf(q) === -1 ? 'none' : '';
+      row.style.display = display;
+      var detail = row.nextElementSibling;
+      if (detail && detail.classList.contains('session-detail')) detail.style.display = display;
+    });
+  });
+})();
+</script>"#;
+
+/// Most sessions whose findings `--inline-sessions` embeds, so the report stays
+/// a manageable size on large corpora.
+const INLINE_SESSIONS_MAX: usize = 200;
+
+/// Finding styles for the aggregate's inline drill-down (the session report
+/// carries its own, fuller set).
+const INLINE_FINDINGS_CSS: &str = r#"<style>
+  tr.session-detail td{padding:0 1.25rem .5rem;background:var(--bg)}
+  tr.session-detail summary{cursor:pointer;padding:.4rem 0;font-size:.72rem;color:var(--text-2);font-family:var(--font-mono)}
+  .finding{padding:.6rem 0;border-bottom:1px solid var(--border);display:flex;flex-direction:column;gap:.3rem}
+  .finding:last-child{border-bottom:none}
+  .finding-top{display:flex;align-items:center;flex-wrap:wrap;gap:.5rem}
+  .finding-kind{padding:.1rem .45rem;border-radius:3px;font-family:var(--font-mono);font-size:.68rem;font-weight:700;background:rgba(248,113,113,.1);color:var(--danger)}
+  .finding-desc{font-size:.82rem;flex:1}
+  .waste-pill{padding:.1rem .5rem;border-radius:20px;font-family:var(--font-mono);font-size:.72rem;font-weight:700;background:rgba(245,158,11,.1);color:var(--warn);white-space:nowrap}
+  .finding-meta,.finding-evidence{font-size:.72rem;color:var(--text-3)}
+  .finding-evidence{font-family:var(--font-mono);padding-left:.5rem}
+  .finding-evidence::before{content:'· '}
+  .finding-fix{font-size:.78rem;color:var(--text-2)}
+  .finding-fix::before{content:'fix: ';color:var(--success);font-weight:600}
+</style>"#;
+
+/// Render the aggregate report. `interactive` adds an in-page search box that
+/// filters the sessions table; without it the output is static HTML only.
+/// `inline_sessions` embeds each session's findings in a collapsible row under
+/// it (for the first [`INLINE_SESSIONS_MAX`] sessions with findings). With a
+/// `sample`, the footer notes that totals cover the sample and estimates the rest.
+pub fn render_aggregate(
+    results: &[AnalysisResult],
+    interactive: bool,
+    inline_sessions: bool,
+    sample: Option<&crate::terminal::Sample>,
+) -> Result<String> {
     let total_cost: f64 = results
         .iter()
         .filter_map(|r| r.session.total_cost_usd)
         .sum();
     let total_msgs: usize = results.iter().map(|r| r.session.message_count).sum();
     let total_findings: usize = results.iter().map(|r| r.findings.len()).sum();
+    let empty_sessions = results.iter().filter(|r| r.session.is_empty()).count();
     let total_waste: f64 = results
         .iter()
         .flat_map(|r| r.findings.iter())
         .filter_map(|f| f.wasted_cost_usd)
         .sum();
+    let breakdown = crate::terminal::sum_breakdowns(results);
+    let pct = crate::terminal::waste_pct(total_waste, total_cost);
+    let avg_score = crate::terminal::avg_efficiency(results);
+    let waste_pct_display = pct
+        .map(|p| format!("{:.1}%", p))
+        .unwrap_or_else(|| "—".to_string());
+    let waste_pct_color = match pct {
+        Some(p) if p >= 15.0 => "var(--danger)",
+        Some(p) if p >= 5.0 => "var(--warn)",
+        Some(_) => "var(--success)",
+        None => "var(--text-2)",
+    };
 
+    let mut inlined = 0usize;
     let sessions_html = results
         .iter()
         .map(|r| {
             let s = &r.session;
             let session_waste: f64 = r.findings.iter().filter_map(|f| f.wasted_cost_usd).sum();
+            let detail = if inline_sessions && !r.findings.is_empty() && inlined < INLINE_SESSIONS_MAX
+            {
+                inlined += 1;
+                format!(
+                    r#"<tr class="session-detail"><td colspan="7"><details><summary>{} finding{}</summary>{}</details></td></tr>"#,
+                    r.findings.len(),
+                    if r.findings.len() == 1 { "" } else { "s" },
+                    render_findings(&r.findings, s.cwd.as_deref())
+                )
+            } else {
+                String::new()
+            };
             format!(
                 r#"<tr>
               <td>{}</td>
@@ -436,21 +629,141 @@ pub fn render_aggregate(results: &[AnalysisResult]) -> Result<String> {
               <td>{}</td>
               <td>{}</td>
               <td>{}</td>
-            </tr>"#,
+            </tr>{}"#,
                 s.source_agent,
                 truncate(&s.session_id, 36),
-                fmt_cost_html(s.total_cost_usd),
+                if s.is_empty() {
+                    "empty".to_string()
+                } else {
+                    format::fmt_cost(s.total_cost_usd, MISSING)
+                },
                 if session_waste > 0.0 {
-                    format!("~${:.2}", session_waste)
+                    format!("~{}", fmt_money(session_waste, 2))
                 } else {
                     "—".to_string()
                 },
                 html_escape(s.cwd.as_deref().unwrap_or("-")),
-                fmt_ts(s.started_at),
+                format::fmt_ts(s.started_at, MISSING),
                 s.message_count,
+                detail,
             )
         })
         .collect::<String>();
+    let with_findings = results.iter().filter(|r| !r.findings.is_empty()).count();
+    let inline_note = if inline_sessions && with_findings > inlined {
+        format!(
+            r#"<div style="padding:.5rem 1.25rem;font-size:.72rem;color:var(--text-3)">Findings shown inline for the first {} of {} sessions with findings.</div>"#,
+            inlined, with_findings
+        )
+    } else {
+        String::new()
+    };
+
+    let waste_kinds = crate::terminal::waste_by_kind(results);
+    let waste_html = if waste_kinds.is_empty() {
+        String::new()
+    } else {
+        let max = waste_kinds[0].1;
+        let rows = waste_kinds
+            .iter()
+            .map(|(kind, usd)| {
+                format!(
+                    r#"<div class="waste-row"><span class="waste-label">{}</span><div class="waste-track"><div class="waste-fill" style="width:{:.1}%"></div></div><span class="waste-value">~{}</span></div>"#,
+                    html_escape(&kind.to_string()),
+                    usd / max * 100.0,
+                    fmt_money(*usd, 2),
+                )
+            })
+            .collect::<String>();
+        format!(
+            r#"<div class="section">
+    <div class="section-header">Waste by Finding Kind</div>
+    <div class="waste-chart">{}</div>
+  </div>"#,
+            rows
+        )
+    };
+
+    let tags = crate::terminal::tag_summaries(results);
+    let tags_html = if tags.is_empty() {
+        String::new()
+    } else {
+        let rows = tags
+            .iter()
+            .map(|t| {
+                format!(
+                    r#"<tr><td class="mono">{}</td><td>{}</td><td class="success">{}</td><td class="danger">{}</td></tr>"#,
+                    html_escape(&t.name),
+                    t.sessions,
+                    fmt_money(t.total_cost_usd, 4),
+                    if t.total_waste_usd > 0.0 {
+                        format!("~{}", fmt_money(t.total_waste_usd, 2))
+                    } else {
+                        MISSING.to_string()
+                    },
+                )
+            })
+            .collect::<String>();
+        format!(
+            r#"<div class="section">
+    <div class="section-header">Cost by Tag</div>
+    <table>
+      <thead><tr><th>Tag</th><th>Sessions</th><th>Cost</th><th>Waste</th></tr></thead>
+      <tbody>{}</tbody>
+    </table>
+  </div>"#,
+            rows
+        )
+    };
+
+    let catalog = crate::terminal::catalog_date();
+    let cost_sources_html = crate::terminal::cost_sources(results)
+        .iter()
+        .map(|c| format!("<div>{}: {}</div>", c.agent, c.describe(catalog)))
+        .collect::<String>();
+    let sample_html = sample
+        .map(|s| {
+            let est = s.estimate(results);
+            format!(
+                "<div>Sampled {} of {} sessions (seed {}); totals cover the sample — estimated across all: ~{} cost, ~{} waste</div>",
+                s.sampled,
+                s.population,
+                s.seed,
+                fmt_money(est.total_cost_usd, 2),
+                fmt_money(est.total_waste_usd, 2)
+            )
+        })
+        .unwrap_or_default();
+
+    let clean = crate::terminal::clean_sessions(results);
+    let clean_html = if clean.is_empty() {
+        String::new()
+    } else {
+        let rows = clean
+            .iter()
+            .map(|r| {
+                let s = &r.session;
+                format!(
+                    r#"<tr><td>{}</td><td class="mono">{}</td><td class="success">{}</td><td>{}</td><td>{}</td></tr>"#,
+                    s.source_agent,
+                    truncate(&s.session_id, 36),
+                    format::fmt_cost(s.total_cost_usd, MISSING),
+                    html_escape(s.cwd.as_deref().unwrap_or("-")),
+                    s.message_count,
+                )
+            })
+            .collect::<String>();
+        format!(
+            r#"<div class="section">
+    <div class="section-header">Clean Sessions (no findings)</div>
+    <table>
+      <thead><tr><th>Agent</th><th>Session ID</th><th>Cost</th><th>CWD</th><th>Messages</th></tr></thead>
+      <tbody>{}</tbody>
+    </table>
+  </div>"#,
+            rows
+        )
+    };
 
     Ok(format!(
         r#"<!DOCTYPE html>
@@ -495,43 +808,146 @@ pub fn render_aggregate(results: &[AnalysisResult]) -> Result<String> {
   td.success{{color:var(--success);font-family:var(--font-mono)}}
   td.danger{{color:var(--danger);font-family:var(--font-mono)}}
   footer{{text-align:center;padding:2rem;color:var(--text-3);font-size:.72rem;font-family:var(--font-mono)}}
+  #session-filter{{float:right;margin-top:-.25rem;width:280px;padding:.3rem .6rem;background:var(--bg);border:1px solid var(--border-2);border-radius:var(--radius);color:var(--text);font-family:var(--font-mono);font-size:.75rem;text-transform:none;letter-spacing:0}}
 </style>
+{inline_css}
 </head>
 <body>
 <div class="header"><span class="header-logo">tracekit</span><span style="color:var(--border-2)">/</span><span style="color:var(--text-3);font-size:.8rem">aggregate report</span></div>
 <div class="container">
   <div class="kpi-grid">
-    <div class="kpi"><div class="kpi-label">Total Cost</div><div class="kpi-value" style="color:var(--success)">${total_cost:.4}</div></div>
-    <div class="kpi kpi-waste"><div class="kpi-label">Identified Waste</div><div class="kpi-value" style="color:var(--danger)">~${total_waste:.2}</div></div>
+    <div class="kpi"><div class="kpi-label">Total Cost</div><div class="kpi-value" style="color:var(--success)">{total_cost}</div></div>
+    <div class="kpi kpi-waste"><div class="kpi-label">Identified Waste</div><div class="kpi-value" style="color:var(--danger)">~{total_waste}</div></div>
+    <div class="kpi"><div class="kpi-label">Waste %</div><div class="kpi-value" style="color:{waste_pct_color}">{waste_pct_display}</div></div>
+    <div class="kpi"><div class="kpi-label">Avg Efficiency</div><div class="kpi-value" style="color:{avg_score_color}">{avg_score}</div></div>
+    <div class="kpi"><div class="kpi-label">Input-side Cost</div><div class="kpi-value">{input_side}</div></div>
+    <div class="kpi"><div class="kpi-label">Output-side Cost</div><div class="kpi-value">{output_side}</div></div>
     <div class="kpi"><div class="kpi-label">Sessions</div><div class="kpi-value" style="color:var(--info)">{session_count}</div></div>
     <div class="kpi"><div class="kpi-label">Messages</div><div class="kpi-value">{total_msgs}</div></div>
     <div class="kpi"><div class="kpi-label">Findings</div><div class="kpi-value" style="color:var(--warn)">{total_findings}</div></div>
+    <div class="kpi"><div class="kpi-label">Empty Sessions</div><div class="kpi-value" style="color:var(--text-2)">{empty_sessions}</div></div>
   </div>
   <div class="section">
-    <div class="section-header">Sessions</div>
-    <table>
+    <div class="section-header">Sessions{filter_input}</div>
+    <table id="sessions">
       <thead><tr>
         <th>Agent</th><th>Session ID</th><th>Cost</th><th>Waste</th>
         <th>CWD</th><th>Started</th><th>Messages</th>
       </tr></thead>
       <tbody>{sessions_html}</tbody>
     </table>
+    {inline_note}
   </div>
+  {waste_html}
+  {tags_html}
+  {clean_html}
 </div>
-<footer>tracekit · {timestamp}</footer>
+<footer>{sample_html}{cost_sources_html}<div>tracekit · {timestamp}</div></footer>
+{filter_script}
 </body>
 </html>"#,
-        total_cost = total_cost,
-        total_waste = total_waste,
+        filter_input = if interactive {
+            r#"<input id="session-filter" type="search" placeholder="Filter by agent, session ID or CWD" autocomplete="off">"#
+        } else {
+            ""
+        },
+        filter_script = if interactive {
+            SESSION_FILTER_SCRIPT
+        } else {
+            ""
+        },
+        total_cost = fmt_money(total_cost, 4),
+        total_waste = fmt_money(total_waste, 2),
+        waste_pct_display = waste_pct_display,
+        waste_pct_color = waste_pct_color,
+        avg_score = avg_score
+            .map(|a| format!("{:.0}/100", a))
+            .unwrap_or_else(|| MISSING.to_string()),
+        avg_score_color = avg_score
+            .map(|a| format!("var(--{})", score_class(a)))
+            .unwrap_or_else(|| "var(--text-2)".to_string()),
+        input_side = format::fmt_cost(breakdown.map(|b| b.input_usd), MISSING),
+        output_side = format::fmt_cost(breakdown.map(|b| b.output_usd), MISSING),
         session_count = results.len(),
         total_msgs = total_msgs,
         total_findings = total_findings,
+        empty_sessions = empty_sessions,
         sessions_html = sessions_html,
+        inline_note = inline_note,
+        inline_css = if inline_sessions {
+            INLINE_FINDINGS_CSS
+        } else {
+            ""
+        },
+        waste_html = waste_html,
+        tags_html = tags_html,
+        clean_html = clean_html,
         timestamp = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"),
     ))
 }
 
-fn render_findings(findings: &[Finding]) -> String {
+/// Stacked bar of input tokens split into fresh, cache-read and cache-write.
+fn render_token_mix(s: &CanonicalSession) -> String {
+    let total = s.total_input_tokens;
+    if total == 0 {
+        return String::new();
+    }
+    let cached = s.total_cache_read_tokens + s.total_cache_write_tokens;
+    let segments = [
+        ("fresh", "Fresh input", total.saturating_sub(cached)),
+        ("read", "Cache read", s.total_cache_read_tokens),
+        ("write", "Cache write", s.total_cache_write_tokens),
+    ];
+    let pct = |n: u64| n as f64 / total as f64 * 100.0;
+
+    let bar = segments
+        .iter()
+        .filter(|(_, _, n)| *n > 0)
+        .map(|(class, label, n)| {
+            format!(
+                r#"<div class="seg-{}" style="width:{:.2}%" title="{}: {}"></div>"#,
+                class,
+                pct(*n),
+                label,
+                fmt_tokens(*n)
+            )
+        })
+        .collect::<String>();
+    let legend = segments
+        .iter()
+        .map(|(class, label, n)| {
+            format!(
+                r#"<span><i class="seg-{}"></i>{} {} ({:.0}%)</span>"#,
+                class,
+                label,
+                fmt_tokens(*n),
+                pct(*n)
+            )
+        })
+        .collect::<String>();
+
+    format!(
+        r#"<div class="token-mix">
+    <div class="kpi-label">Input Token Mix</div>
+    <div class="token-bar">{}</div>
+    <div class="token-legend">{}</div>
+  </div>"#,
+        bar, legend
+    )
+}
+
+/// KPI colour class for an efficiency score.
+fn score_class(score: f64) -> &'static str {
+    if score >= 80.0 {
+        "success"
+    } else if score >= 50.0 {
+        "warn"
+    } else {
+        "danger"
+    }
+}
+
+fn render_findings(findings: &[Finding], cwd: Option<&str>) -> String {
     if findings.is_empty() {
         return r#"<div class="no-findings">No inefficiencies detected</div>"#.to_string();
     }
@@ -543,7 +959,12 @@ fn render_findings(findings: &[Finding]) -> String {
                 .evidence
                 .iter()
                 .take(5)
-                .map(|e| format!(r#"<div class="finding-evidence">{}</div>"#, html_escape(e)))
+                .map(|e| {
+                    format!(
+                        r#"<div class="finding-evidence">{}</div>"#,
+                        html_escape(&format::relative_paths(e, cwd))
+                    )
+                })
                 .collect::<String>();
 
             let waste_html = f
@@ -552,7 +973,7 @@ fn render_findings(findings: &[Finding]) -> String {
                 .map(|c| {
                     format!(
                         r#"<span class="waste-pill">~{} wasted</span>"#,
-                        fmt_cost_html(Some(c))
+                        format::fmt_cost(Some(c), MISSING)
                     )
                 })
                 .unwrap_or_default();
@@ -566,12 +987,14 @@ fn render_findings(findings: &[Finding]) -> String {
               </div>
               <div class="finding-meta">confidence {conf:.0}%</div>
               {evidence}
+              <div class="finding-fix">{fix}</div>
             </div>"#,
-                kind = f.kind,
-                desc = html_escape(&f.description),
+                kind = html_escape(&f.kind.to_string()),
+                desc = html_escape(&format::relative_paths(&f.description, cwd)),
                 waste = waste_html,
                 conf = f.confidence * 100.0,
                 evidence = evidence_html,
+                fix = html_escape(f.kind.suggestion()),
             )
         })
         .collect()
@@ -592,12 +1015,18 @@ fn render_expensive_messages(messages: &[ExpensiveMessage]) -> String {
               <td class="mono">{}</td>
               <td class="mono">{}</td>
               <td class="mono">{}</td>
+              <td class="{}">{}</td>
             </tr>"#,
                 m.sequence,
-                fmt_cost_html(Some(m.cost_usd)),
+                format::fmt_cost(Some(m.cost_usd), MISSING),
                 fmt_tokens(m.input_tokens),
                 fmt_tokens(m.output_tokens),
                 m.tool_count,
+                match m.finish_reason.as_deref() {
+                    Some(r) if is_truncation_reason(r) => "danger",
+                    _ => "mono",
+                },
+                html_escape(m.finish_reason.as_deref().unwrap_or("—")),
             )
         })
         .collect::<String>();
@@ -605,7 +1034,7 @@ fn render_expensive_messages(messages: &[ExpensiveMessage]) -> String {
     format!(
         r#"<table>
           <thead><tr>
-            <th>Turn</th><th>Cost</th><th>Billed Input</th><th>Output</th><th>Tools</th>
+            <th>Turn</th><th>Cost</th><th>Billed Input</th><th>Output</th><th>Tools</th><th>Finish</th>
           </tr></thead>
           <tbody>{}</tbody>
         </table>"#,
@@ -613,50 +1042,164 @@ fn render_expensive_messages(messages: &[ExpensiveMessage]) -> String {
     )
 }
 
-fn fmt_cost_html(cost: Option<f64>) -> String {
-    match cost {
-        Some(c) => format!("${:.4}", c),
-        None => "—".to_string(),
-    }
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
 }
 
-fn fmt_tokens(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}k", n as f64 / 1_000.0)
-    } else {
-        n.to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_mix_segments_match_token_proportions() {
+        let session: CanonicalSession = serde_json::from_value(serde_json::json!({
+            "session_id": "s",
+            "source_agent": "claude",
+            "source_path": "/tmp/s.jsonl",
+            "cwd": null,
+            "title": null,
+            "started_at": null,
+            "ended_at": null,
+            "model": null,
+            "message_count": 2,
+            "total_cost_usd": null,
+            "total_input_tokens": 1000,
+            "total_output_tokens": 50,
+            "total_cache_read_tokens": 600,
+            "total_cache_write_tokens": 150,
+        }))
+        .unwrap();
+
+        let mix = render_token_mix(&session);
+        assert!(
+            mix.contains(r#"class="seg-fresh" style="width:25.00%""#),
+            "{}",
+            mix
+        );
+        assert!(
+            mix.contains(r#"class="seg-read" style="width:60.00%""#),
+            "{}",
+            mix
+        );
+        assert!(
+            mix.contains(r#"class="seg-write" style="width:15.00%""#),
+            "{}",
+            mix
+        );
     }
-}
 
-fn fmt_duration(secs: Option<i64>) -> String {
-    match secs {
-        None => "—".to_string(),
-        Some(s) if s < 60 => format!("{}s", s),
-        Some(s) if s < 3600 => format!("{}m{}s", s / 60, s % 60),
-        Some(s) => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+    fn result(id: &str, findings: &[&str]) -> AnalysisResult {
+        let findings: Vec<serde_json::Value> = findings
+            .iter()
+            .map(|description| {
+                serde_json::json!({
+                    "kind": "retry_loop",
+                    "description": description,
+                    "evidence": [],
+                    "wasted_tokens": null,
+                    "wasted_cost_usd": 0.5,
+                    "confidence": 0.8,
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "session": {
+                "session_id": id,
+                "source_agent": "claude",
+                "source_path": format!("/tmp/{}.jsonl", id),
+                "cwd": null,
+                "title": null,
+                "started_at": null,
+                "ended_at": null,
+                "model": null,
+                "message_count": 4,
+                "total_cost_usd": 2.0,
+                "total_input_tokens": 0,
+                "total_output_tokens": 0,
+            },
+            "findings": findings,
+            "top_expensive_messages": [],
+        }))
+        .unwrap()
     }
-}
 
-fn fmt_ts(ts: Option<chrono::DateTime<chrono::Utc>>) -> String {
-    match ts {
-        Some(t) => t.format("%Y-%m-%d %H:%M").to_string(),
-        None => "—".to_string(),
+    #[test]
+    fn inline_sessions_put_findings_in_their_own_details_row() {
+        let results = [
+            result("session-alpha", &["alpha retried the build"]),
+            result("session-clean", &[]),
+            result("session-beta", &["beta reread the config"]),
+        ];
+        let html = render_aggregate(&results, false, true, None).unwrap();
+
+        // Each session's details block sits between its row and the next session's
+        let block = |id: &str| {
+            let cell = format!(r#"<td class="mono">{}</td>"#, id);
+            let start = html.find(&cell).unwrap();
+            let end = html[start + cell.len()..]
+                .find(r#"<td class="mono">"#)
+                .map_or(html.len(), |i| start + cell.len() + i);
+            html[start..end].to_string()
+        };
+        let alpha = block("session-alpha");
+        assert!(alpha.contains("<details><summary>1 finding</summary>"));
+        assert!(alpha.contains("alpha retried the build"));
+        assert!(!alpha.contains("beta reread the config"));
+        assert!(!block("session-clean").contains("<details>"));
+        assert!(block("session-beta").contains("beta reread the config"));
+
+        let flat = render_aggregate(&results, false, false, None).unwrap();
+        assert!(!flat.contains("alpha retried the build"));
     }
-}
 
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-}
+    #[test]
+    fn waste_bars_scale_with_waste_per_kind() {
+        let with_waste = |id, wastes: &[(FindingKind, f64)]| {
+            let mut r = result(id, &vec!["finding"; wastes.len()]);
+            for (f, (kind, usd)) in r.findings.iter_mut().zip(wastes) {
+                f.kind = kind.clone();
+                f.wasted_cost_usd = Some(*usd);
+            }
+            r
+        };
+        let results = [
+            with_waste(
+                "a",
+                &[
+                    (FindingKind::RetryLoop, 3.0),
+                    (FindingKind::EditCascade, 1.0),
+                ],
+            ),
+            with_waste(
+                "b",
+                &[
+                    (FindingKind::ContextBloat, 2.0),
+                    (FindingKind::RetryLoop, 1.0),
+                ],
+            ),
+        ];
+        let html = render_aggregate(&results, false, false, None).unwrap();
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}…", &s[..max.saturating_sub(1)])
+        // Largest kind fills the track; the rest are proportional, largest first
+        let bars: Vec<(&str, &str)> = html
+            .split(r#"<span class="waste-label">"#)
+            .skip(1)
+            .map(|row| {
+                let label = &row[..row.find('<').unwrap()];
+                let width = row.split("width:").nth(1).unwrap();
+                (label, &width[..width.find('"').unwrap()])
+            })
+            .collect();
+        assert_eq!(
+            bars,
+            [
+                ("RETRY_LOOP", "100.0%"),
+                ("CONTEXT_BLOAT", "50.0%"),
+                ("EDIT_CASCADE", "25.0%"),
+            ]
+        );
     }
 }
@@ -1,15 +1,107 @@
 use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeMap;
 use tracekit_core::*;
 
 pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
     Ok(serde_json::to_string_pretty(result)?)
 }
 
+/// JSON Lines export: one `AnalysisResult` per line — the object
+/// `report session --format json` prints, compacted — so analyses can be
+/// archived and re-rendered later with `report from-jsonl`.
+pub fn render_jsonl(results: &[AnalysisResult]) -> Result<String> {
+    let mut out = String::new();
+    for r in results {
+        out.push_str(&serde_json::to_string(r)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Read results written by [`render_jsonl`]. Blank lines are skipped.
+pub fn parse_jsonl(text: &str) -> Result<Vec<AnalysisResult>> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| anyhow::anyhow!("line {}: not an analysis result: {}", i + 1, e))
+        })
+        .collect()
+}
+
+pub fn render_session(session: &CanonicalSession) -> Result<String> {
+    Ok(serde_json::to_string_pretty(session)?)
+}
+
 pub fn render_session_list(sessions: &[CanonicalSession]) -> Result<String> {
     Ok(serde_json::to_string_pretty(sessions)?)
 }
 
-pub fn render_aggregate(results: &[AnalysisResult]) -> Result<String> {
+/// Session list wrapped with totals, for `list sessions --format json --summary`.
+#[derive(Debug, Serialize)]
+pub struct SessionListSummary<'a> {
+    pub count: usize,
+    /// Sum over sessions with a known cost
+    pub total_cost_usd: f64,
+    pub agents: BTreeMap<String, AgentTotals>,
+    /// Earliest and latest start time; absent when no session has one
+    pub date_range: Option<DateRange>,
+    pub sessions: &'a [CanonicalSession],
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct AgentTotals {
+    pub count: usize,
+    pub total_cost_usd: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DateRange {
+    pub from: chrono::DateTime<chrono::Utc>,
+    pub to: chrono::DateTime<chrono::Utc>,
+}
+
+impl<'a> SessionListSummary<'a> {
+    pub fn new(sessions: &'a [CanonicalSession]) -> Self {
+        let mut agents: BTreeMap<String, AgentTotals> = BTreeMap::new();
+        for s in sessions {
+            let totals = agents.entry(s.source_agent.to_string()).or_default();
+            totals.count += 1;
+            totals.total_cost_usd += s.total_cost_usd.unwrap_or(0.0);
+        }
+        let starts = sessions.iter().filter_map(|s| s.started_at);
+        let date_range = starts
+            .clone()
+            .min()
+            .zip(starts.max())
+            .map(|(from, to)| DateRange { from, to });
+        Self {
+            count: sessions.len(),
+            total_cost_usd: sessions
+                .iter()
+                .filter_map(|s| s.total_cost_usd)
+                .fold(0.0, |acc, c| acc + c),
+            agents,
+            date_range,
+            sessions,
+        }
+    }
+}
+
+pub fn render_session_list_summary(sessions: &[CanonicalSession]) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&SessionListSummary::new(
+        sessions,
+    ))?)
+}
+
+/// Render the aggregate report. With a `sample`, totals cover the sampled
+/// sessions and a `sample` object adds population estimates.
+pub fn render_aggregate(
+    results: &[AnalysisResult],
+    sample: Option<&crate::terminal::Sample>,
+) -> Result<String> {
     let total_cost: f64 = results
         .iter()
         .filter_map(|r| r.session.total_cost_usd)
@@ -23,13 +115,172 @@ pub fn render_aggregate(results: &[AnalysisResult]) -> Result<String> {
         }
     }
 
-    let summary = serde_json::json!({
+    let empty_sessions = results.iter().filter(|r| r.session.is_empty()).count();
+    let total_waste: f64 = results
+        .iter()
+        .flat_map(|r| r.findings.iter())
+        .filter_map(|f| f.wasted_cost_usd)
+        .sum();
+
+    let clean_sessions: Vec<serde_json::Value> = crate::terminal::clean_sessions(results)
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "session_id": r.session.session_id,
+                "agent": r.session.source_agent,
+                "total_cost_usd": r.session.total_cost_usd,
+            })
+        })
+        .collect();
+
+    // Tag groups keep their `tag` key for existing consumers
+    let by_tag: Vec<serde_json::Value> = crate::terminal::tag_summaries(results)
+        .into_iter()
+        .map(|t| {
+            serde_json::json!({
+                "tag": t.name,
+                "sessions": t.sessions,
+                "total_cost_usd": t.total_cost_usd,
+                "total_waste_usd": t.total_waste_usd,
+            })
+        })
+        .collect();
+
+    let mut summary = serde_json::json!({
         "sessions_analyzed": results.len(),
+        "empty_sessions": empty_sessions,
         "total_cost_usd": total_cost,
+        "total_waste_usd": total_waste,
+        "waste_pct": crate::terminal::waste_pct(total_waste, total_cost),
+        "avg_efficiency_score": crate::terminal::avg_efficiency(results),
+        "cost_breakdown": crate::terminal::sum_breakdowns(results),
         "total_messages": results.iter().map(|r| r.session.message_count).sum::<usize>(),
         "finding_counts": finding_counts,
+        "clean_sessions": clean_sessions,
+        "by_tag": by_tag,
+        "pricing_catalog_date": crate::terminal::catalog_date().to_string(),
+        "cost_sources": crate::terminal::cost_sources(results),
         "sessions": results,
     });
+    if let Some(s) = sample {
+        summary["sample"] = serde_json::json!({
+            "sampled": s.sampled,
+            "population": s.population,
+            "seed": s.seed,
+            "scale": s.scale(),
+            "estimated": s.estimate(results),
+        });
+    }
 
     Ok(serde_json::to_string_pretty(&summary)?)
 }
+
+/// Two windows' aggregate stats and the change between them, for
+/// `report compare --format json`. Deltas are `after - before`.
+pub fn render_comparison(
+    before: &crate::terminal::AggregateStats,
+    after: &crate::terminal::AggregateStats,
+) -> Result<String> {
+    let delta = |b: Option<f64>, a: Option<f64>| b.zip(a).map(|(b, a)| a - b);
+    let out = serde_json::json!({
+        "before": before,
+        "after": after,
+        "delta": {
+            "sessions": after.sessions as i64 - before.sessions as i64,
+            "avg_cost_usd": delta(before.avg_cost_usd, after.avg_cost_usd),
+            "findings_per_session": delta(before.findings_per_session, after.findings_per_session),
+            "cache_hit_rate": delta(before.cache_hit_rate, after.cache_hit_rate),
+            "waste_pct": delta(before.waste_pct, after.waste_pct),
+            "avg_efficiency_score": delta(before.avg_efficiency_score, after.avg_efficiency_score),
+        },
+    });
+    Ok(serde_json::to_string_pretty(&out)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, cost: f64, wastes: &[f64]) -> AnalysisResult {
+        let findings: Vec<serde_json::Value> = wastes
+            .iter()
+            .map(|w| {
+                serde_json::json!({
+                    "kind": "retry_loop",
+                    "description": "retried",
+                    "evidence": [],
+                    "wasted_tokens": null,
+                    "wasted_cost_usd": w,
+                    "confidence": 0.8,
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "session": {
+                "session_id": id,
+                "source_agent": "claude",
+                "source_path": format!("/tmp/{}.jsonl", id),
+                "cwd": null,
+                "title": null,
+                "started_at": null,
+                "ended_at": null,
+                "model": null,
+                "message_count": 4,
+                "total_cost_usd": cost,
+                "total_input_tokens": 0,
+                "total_output_tokens": 0,
+            },
+            "findings": findings,
+            "top_expensive_messages": [],
+        }))
+        .unwrap()
+    }
+
+    fn aggregate(results: &[AnalysisResult]) -> serde_json::Value {
+        serde_json::from_str(&render_aggregate(results, None).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn aggregate_reports_waste_as_share_of_cost() {
+        let out = aggregate(&[result("a", 3.0, &[0.5, 0.25]), result("b", 1.0, &[0.25])]);
+        assert_eq!(out["total_waste_usd"], 1.0);
+        assert_eq!(out["waste_pct"], 25.0);
+
+        let free = aggregate(&[result("a", 0.0, &[])]);
+        assert!(free["waste_pct"].is_null());
+    }
+
+    #[test]
+    fn clean_sessions_exclude_flagged_and_empty_sessions() {
+        let mut empty = result("empty", 0.0, &[]);
+        empty.session.message_count = 0;
+        let out = aggregate(&[
+            result("cheap", 1.0, &[]),
+            result("flagged", 5.0, &[0.5]),
+            empty,
+            result("pricey", 3.0, &[]),
+        ]);
+        let ids: Vec<&str> = out["clean_sessions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["session_id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["pricey", "cheap"]);
+    }
+
+    #[test]
+    fn jsonl_export_reimports_and_renders_the_same_aggregate() {
+        let results = [result("a", 3.0, &[0.5]), result("b", 1.0, &[])];
+        let text = render_jsonl(&results).unwrap();
+        assert_eq!(text.lines().count(), 2);
+
+        let back = parse_jsonl(&format!("{}\n", text)).unwrap();
+        assert_eq!(back.len(), 2);
+        assert_eq!(back[1].session.session_id, "b");
+        assert_eq!(aggregate(&back), aggregate(&results));
+
+        let err = parse_jsonl(&format!("{}{{}}\n", text)).unwrap_err();
+        assert!(err.to_string().starts_with("line 3:"), "{}", err);
+    }
+}
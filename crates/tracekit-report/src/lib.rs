@@ -1,5 +1,11 @@
+pub mod currency;
+pub mod format;
 pub mod html;
 pub mod json;
+pub mod sarif;
 pub mod terminal;
+pub mod toml;
+pub mod tools;
 
+pub use currency::{display_currency, set_display_currency, Currency};
 pub use terminal::*;
@@ -0,0 +1,111 @@
+/// SARIF 2.1.0 output, so findings can be uploaded to GitHub code scanning or
+/// any other SARIF viewer. Each finding is a result whose rule is its kind; the
+/// session's trace file is the result's location.
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::path::Path;
+use tracekit_core::*;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// SARIF level for a finding. Findings are inefficiencies, not defects, so the
+/// most confident ones are warnings and the rest notes.
+fn level(finding: &Finding) -> &'static str {
+    if finding.confidence >= 0.7 {
+        "warning"
+    } else {
+        "note"
+    }
+}
+
+/// `file://` URI for an absolute trace path; relative (e.g. redacted) paths are
+/// kept as relative references.
+fn artifact_uri(path: &Path) -> String {
+    let p = path
+        .to_string_lossy()
+        .replace('\\', "/")
+        .replace(' ', "%20");
+    if p.starts_with('/') {
+        format!("file://{}", p)
+    } else if path.is_absolute() {
+        format!("file:///{}", p)
+    } else {
+        p
+    }
+}
+
+fn rule(kind: &FindingKind) -> Value {
+    let info = FINDING_CATALOG.iter().find(|i| &i.kind == kind);
+    let description = info
+        .map(|i| i.description.to_string())
+        .unwrap_or_else(|| kind.to_string());
+    let mut rule = json!({
+        "id": kind.as_str(),
+        "name": kind.to_string(),
+        "shortDescription": { "text": description },
+        "help": { "text": kind.suggestion() },
+    });
+    if let Some(i) = info {
+        rule["properties"] = json!({
+            "threshold": i.threshold,
+            "default_confidence": i.default_confidence,
+        });
+    }
+    rule
+}
+
+pub fn render_sarif(results: &[AnalysisResult]) -> Result<String> {
+    // One rule per kind that occurs, in order of first appearance
+    let mut kinds: Vec<&FindingKind> = Vec::new();
+    for f in results.iter().flat_map(|r| &r.findings) {
+        if !kinds.contains(&&f.kind) {
+            kinds.push(&f.kind);
+        }
+    }
+
+    let sarif_results: Vec<Value> = results
+        .iter()
+        .flat_map(|r| r.findings.iter().map(move |f| (&r.session, f)))
+        .map(|(session, f)| {
+            let rule_index = kinds.iter().position(|k| *k == &f.kind);
+            json!({
+                "ruleId": f.kind.as_str(),
+                "ruleIndex": rule_index,
+                "level": level(f),
+                "message": { "text": f.description },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": artifact_uri(&session.source_path),
+                        },
+                    },
+                }],
+                "properties": {
+                    "session_id": session.session_id,
+                    "agent": session.source_agent.to_string(),
+                    "evidence": f.evidence,
+                    "evidence_refs": f.evidence_refs,
+                    "confidence": f.confidence,
+                    "wasted_tokens": f.wasted_tokens,
+                    "wasted_cost_usd": f.wasted_cost_usd,
+                },
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "tracekit",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": kinds.iter().map(|k| rule(k)).collect::<Vec<_>>(),
+                },
+            },
+            "results": sarif_results,
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&log)?)
+}
@@ -1,49 +1,722 @@
-use colored::Colorize;
+use crate::currency::fmt_money;
+use crate::format::{self, truncate};
+use colored::{ColoredString, Colorize};
 use tracekit_core::*;
 
 // ── formatting helpers ────────────────────────────────────────────────────────
 
+pub use crate::format::fmt_tokens;
+
 pub fn fmt_cost(cost: Option<f64>) -> String {
-    match cost {
-        Some(c) => format!("${:.4}", c),
-        None => "-".to_string(),
-    }
+    format::fmt_cost(cost, "-")
+}
+
+pub fn fmt_duration(secs: Option<i64>) -> String {
+    format::fmt_duration(secs, "-")
 }
 
-pub fn fmt_tokens(n: u64) -> String {
-    if n >= 1_000_000 {
-        format!("{:.1}M", n as f64 / 1_000_000.0)
-    } else if n >= 1_000 {
-        format!("{:.1}k", n as f64 / 1_000.0)
+/// Identified waste as a percentage of total cost; `None` when there is no cost.
+pub fn waste_pct(waste: f64, cost: f64) -> Option<f64> {
+    if cost > 0.0 {
+        Some(waste / cost * 100.0)
     } else {
-        n.to_string()
+        None
     }
 }
 
-pub fn fmt_duration(secs: Option<i64>) -> String {
-    match secs {
-        None => "-".to_string(),
-        Some(s) if s < 60 => format!("{}s", s),
-        Some(s) if s < 3600 => format!("{}m{}s", s / 60, s % 60),
-        Some(s) => format!("{}h{}m", s / 3600, (s % 3600) / 60),
+/// Mean efficiency score over sessions that parsed any messages.
+pub fn avg_efficiency(results: &[AnalysisResult]) -> Option<f64> {
+    let scores: Vec<f64> = results
+        .iter()
+        .filter(|r| !r.session.is_empty())
+        .map(|r| session_score(r) as f64)
+        .collect();
+    (!scores.is_empty()).then(|| scores.iter().sum::<f64>() / scores.len() as f64)
+}
+
+/// Sessions ordered least efficient first.
+pub fn sort_by_efficiency(results: &mut [AnalysisResult]) {
+    results.sort_by_key(session_score);
+}
+
+fn score_display(score: u8) -> ColoredString {
+    score_colored(score, format!("{}/100", score))
+}
+
+/// `text` coloured by how good `score` is.
+fn score_colored(score: u8, text: String) -> ColoredString {
+    match score {
+        80.. => text.green(),
+        50..=79 => text.yellow(),
+        _ => text.red(),
     }
 }
 
-pub fn fmt_ts(ts: Option<chrono::DateTime<chrono::Utc>>) -> String {
-    match ts {
-        Some(t) => t.format("%Y-%m-%d %H:%M").to_string(),
-        None => "-".to_string(),
+/// Cost per 1,000 messages, so sessions of different lengths compare by
+/// per-interaction cost; `None` without messages.
+pub fn cost_per_1k_messages(cost: f64, messages: usize) -> Option<f64> {
+    (messages > 0).then(|| cost / messages as f64 * 1000.0)
+}
+
+/// Non-empty sessions with no findings, most expensive first.
+pub fn clean_sessions(results: &[AnalysisResult]) -> Vec<&AnalysisResult> {
+    let mut clean: Vec<&AnalysisResult> = results
+        .iter()
+        .filter(|r| r.findings.is_empty() && !r.session.is_empty())
+        .collect();
+    clean.sort_by(|a, b| {
+        b.session
+            .total_cost_usd
+            .unwrap_or(0.0)
+            .partial_cmp(&a.session.total_cost_usd.unwrap_or(0.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    clean
+}
+
+/// Sum per-session cost breakdowns; `None` if no session had one.
+pub fn sum_breakdowns(results: &[AnalysisResult]) -> Option<CostBreakdown> {
+    results
+        .iter()
+        .filter_map(|r| r.session.cost_breakdown)
+        .fold(None, |acc: Option<CostBreakdown>, b| {
+            let mut total = acc.unwrap_or_default();
+            total += b;
+            Some(total)
+        })
+}
+
+/// Sessions, cost and identified waste for one group (a tag, a model family)
+/// across an aggregate.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GroupSummary {
+    pub name: String,
+    pub sessions: usize,
+    pub total_cost_usd: f64,
+    pub total_waste_usd: f64,
+}
+
+/// Group results by the keys `keys_of` returns for each, most expensive first.
+/// A session counts toward each of its keys; sessions with none are left out.
+pub fn group_summaries<F>(results: &[AnalysisResult], keys_of: F) -> Vec<GroupSummary>
+where
+    F: Fn(&AnalysisResult) -> Vec<String>,
+{
+    let mut by_key: std::collections::BTreeMap<String, GroupSummary> = Default::default();
+    for r in results {
+        let waste: f64 = r.findings.iter().filter_map(|f| f.wasted_cost_usd).sum();
+        for key in keys_of(r) {
+            let entry = by_key.entry(key.clone()).or_insert_with(|| GroupSummary {
+                name: key,
+                sessions: 0,
+                total_cost_usd: 0.0,
+                total_waste_usd: 0.0,
+            });
+            entry.sessions += 1;
+            entry.total_cost_usd += r.session.total_cost_usd.unwrap_or(0.0);
+            entry.total_waste_usd += waste;
+        }
     }
+    let mut summaries: Vec<GroupSummary> = by_key.into_values().collect();
+    summaries.sort_by(|a, b| {
+        b.total_cost_usd
+            .partial_cmp(&a.total_cost_usd)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    summaries
 }
 
-fn truncate(s: &str, max: usize) -> String {
-    if s.len() <= max {
-        s.to_string()
-    } else {
-        format!("{}…", &s[..max.saturating_sub(1)])
+/// Group results by tag. A session counts toward each of its tags.
+pub fn tag_summaries(results: &[AnalysisResult]) -> Vec<GroupSummary> {
+    group_summaries(results, |r| r.session.tags.clone())
+}
+
+/// Group results by the family of each session's model (see
+/// [`classify_model`]); unrecognized or missing models group as "unknown".
+pub fn model_family_summaries(results: &[AnalysisResult]) -> Vec<GroupSummary> {
+    group_summaries(results, |r| {
+        let family = r.session.model.as_deref().and_then(classify_model);
+        vec![family.unwrap_or("unknown").to_string()]
+    })
+}
+
+/// A seeded random subset of sessions standing in for all that matched
+/// (`--sample`); report totals cover the subset and are scaled up as estimates.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct Sample {
+    pub sampled: usize,
+    pub population: usize,
+    pub seed: u64,
+}
+
+impl Sample {
+    /// Factor taking a total over the sample to an estimate over the population.
+    pub fn scale(&self) -> f64 {
+        if self.sampled == 0 {
+            return 0.0;
+        }
+        self.population as f64 / self.sampled as f64
+    }
+
+    /// Cost, waste and findings over the population, estimated from `results`.
+    pub fn estimate(&self, results: &[AnalysisResult]) -> SampleEstimate {
+        let cost: f64 = results
+            .iter()
+            .filter_map(|r| r.session.total_cost_usd)
+            .sum();
+        let waste: f64 = results
+            .iter()
+            .flat_map(|r| r.findings.iter())
+            .filter_map(|f| f.wasted_cost_usd)
+            .sum();
+        let findings: usize = results.iter().map(|r| r.findings.len()).sum();
+        SampleEstimate {
+            total_cost_usd: cost * self.scale(),
+            total_waste_usd: waste * self.scale(),
+            total_findings: (findings as f64 * self.scale()).round() as usize,
+        }
     }
 }
 
+/// Population totals extrapolated from a [`Sample`].
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct SampleEstimate {
+    pub total_cost_usd: f64,
+    pub total_waste_usd: f64,
+    pub total_findings: usize,
+}
+
+pub fn print_sample_estimate(results: &[AnalysisResult], sample: &Sample) {
+    let est = sample.estimate(results);
+    println!(
+        "{}",
+        "── Sampled Estimate ────────────────────────────────────────────".bold()
+    );
+    println!(
+        "  Sampled           : {} of {} sessions (seed {})",
+        sample.sampled, sample.population, sample.seed
+    );
+    println!(
+        "  Est. total cost   : {}",
+        format!("~{}", fmt_money(est.total_cost_usd, 2))
+            .green()
+            .bold()
+    );
+    if est.total_waste_usd > 0.0 {
+        println!(
+            "  Est. total waste  : {}",
+            format!("~{}", fmt_money(est.total_waste_usd, 2)).red()
+        );
+    }
+    println!("  Est. findings     : ~{}", est.total_findings);
+    println!(
+        "  {} figures above cover the sample only; these scale them by {:.1}x",
+        "!".yellow(),
+        sample.scale()
+    );
+    println!();
+}
+
+/// Where one agent's share of an aggregate's cost came from: reported by the
+/// agent itself, or estimated from the price catalog.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CostSource {
+    pub agent: Agent,
+    pub sessions: usize,
+    pub observed_usd: f64,
+    pub estimated_usd: f64,
+}
+
+impl CostSource {
+    /// One-line note on how the agent's costs were arrived at, naming the
+    /// catalog month for any estimated part.
+    pub fn describe(&self, catalog: PricingDate) -> String {
+        match (self.observed_usd > 0.0, self.estimated_usd > 0.0) {
+            (true, false) => "observed (reported by the agent)".to_string(),
+            (false, true) => format!("estimated from catalog prices as of {}", catalog),
+            (true, true) => format!(
+                "mixed: {} observed, {} estimated from catalog prices as of {}",
+                fmt_money(self.observed_usd, 2),
+                fmt_money(self.estimated_usd, 2),
+                catalog
+            ),
+            (false, false) => "no cost data".to_string(),
+        }
+    }
+}
+
+/// Month the price catalog was read as of: `--pricing-date` if set, else the
+/// current catalog's [`CATALOG_AS_OF`].
+pub fn catalog_date() -> PricingDate {
+    pricing_date().unwrap_or(CATALOG_AS_OF)
+}
+
+/// Observed vs estimated cost per agent, in agent order, so blended totals
+/// can say which parts rest on catalog prices.
+pub fn cost_sources(results: &[AnalysisResult]) -> Vec<CostSource> {
+    let mut by_agent: Vec<CostSource> = Vec::new();
+    for r in results.iter().filter(|r| !r.session.is_empty()) {
+        let s = &r.session;
+        let observed = s.observed_cost_usd.unwrap_or(0.0);
+        let estimated = (s.total_cost_usd.unwrap_or(0.0) - observed).max(0.0);
+        let idx = match by_agent.iter().position(|c| c.agent == s.source_agent) {
+            Some(i) => i,
+            None => {
+                by_agent.push(CostSource {
+                    agent: s.source_agent,
+                    sessions: 0,
+                    observed_usd: 0.0,
+                    estimated_usd: 0.0,
+                });
+                by_agent.len() - 1
+            }
+        };
+        let entry = &mut by_agent[idx];
+        entry.sessions += 1;
+        entry.observed_usd += observed;
+        entry.estimated_usd += estimated;
+    }
+    by_agent.sort_by_key(|c| c.agent.to_string());
+    by_agent
+}
+
+/// Identified waste per finding kind across all results, largest first. Kinds
+/// whose findings carry no cost estimate are left out.
+pub fn waste_by_kind(results: &[AnalysisResult]) -> Vec<(FindingKind, f64)> {
+    let mut totals: Vec<(FindingKind, f64)> = Vec::new();
+    for f in results.iter().flat_map(|r| &r.findings) {
+        let Some(cost) = f.wasted_cost_usd else {
+            continue;
+        };
+        match totals.iter_mut().find(|(k, _)| *k == f.kind) {
+            Some((_, total)) => *total += cost,
+            None => totals.push((f.kind.clone(), cost)),
+        }
+    }
+    totals.retain(|(_, total)| *total > 0.0);
+    totals.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    totals
+}
+
+/// Period for the aggregate cost timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeBucket {
+    Day,
+    Week,
+}
+
+/// Most periods the cost timeline shows (the most recent ones).
+const MAX_TIMELINE_BUCKETS: usize = 60;
+
+/// Total cost per day or ISO week, oldest first. Periods with no sessions between
+/// the first and last are filled in as zero; sessions without a start time are skipped.
+pub fn cost_buckets(results: &[AnalysisResult], bucket: TimeBucket) -> Vec<(String, f64)> {
+    use chrono::{Datelike, Duration, NaiveDate};
+
+    let start_of = |d: NaiveDate| match bucket {
+        TimeBucket::Day => d,
+        TimeBucket::Week => d - Duration::days(d.weekday().num_days_from_monday() as i64),
+    };
+    let mut by_period: std::collections::BTreeMap<NaiveDate, f64> = Default::default();
+    for r in results {
+        if let Some(t) = r.session.started_at {
+            *by_period.entry(start_of(t.date_naive())).or_default() +=
+                r.session.total_cost_usd.unwrap_or(0.0);
+        }
+    }
+    let (Some(&first), Some(&last)) = (by_period.keys().next(), by_period.keys().next_back())
+    else {
+        return Vec::new();
+    };
+
+    let step = match bucket {
+        TimeBucket::Day => Duration::days(1),
+        TimeBucket::Week => Duration::days(7),
+    };
+    let mut buckets = Vec::new();
+    let mut d = first;
+    while d <= last {
+        let label = match bucket {
+            TimeBucket::Day => d.format("%Y-%m-%d").to_string(),
+            TimeBucket::Week => d.format("%G-W%V").to_string(),
+        };
+        buckets.push((label, by_period.get(&d).copied().unwrap_or(0.0)));
+        d += step;
+    }
+    buckets
+}
+
+/// Horizontal bar chart, one line per bucket: label, a bar scaled so the largest
+/// value spans `width` columns, and the value in the display currency.
+pub fn render_bar_chart(buckets: &[(String, f64)], width: usize) -> String {
+    let label_w = buckets.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+    let max = buckets.iter().map(|(_, v)| *v).fold(0.0f64, f64::max);
+    let mut out = String::new();
+    for (label, value) in buckets {
+        let mut n = if max > 0.0 {
+            ((value / max) * width as f64).round() as usize
+        } else {
+            0
+        };
+        // Keep any spend visible, however small next to the peak
+        if *value > 0.0 && n == 0 {
+            n = 1;
+        }
+        out.push_str(&format!(
+            "  {:<label_w$}  {}{}  {}\n",
+            label,
+            "█".repeat(n).green(),
+            " ".repeat(width - n),
+            fmt_money(*value, 2),
+        ));
+    }
+    out
+}
+
+/// Print up to `limit` groups under `heading`: name, cost, session count and waste.
+fn print_group_summaries(heading: &str, groups: &[GroupSummary], limit: usize) {
+    println!("{}", heading.bold());
+    for g in groups.iter().take(limit) {
+        let waste = if g.total_waste_usd > 0.0 {
+            format!("~{} waste", fmt_money(g.total_waste_usd, 2))
+        } else {
+            String::new()
+        };
+        println!(
+            "  {:<30}  {:>10}  {:>4} sessions  {}",
+            truncate(&g.name, 30).cyan(),
+            fmt_cost(Some(g.total_cost_usd)).yellow(),
+            g.sessions,
+            waste.red()
+        );
+    }
+}
+
+/// Print cost per 1k messages overall and for the sessions that cost the most
+/// per message, with their absolute cost alongside.
+pub fn print_normalized_costs(results: &[AnalysisResult]) {
+    let mut rows: Vec<(&AnalysisResult, f64)> = results
+        .iter()
+        .filter_map(|r| {
+            let s = &r.session;
+            cost_per_1k_messages(s.total_cost_usd?, s.message_count).map(|n| (r, n))
+        })
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+    rows.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let total_cost: f64 = rows
+        .iter()
+        .filter_map(|(r, _)| r.session.total_cost_usd)
+        .sum();
+    let total_msgs: usize = rows.iter().map(|(r, _)| r.session.message_count).sum();
+    println!(
+        "{}",
+        "── Cost per 1k Messages ────────────────────────────────────────".bold()
+    );
+    println!(
+        "  Overall           : {}",
+        fmt_cost(cost_per_1k_messages(total_cost, total_msgs))
+            .green()
+            .bold()
+    );
+    for (i, (r, per_1k)) in rows.iter().take(10).enumerate() {
+        let s = &r.session;
+        println!(
+            "  {}. {:>10}  {}  {:>5} msgs  {:>8}  {}",
+            i + 1,
+            fmt_cost(Some(*per_1k)).yellow(),
+            format!("({} total)", fmt_cost(s.total_cost_usd)).dimmed(),
+            s.message_count,
+            s.source_agent.to_string().cyan(),
+            truncate(&s.session_id, 36),
+        );
+    }
+}
+
+/// Print the ten least efficient sessions, for `report aggregate --sort efficiency`.
+pub fn print_least_efficient(results: &[AnalysisResult]) {
+    let mut rows: Vec<(&AnalysisResult, u8)> = results
+        .iter()
+        .filter(|r| !r.session.is_empty())
+        .map(|r| (r, session_score(r)))
+        .collect();
+    if rows.is_empty() {
+        return;
+    }
+    rows.sort_by_key(|(_, score)| *score);
+
+    println!(
+        "{}",
+        "── Least Efficient Sessions ────────────────────────────────────".bold()
+    );
+    for (i, (r, score)) in rows.iter().take(10).enumerate() {
+        let s = &r.session;
+        println!(
+            "  {}. {:>7}  {:>10}  {:>8}  {}  {}",
+            i + 1,
+            score_display(*score),
+            fmt_cost(s.total_cost_usd).yellow(),
+            s.source_agent.to_string().cyan(),
+            truncate(&s.session_id, 36),
+            truncate(s.cwd.as_deref().unwrap_or("-"), 40).dimmed(),
+        );
+    }
+}
+
+/// Headline numbers for a set of analysed sessions, for comparing two windows
+/// with `report compare`. Averages are `None` when nothing contributes to them.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct AggregateStats {
+    pub sessions: usize,
+    pub total_cost_usd: f64,
+    pub avg_cost_usd: Option<f64>,
+    pub findings_per_session: Option<f64>,
+    /// Share of input tokens read from the prompt cache, 0–1
+    pub cache_hit_rate: Option<f64>,
+    pub waste_pct: Option<f64>,
+    pub avg_efficiency_score: Option<f64>,
+}
+
+impl AggregateStats {
+    /// Empty sessions are left out, as in the aggregate summary.
+    pub fn from_results(results: &[AnalysisResult]) -> Self {
+        let counted: Vec<&AnalysisResult> =
+            results.iter().filter(|r| !r.session.is_empty()).collect();
+        let n = counted.len();
+        let total_cost = counted
+            .iter()
+            .filter_map(|r| r.session.total_cost_usd)
+            .fold(0.0, |acc, c| acc + c);
+        let total_waste = counted
+            .iter()
+            .flat_map(|r| r.findings.iter())
+            .filter_map(|f| f.wasted_cost_usd)
+            .fold(0.0, |acc, w| acc + w);
+        let findings: usize = counted.iter().map(|r| r.findings.len()).sum();
+        let input: u64 = counted.iter().map(|r| r.session.total_input_tokens).sum();
+        let cache_read: u64 = counted
+            .iter()
+            .map(|r| r.session.total_cache_read_tokens)
+            .sum();
+        Self {
+            sessions: n,
+            total_cost_usd: total_cost,
+            avg_cost_usd: (n > 0).then(|| total_cost / n as f64),
+            findings_per_session: (n > 0).then(|| findings as f64 / n as f64),
+            cache_hit_rate: (input > 0).then(|| cache_read as f64 / input as f64),
+            waste_pct: waste_pct(total_waste, total_cost),
+            avg_efficiency_score: avg_efficiency(results),
+        }
+    }
+}
+
+/// Print two windows' stats side by side with the change from `before` to `after`.
+pub fn print_comparison(
+    before_label: &str,
+    before: &AggregateStats,
+    after_label: &str,
+    after: &AggregateStats,
+) {
+    println!(
+        "\n{}",
+        "── Before / After ──────────────────────────────────────────────".bold()
+    );
+    println!("  Before : {}", before_label);
+    println!("  After  : {}", after_label);
+    println!();
+    println!(
+        "  {:<22} {:>14} {:>14} {:>12}",
+        "",
+        "before".bold(),
+        "after".bold(),
+        "change".bold()
+    );
+
+    // `lower_is_better` decides whether a drop is coloured green or red
+    let row = |label: &str,
+               b: Option<f64>,
+               a: Option<f64>,
+               fmt: &dyn Fn(f64) -> String,
+               lower_is_better: bool| {
+        let cell = |v: Option<f64>| v.map(fmt).unwrap_or_else(|| "-".to_string());
+        let delta = match (b, a) {
+            (Some(b), Some(a)) => {
+                let d = a - b;
+                let text = format!("{}{}", if d >= 0.0 { "+" } else { "-" }, fmt(d.abs()));
+                if d.abs() < 1e-9 {
+                    text.normal()
+                } else if (d < 0.0) == lower_is_better {
+                    text.green()
+                } else {
+                    text.red()
+                }
+            }
+            _ => "-".normal(),
+        };
+        println!(
+            "  {:<22} {:>14} {:>14} {:>12}",
+            label,
+            cell(b),
+            cell(a),
+            delta
+        );
+    };
+
+    let count = |v: f64| format!("{:.0}", v);
+    let money = |v: f64| fmt_cost(Some(v));
+    let ratio = |v: f64| format!("{:.2}", v);
+    let pct = |v: f64| format!("{:.1}%", v);
+    row(
+        "Sessions",
+        Some(before.sessions as f64),
+        Some(after.sessions as f64),
+        &count,
+        false,
+    );
+    row(
+        "Avg cost / session",
+        before.avg_cost_usd,
+        after.avg_cost_usd,
+        &money,
+        true,
+    );
+    row(
+        "Findings / session",
+        before.findings_per_session,
+        after.findings_per_session,
+        &ratio,
+        true,
+    );
+    row(
+        "Cache hit rate",
+        before.cache_hit_rate.map(|r| r * 100.0),
+        after.cache_hit_rate.map(|r| r * 100.0),
+        &pct,
+        false,
+    );
+    row("Waste", before.waste_pct, after.waste_pct, &pct, true);
+    row(
+        "Avg efficiency",
+        before.avg_efficiency_score,
+        after.avg_efficiency_score,
+        &count,
+        false,
+    );
+}
+
+/// Print how tracekit's cost estimates compare with observed costs, per model,
+/// marking models whose mean relative error exceeds `threshold_pct`.
+pub fn print_pricing_checks(checks: &[PricingCheck], threshold_pct: f64) {
+    println!(
+        "\n{}",
+        "── Pricing Validation ──────────────────────────────────────────".bold()
+    );
+    if checks.is_empty() {
+        println!(
+            "  {}",
+            "No turns with both an observed cost and a priced model.".dimmed()
+        );
+        return;
+    }
+    println!(
+        "  {:<28} {:>6} {:>11} {:>11} {:>9}",
+        "MODEL", "TURNS", "OBSERVED", "ESTIMATED", "MEAN ERR"
+    );
+    for c in checks {
+        let err = format!("{:.1}%", c.mean_relative_error * 100.0);
+        println!(
+            "  {:<28} {:>6} {:>11} {:>11} {:>9}",
+            truncate(&c.model, 28),
+            c.turns,
+            fmt_cost(Some(c.observed_usd)),
+            fmt_cost(Some(c.estimated_usd)),
+            if c.diverges(threshold_pct) {
+                err.red().bold()
+            } else {
+                err.green()
+            }
+        );
+    }
+    let diverging = checks.iter().filter(|c| c.diverges(threshold_pct)).count();
+    if diverging > 0 {
+        println!(
+            "\n  {} {} model(s) diverge from observed cost by more than {}%; their catalog prices may be stale",
+            "!".yellow(),
+            diverging,
+            threshold_pct
+        );
+    }
+}
+
+/// Print the aggregate's cost split by model family.
+pub fn print_model_families(results: &[AnalysisResult]) {
+    print_group_summaries(
+        "── Cost by Model Family ────────────────────────────────────────",
+        &model_family_summaries(results),
+        usize::MAX,
+    );
+}
+
+/// Print every tag's cost; the aggregate summary only shows the top ten.
+pub fn print_tags(results: &[AnalysisResult]) {
+    let tags = tag_summaries(results);
+    if tags.is_empty() {
+        println!("{}", "No tagged sessions.".yellow());
+        return;
+    }
+    print_group_summaries(
+        "── Cost by Tag (all) ───────────────────────────────────────────",
+        &tags,
+        usize::MAX,
+    );
+}
+
+/// Print the aggregate's cost per day or week as a bar chart sized to the
+/// terminal (`$COLUMNS`, default 80).
+pub fn print_cost_timeline(results: &[AnalysisResult], bucket: TimeBucket) {
+    let mut buckets = cost_buckets(results, bucket);
+    if buckets.is_empty() {
+        return;
+    }
+    println!(
+        "{}",
+        match bucket {
+            TimeBucket::Day => "── Cost by Day ─────────────────────────────────────────────────",
+            TimeBucket::Week => "── Cost by Week ────────────────────────────────────────────────",
+        }
+        .bold()
+    );
+    let skipped = buckets.len().saturating_sub(MAX_TIMELINE_BUCKETS);
+    if skipped > 0 {
+        buckets.drain(..skipped);
+        println!(
+            "  {}",
+            format!(
+                "(most recent {}; {} earlier omitted)",
+                MAX_TIMELINE_BUCKETS, skipped
+            )
+            .dimmed()
+        );
+    }
+
+    let columns = std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .unwrap_or(80);
+    let label_w = buckets.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+    // Room for the indent, label, gaps and a value like `$1234.56`
+    let width = columns.saturating_sub(label_w + 16).clamp(10, 60);
+    print!("{}", render_bar_chart(&buckets, width));
+    println!();
+}
+
+pub fn fmt_ts(ts: Option<chrono::DateTime<chrono::Utc>>) -> String {
+    format::fmt_ts(ts, "-")
+}
+
 // ── session list ──────────────────────────────────────────────────────────────
 
 pub fn print_session_list(sessions: &[CanonicalSession]) {
@@ -54,15 +727,61 @@ pub fn print_session_list(sessions: &[CanonicalSession]) {
 
     let col_widths = (8, 38, 32, 17, 5, 10);
     let (w_agent, w_id, w_cwd, w_ts, w_msgs, w_cost) = col_widths;
+    // Only OpenCode records titles (or --title-from-prompt), so the column is opt-in
+    let w_title = if sessions.iter().any(|s| s.title.is_some()) {
+        40
+    } else {
+        0
+    };
+    // Skipped malformed lines, shown only when some trace had them
+    let w_errors = if sessions.iter().any(|s| s.parse_errors > 0) {
+        6
+    } else {
+        0
+    };
+    // Efficiency scores, present only after `--sort efficiency` analyzed the sessions
+    let w_score = if sessions.iter().any(|s| s.efficiency_score.is_some()) {
+        5
+    } else {
+        0
+    };
+    // Interrupted/aborted sessions, likewise
+    let w_ended = if sessions.iter().any(|s| s.ended_abnormally) {
+        7
+    } else {
+        0
+    };
 
     println!(
-        "{:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {:>w4$}  {:>w5$}",
+        "{:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {:>w4$}  {:>w5$}{}{}{}{}",
         "AGENT".bold(),
         "SESSION ID".bold(),
         "CWD".bold(),
         "STARTED".bold(),
         "MSGS".bold(),
         "COST".bold(),
+        if w_score > 0 {
+            format!("  {}", "SCORE".bold())
+        } else {
+            String::new()
+        },
+        if w_errors > 0 {
+            format!("  {}", "ERRORS".bold())
+        } else {
+            String::new()
+        },
+        if w_ended > 0 && w_title > 0 {
+            format!("  {}", format!("{:<w$}", "ENDED", w = w_ended).bold())
+        } else if w_ended > 0 {
+            format!("  {}", "ENDED".bold())
+        } else {
+            String::new()
+        },
+        if w_title > 0 {
+            format!("  {}", "TITLE".bold())
+        } else {
+            String::new()
+        },
         w0 = w_agent,
         w1 = w_id,
         w2 = w_cwd,
@@ -70,23 +789,21 @@ pub fn print_session_list(sessions: &[CanonicalSession]) {
         w4 = w_msgs,
         w5 = w_cost,
     );
+    let extra_cols: usize = [w_score, w_errors, w_ended, w_title]
+        .iter()
+        .filter(|w| **w > 0)
+        .map(|w| w + 2)
+        .sum();
     println!(
         "{}",
-        "─".repeat(w_agent + w_id + w_cwd + w_ts + w_msgs + w_cost + 10)
+        "─".repeat(w_agent + w_id + w_cwd + w_ts + w_msgs + w_cost + 10 + extra_cols)
     );
 
     for s in sessions {
         let cwd_display = s
             .cwd
             .as_deref()
-            .map(|c| {
-                let home = std::env::var("HOME").unwrap_or_default();
-                if !home.is_empty() && c.starts_with(&home) {
-                    format!("~{}", &c[home.len()..])
-                } else {
-                    c.to_string()
-                }
-            })
+            .map(collapse_home)
             .unwrap_or_else(|| "-".to_string());
 
         let agent_colored = match s.source_agent {
@@ -97,14 +814,48 @@ pub fn print_session_list(sessions: &[CanonicalSession]) {
             Agent::Kodo => s.source_agent.to_string().blue().to_string(),
         };
 
+        let cost_display = if s.is_empty() {
+            "empty".to_string()
+        } else {
+            fmt_cost(s.total_cost_usd)
+        };
+
+        let score_display = match (w_score, s.efficiency_score) {
+            (0, _) => String::new(),
+            (w, None) => format!("  {}", format!("{:>w$}", "-", w = w).dimmed()),
+            (w, Some(n)) => format!("  {}", score_colored(n, format!("{:>w$}", n, w = w))),
+        };
+
+        let errors_display = match (w_errors, s.parse_errors) {
+            (0, _) => String::new(),
+            (w, 0) => format!("  {}", format!("{:>w$}", "-", w = w).dimmed()),
+            (w, n) => format!("  {}", format!("{:>w$}", n, w = w).red()),
+        };
+
+        let ended_display = match (w_ended, s.ended_abnormally) {
+            (0, _) => String::new(),
+            (w, false) => format!("  {}", format!("{:<w$}", "-", w = w).dimmed()),
+            (w, true) => format!("  {}", format!("{:<w$}", "aborted", w = w).yellow()),
+        };
+
+        let title_display = match (w_title, s.title.as_deref()) {
+            (0, _) => String::new(),
+            (w, Some(t)) => format!("  {}", truncate(t, w)),
+            (_, None) => format!("  {}", "-".dimmed()),
+        };
+
         println!(
-            "{:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {:>w4$}  {:>w5$}",
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:<w3$}  {:>w4$}  {:>w5$}{}{}{}{}",
             agent_colored,
             truncate(&s.session_id, w_id),
             truncate(&cwd_display, w_cwd),
             fmt_ts(s.started_at),
             s.message_count,
-            fmt_cost(s.total_cost_usd),
+            cost_display,
+            score_display,
+            errors_display,
+            ended_display,
+            title_display,
             w0 = w_agent,
             w1 = w_id,
             w2 = w_cwd,
@@ -125,7 +876,11 @@ pub fn print_analysis(result: &AnalysisResult) {
         "\n{}",
         "── Session ─────────────────────────────────────────────────────".bold()
     );
-    println!("  Agent      : {}", s.source_agent.to_string().cyan());
+    println!(
+        "  Agent      : {} {}",
+        s.source_agent.to_string().cyan(),
+        format!("[{}]", result.session_kind).dimmed()
+    );
     println!("  Session ID : {}", s.session_id);
     println!("  Path       : {}", s.source_path.display());
     if let Some(cwd) = &s.cwd {
@@ -134,15 +889,104 @@ pub fn print_analysis(result: &AnalysisResult) {
     if let Some(model) = &s.model {
         println!("  Model      : {}", model);
     }
+    if !s.tags.is_empty() {
+        println!("  Tags       : {}", s.tags.join(", "));
+    }
     println!("  Started    : {}", fmt_ts(s.started_at));
     println!("  Duration   : {}", fmt_duration(s.duration_secs()));
-    println!("  Messages   : {}", s.message_count);
-    println!("  Input tok  : {}", fmt_tokens(s.total_input_tokens));
+    println!(
+        "  Messages   : {} {}",
+        s.message_count,
+        format!(
+            "(user {} / assistant {} / system {})",
+            s.user_count, s.assistant_count, s.system_count
+        )
+        .dimmed()
+    );
+    if let Some(size) = &s.system_prompt {
+        println!("  Sys prompt : {}", format::fmt_system_prompt(size));
+    }
+    if !s.is_empty() {
+        println!("  Efficiency : {}", score_display(session_score(result)));
+    }
+    if s.is_empty() {
+        println!(
+            "  Status     : {}",
+            "empty — no messages parsed (file empty or unparseable)".yellow()
+        );
+    }
+    if s.ended_abnormally {
+        println!(
+            "  Status     : {}",
+            "ended abnormally — interrupted or aborted before the agent finished".yellow()
+        );
+    }
+    if s.parse_errors > 0 {
+        println!(
+            "  {}",
+            format!(
+                "! {} malformed line{} skipped while parsing — totals may be incomplete",
+                s.parse_errors,
+                if s.parse_errors == 1 { "" } else { "s" }
+            )
+            .yellow()
+        );
+    }
+    let cached = s.total_cache_read_tokens + s.total_cache_write_tokens;
+    if cached > 0 {
+        println!(
+            "  Input tok  : {} {}",
+            fmt_tokens(s.total_input_tokens),
+            format!(
+                "(of which cache read {} / cache write {})",
+                fmt_tokens(s.total_cache_read_tokens),
+                fmt_tokens(s.total_cache_write_tokens)
+            )
+            .dimmed()
+        );
+    } else {
+        println!("  Input tok  : {}", fmt_tokens(s.total_input_tokens));
+    }
     println!("  Output tok : {}", fmt_tokens(s.total_output_tokens));
+    if s.total_reasoning_tokens > 0 {
+        println!("  Reasoning  : {}", fmt_tokens(s.total_reasoning_tokens));
+    }
     println!(
         "  Total cost : {}",
-        fmt_cost(s.total_cost_usd).green().bold().to_string()
+        fmt_cost(s.total_cost_usd).green().bold()
     );
+    if let Some(conf) = s
+        .pricing_confidence
+        .filter(|c| *c != PricingConfidence::Exact)
+    {
+        println!(
+            "  Pricing    : {}",
+            format!("{} — a model isn't in the price catalog", conf.describe()).yellow()
+        );
+    }
+    if let Some(b) = s.cost_breakdown {
+        println!(
+            "  Cost split : input-side {} / output-side {}",
+            fmt_cost(Some(b.input_usd)),
+            fmt_cost(Some(b.output_usd))
+        );
+    }
+    if s.heuristic_turns > 0 {
+        println!(
+            "  Estimated  : {}",
+            format!(
+                "{} turns without usage estimated from text length (heuristic)",
+                s.heuristic_turns
+            )
+            .dimmed()
+        );
+    }
+    if let Some(aux) = s.auxiliary_cost_usd {
+        println!(
+            "  Aux cost   : {} (embeddings/summarization)",
+            fmt_cost(Some(aux))
+        );
+    }
 
     let total_waste: f64 = result
         .findings
@@ -152,7 +996,7 @@ pub fn print_analysis(result: &AnalysisResult) {
     if total_waste > 0.0 {
         println!(
             "  Identified waste : {}",
-            format!("~${:.2}", total_waste).red().bold().to_string()
+            format!("~{}", fmt_money(total_waste, 2)).red().bold()
         );
     }
 
@@ -163,20 +1007,28 @@ pub fn print_analysis(result: &AnalysisResult) {
             "── Top Expensive Generations ───────────────────────────────────".bold()
         );
         for (i, m) in result.top_expensive_messages.iter().enumerate() {
+            let finish = match m.finish_reason.as_deref() {
+                Some(r) if is_truncation_reason(r) => format!("  finish:{}", r).red().to_string(),
+                Some(r) => format!("  finish:{}", r).dimmed().to_string(),
+                None => String::new(),
+            };
             println!(
-                "  {}. turn {:>4}  {:>10}  in:{:>8}  out:{:>7}  tools:{}",
+                "  {}. turn {:>4}  {:>10}  in:{:>8}  out:{:>7}  tools:{}{}",
                 i + 1,
                 m.sequence,
                 fmt_cost(Some(m.cost_usd)).yellow(),
                 fmt_tokens(m.input_tokens),
                 fmt_tokens(m.output_tokens),
                 m.tool_count,
+                finish,
             );
         }
     }
 
     // Findings
-    if result.findings.is_empty() {
+    if s.is_empty() {
+        println!("\n{}", "Nothing to analyze.".yellow());
+    } else if result.findings.is_empty() {
         println!("\n{}", "No inefficiency findings.".green());
     } else {
         println!(
@@ -193,16 +1045,43 @@ pub fn print_analysis(result: &AnalysisResult) {
                 _ => String::new(),
             };
             println!("\n  {}. {} {}{}", i + 1, kind_str, conf, waste);
-            println!("     {}", f.description);
+            let cwd = s.cwd.as_deref();
+            println!("     {}", format::relative_paths(&f.description, cwd));
             for ev in f.evidence.iter().take(3) {
-                println!("       · {}", ev.dimmed());
+                println!("       · {}", format::relative_paths(ev, cwd).dimmed());
             }
+            println!("     {} {}", "fix:".green(), f.kind.suggestion());
         }
     }
 
     println!();
 }
 
+/// Print each detector's signal vs threshold (`analyze session --explain`).
+pub fn print_detector_traces(traces: &[DetectorTrace]) {
+    if traces.is_empty() {
+        return;
+    }
+    println!(
+        "{}",
+        "── Detector Diagnostics ────────────────────────────────────────".bold()
+    );
+    for t in traces {
+        let status = if t.fired > 0 {
+            format!("fired {}", t.fired).red().bold().to_string()
+        } else {
+            "quiet".dimmed().to_string()
+        };
+        println!(
+            "  {:<22} {:<10} {}",
+            t.detector,
+            status,
+            t.detail.as_deref().unwrap_or("no diagnostics")
+        );
+    }
+    println!();
+}
+
 // ── aggregate summary ─────────────────────────────────────────────────────────
 
 pub fn print_aggregate(results: &[AnalysisResult]) {
@@ -221,20 +1100,72 @@ pub fn print_aggregate(results: &[AnalysisResult]) {
         .sum();
     let total_msgs: usize = results.iter().map(|r| r.session.message_count).sum();
     let total_findings: usize = results.iter().map(|r| r.findings.len()).sum();
+    let empty_sessions = results.iter().filter(|r| r.session.is_empty()).count();
 
     println!("  Sessions analyzed : {}", results.len());
+    if empty_sessions > 0 {
+        println!(
+            "  Empty sessions    : {}",
+            format!("{} (no messages parsed; excluded below)", empty_sessions).yellow()
+        );
+    }
     println!("  Total messages    : {}", total_msgs);
     println!(
         "  Total cost        : {}",
-        fmt_cost(Some(total_cost)).green().bold().to_string()
+        fmt_cost(Some(total_cost)).green().bold()
     );
+    if let Some(b) = sum_breakdowns(results) {
+        println!("  Input-side cost   : {}", fmt_cost(Some(b.input_usd)));
+        println!("  Output-side cost  : {}", fmt_cost(Some(b.output_usd)));
+    }
+    let guessed = results
+        .iter()
+        .filter(|r| {
+            r.session
+                .pricing_confidence
+                .is_some_and(|c| c != PricingConfidence::Exact)
+        })
+        .count();
+    if guessed > 0 {
+        println!(
+            "  Guessed pricing   : {}",
+            format!(
+                "{} sessions priced from a family or generic default",
+                guessed
+            )
+            .yellow()
+        );
+    }
     println!("  Total findings    : {}", total_findings);
 
+    let total_waste: f64 = results
+        .iter()
+        .flat_map(|r| r.findings.iter())
+        .filter_map(|f| f.wasted_cost_usd)
+        .sum();
+    if total_waste > 0.0 {
+        println!(
+            "  Identified waste  : {}",
+            format!("~{}", fmt_money(total_waste, 2)).red().bold()
+        );
+    }
+    let pct_display = match waste_pct(total_waste, total_cost) {
+        Some(p) if p >= 15.0 => format!("{:.1}%", p).red().bold(),
+        Some(p) if p >= 5.0 => format!("{:.1}%", p).yellow(),
+        Some(p) => format!("{:.1}%", p).green(),
+        None => "-".normal(),
+    };
+    println!("  Waste %           : {}", pct_display);
+    if let Some(avg) = avg_efficiency(results) {
+        println!("  Avg efficiency    : {}", score_display(avg.round() as u8));
+    }
+
     println!(
         "\n{}",
         "── Top Sessions by Cost ────────────────────────────────────────".bold()
     );
-    let mut sorted: Vec<&AnalysisResult> = results.iter().collect();
+    let mut sorted: Vec<&AnalysisResult> =
+        results.iter().filter(|r| !r.session.is_empty()).collect();
     sorted.sort_by(|a, b| {
         b.session
             .total_cost_usd
@@ -247,10 +1178,11 @@ pub fn print_aggregate(results: &[AnalysisResult]) {
         let s = &r.session;
         let cwd_display = s.cwd.as_deref().unwrap_or("-");
         println!(
-            "  {}. {:>10}  {:>8}  {}  {}",
+            "  {}. {:>10}  {:>8}  {:>7}  {}  {}",
             i + 1,
             fmt_cost(s.total_cost_usd).yellow(),
             s.source_agent.to_string().cyan(),
+            score_display(session_score(r)),
             truncate(&s.session_id, 36),
             truncate(cwd_display, 40).dimmed(),
         );
@@ -270,12 +1202,57 @@ pub fn print_aggregate(results: &[AnalysisResult]) {
             "── Most Common Inefficiencies ──────────────────────────────────".bold()
         );
         let mut counts: Vec<(String, usize)> = finding_counts.into_iter().collect();
-        counts.sort_by(|a, b| b.1.cmp(&a.1));
+        counts.sort_by_key(|c| std::cmp::Reverse(c.1));
         for (kind, count) in counts.iter().take(7) {
             println!("  {:<30}  {}", kind.red(), count);
         }
     }
 
+    let tags = tag_summaries(results);
+    if !tags.is_empty() {
+        println!();
+        print_group_summaries(
+            "── Cost by Tag ─────────────────────────────────────────────────",
+            &tags,
+            10,
+        );
+    }
+
+    let clean = clean_sessions(results);
+    if !clean.is_empty() {
+        println!(
+            "\n{}",
+            "── Clean Sessions (no findings) ────────────────────────────────".bold()
+        );
+        for (i, r) in clean.iter().take(10).enumerate() {
+            let s = &r.session;
+            println!(
+                "  {}. {:>10}  {:>8}  {}  {}",
+                i + 1,
+                fmt_cost(s.total_cost_usd).green(),
+                s.source_agent.to_string().cyan(),
+                truncate(&s.session_id, 36),
+                truncate(s.cwd.as_deref().unwrap_or("-"), 40).dimmed(),
+            );
+        }
+    }
+
+    let sources = cost_sources(results);
+    if !sources.is_empty() {
+        println!(
+            "\n{}",
+            "── Cost Sources ────────────────────────────────────────────────".bold()
+        );
+        let catalog = catalog_date();
+        for c in &sources {
+            println!(
+                "  {:<10}  {}",
+                c.agent.to_string().cyan(),
+                c.describe(catalog).dimmed()
+            );
+        }
+    }
+
     println!();
 }
 
@@ -304,3 +1281,68 @@ pub fn print_expensive_sessions(results: &[AnalysisResult], top_n: usize) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, model: Option<&str>, cost: f64) -> AnalysisResult {
+        serde_json::from_value(serde_json::json!({
+            "session": {
+                "session_id": id,
+                "source_agent": "claude",
+                "source_path": format!("/tmp/{}.jsonl", id),
+                "cwd": null,
+                "title": null,
+                "started_at": null,
+                "ended_at": null,
+                "model": model,
+                "message_count": 4,
+                "total_cost_usd": cost,
+                "total_input_tokens": 0,
+                "total_output_tokens": 0,
+            },
+            "findings": [],
+            "top_expensive_messages": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn model_families_group_across_version_strings() {
+        let results = [
+            result("a", Some("claude-sonnet-4-20250514"), 2.0),
+            result("b", Some("claude-3-5-sonnet-20241022"), 1.0),
+            result("c", Some("gpt-4o-2024-08-06"), 4.0),
+            result("d", Some("mystery-model"), 0.5),
+            result("e", None, 0.25),
+        ];
+
+        let summaries = model_family_summaries(&results);
+        let groups: Vec<(&str, usize, f64)> = summaries
+            .iter()
+            .map(|g| (g.name.as_str(), g.sessions, g.total_cost_usd))
+            .collect();
+        assert_eq!(
+            groups,
+            [("gpt-4o", 1, 4.0), ("sonnet", 2, 3.0), ("unknown", 2, 0.75)]
+        );
+    }
+
+    #[test]
+    fn cost_per_1k_messages_compares_sessions_of_different_lengths() {
+        let mut short = result("short", Some("claude-sonnet-4"), 0.5);
+        short.session.message_count = 10;
+        let mut long = result("long", Some("claude-sonnet-4"), 20.0);
+        long.session.message_count = 2_000;
+
+        let per_1k = |r: &AnalysisResult| {
+            cost_per_1k_messages(r.session.total_cost_usd.unwrap(), r.session.message_count)
+                .unwrap()
+        };
+        // The long session costs 40x more but is 5x cheaper per interaction
+        assert!((per_1k(&short) - 50.0).abs() < 1e-9);
+        assert!((per_1k(&long) - 10.0).abs() < 1e-9);
+        assert_eq!(cost_per_1k_messages(3.0, 0), None);
+    }
+}
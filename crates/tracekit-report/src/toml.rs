@@ -0,0 +1,76 @@
+use anyhow::Result;
+use tracekit_core::*;
+
+pub fn render_analysis(result: &AnalysisResult) -> Result<String> {
+    Ok(::toml::to_string_pretty(result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn analysis_round_trips_through_toml() {
+        let result: AnalysisResult = serde_json::from_value(json!({
+            "session": {
+                "session_id": "s1",
+                "source_agent": "codex",
+                "source_path": "/tmp/s1.jsonl",
+                "cwd": "/work/proj",
+                "title": null,
+                "started_at": "2026-01-01T00:00:00Z",
+                "ended_at": null,
+                "model": "gpt-5",
+                "message_count": 4,
+                "total_cost_usd": 1.5,
+                "total_input_tokens": 1200,
+                "total_output_tokens": 300,
+                "tags": ["branch:main"],
+            },
+            "session_kind": "agentic",
+            "findings": [
+                {
+                    "kind": "retry_loop",
+                    "description": "retried",
+                    "evidence": ["turn 2", "turn 3"],
+                    "evidence_refs": [
+                        {"sequence": 2, "tool_name": "Bash", "message_id": "m2"},
+                        {"sequence": 3, "message_id": "m3"},
+                    ],
+                    "wasted_tokens": null,
+                    "wasted_cost_usd": 0.25,
+                    "confidence": 0.8,
+                },
+                {
+                    "kind": "context_bloat",
+                    "description": "large context",
+                    "evidence": [],
+                    "wasted_tokens": 900,
+                    "wasted_cost_usd": null,
+                    "confidence": 0.5,
+                },
+            ],
+            "top_expensive_messages": [{
+                "message_id": "m2",
+                "sequence": 2,
+                "role": "assistant",
+                "model": null,
+                "cost_usd": 0.75,
+                "input_tokens": 600,
+                "output_tokens": 150,
+                "tool_count": 1,
+            }],
+        }))
+        .unwrap();
+
+        let text = render_analysis(&result).unwrap();
+        let back: AnalysisResult = ::toml::from_str(&text).unwrap();
+        assert_eq!(
+            serde_json::to_value(&back).unwrap(),
+            serde_json::to_value(&result).unwrap()
+        );
+        assert_eq!(back.findings[0].evidence_refs[1].tool_name, None);
+        assert_eq!(back.findings[1].wasted_tokens, Some(900));
+    }
+}
@@ -0,0 +1,121 @@
+/// Flat export of every tool call, one row per call, for fleet-wide tool
+/// analytics (which tools fail most, which take longest). Rows are written as
+/// each session is parsed, so large histories never sit in memory at once.
+use anyhow::Result;
+use serde::Serialize;
+use std::io::Write;
+use tracekit_core::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolExportFormat {
+    /// A JSON array with one object per line
+    Json,
+    Csv,
+}
+
+const CSV_HEADER: &str = "session_id,agent,turn,tool_name,status,duration_ms,args_summary";
+
+#[derive(Debug, Serialize)]
+pub struct ToolCallRow<'a> {
+    pub session_id: &'a str,
+    pub agent: Agent,
+    /// Sequence of the message that made the call
+    pub turn: usize,
+    pub tool_name: &'a str,
+    pub status: ToolStatus,
+    pub duration_ms: Option<u64>,
+    pub args_summary: Option<&'a str>,
+}
+
+/// Every tool call in a parsed session, in turn order.
+pub fn tool_rows(parsed: &ParsedSession) -> impl Iterator<Item = ToolCallRow<'_>> {
+    parsed.messages.iter().flat_map(move |m| {
+        m.tool_calls.iter().map(move |t| ToolCallRow {
+            session_id: &parsed.session.session_id,
+            agent: parsed.session.source_agent,
+            turn: m.sequence,
+            tool_name: &t.tool_name,
+            status: t.status,
+            duration_ms: t.duration_ms,
+            args_summary: t.args_summary.as_deref(),
+        })
+    })
+}
+
+/// Streams tool call rows to `out`. Call [`ToolCallWriter::finish`] to close
+/// the JSON array (and flush) once every session has been written.
+pub struct ToolCallWriter<W: Write> {
+    out: W,
+    format: ToolExportFormat,
+    rows: usize,
+}
+
+impl<W: Write> ToolCallWriter<W> {
+    pub fn new(mut out: W, format: ToolExportFormat) -> Result<Self> {
+        match format {
+            ToolExportFormat::Json => write!(out, "[")?,
+            ToolExportFormat::Csv => writeln!(out, "{}", CSV_HEADER)?,
+        }
+        Ok(Self {
+            out,
+            format,
+            rows: 0,
+        })
+    }
+
+    pub fn write_session(&mut self, parsed: &ParsedSession) -> Result<()> {
+        for row in tool_rows(parsed) {
+            match self.format {
+                ToolExportFormat::Json => {
+                    let sep = if self.rows == 0 { "" } else { "," };
+                    write!(self.out, "{}\n  {}", sep, serde_json::to_string(&row)?)?;
+                }
+                ToolExportFormat::Csv => writeln!(self.out, "{}", csv_line(&row))?,
+            }
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// Finish the output and return the number of rows written.
+    pub fn finish(mut self) -> Result<usize> {
+        if self.format == ToolExportFormat::Json {
+            if self.rows > 0 {
+                writeln!(self.out)?;
+            }
+            writeln!(self.out, "]")?;
+        }
+        self.out.flush()?;
+        Ok(self.rows)
+    }
+}
+
+fn status_str(status: ToolStatus) -> &'static str {
+    match status {
+        ToolStatus::Success => "success",
+        ToolStatus::Error => "error",
+        ToolStatus::Unknown => "unknown",
+    }
+}
+
+fn csv_line(row: &ToolCallRow) -> String {
+    [
+        csv_field(row.session_id),
+        row.agent.to_string(),
+        row.turn.to_string(),
+        csv_field(row.tool_name),
+        status_str(row.status).to_string(),
+        row.duration_ms.map(|d| d.to_string()).unwrap_or_default(),
+        csv_field(row.args_summary.unwrap_or("")),
+    ]
+    .join(",")
+}
+
+/// Quote a field when it holds a comma, quote or line break (RFC 4180).
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}